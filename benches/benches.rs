@@ -1,5 +1,7 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 use std::mem;
+use std::sync::mpsc;
+use std::thread;
 use std::time::{Duration, Instant};
 
 criterion_group!(benches, bench);
@@ -48,12 +50,12 @@ macro_rules! bench_send_and_recv {
             group.finish();
         }
         {
-            let mut group = $c.benchmark_group("create_send_and_recv_ref");
+            let mut group = $c.benchmark_group("create_send_and_recv_mut");
             $(group.bench_function(stringify!($type), |b| {
                 b.iter(|| {
-                    let (sender, receiver) = oneshot::channel();
+                    let (sender, mut receiver) = oneshot::channel();
                     sender.send(black_box($value)).unwrap();
-                    receiver.recv_ref().unwrap()
+                    receiver.recv_mut().unwrap()
                 });
             });)*
             group.finish();
@@ -74,6 +76,8 @@ fn bench(c: &mut Criterion) {
     bench_try_recv(c);
     bench_recv_deadline_now(c);
     bench_recv_timeout_zero(c);
+    bench_recv_round_trip(c);
+    bench_recv_set(c);
 }
 
 fn bench_try_recv(c: &mut Criterion) {
@@ -120,3 +124,74 @@ fn bench_recv_timeout_zero(c: &mut Criterion) {
         });
     }
 }
+
+fn bench_recv_round_trip(c: &mut Criterion) {
+    // A thread that answers every request right away, so the receiver rarely has to wait long.
+    let (request_sender, request_receiver) = mpsc::channel::<oneshot::Sender<u128>>();
+    let responder = thread::spawn(move || {
+        for response_sender in request_receiver {
+            let _ = response_sender.send(1234567u128);
+        }
+    });
+
+    c.bench_function("recv_round_trip", |b| {
+        b.iter(|| {
+            let (sender, receiver) = oneshot::channel();
+            request_sender.send(sender).unwrap();
+            receiver.recv().unwrap()
+        })
+    });
+    c.bench_function("recv_spin_round_trip", |b| {
+        b.iter(|| {
+            let (sender, receiver) = oneshot::channel();
+            request_sender.send(sender).unwrap();
+            receiver.recv_spin(1000).unwrap()
+        })
+    });
+    c.bench_function("recv_busy_round_trip", |b| {
+        b.iter(|| {
+            let (sender, receiver) = oneshot::channel();
+            request_sender.send(sender).unwrap();
+            receiver.recv_busy(None).unwrap()
+        })
+    });
+
+    mem::drop(request_sender);
+    responder.join().unwrap();
+}
+
+fn bench_recv_set(c: &mut Criterion) {
+    use futures_util::stream::{FuturesUnordered, StreamExt};
+    use futures_util::FutureExt;
+    use std::task::{Context, Poll};
+
+    const CHANNELS: u32 = 100;
+    let mut cx = Context::from_waker(futures_util::task::noop_waker_ref());
+
+    c.bench_function("recv_set_100", |b| {
+        let mut set = oneshot::RecvSet::new();
+        b.iter(|| {
+            for key in 0..CHANNELS {
+                let (sender, receiver) = oneshot::channel::<u128>();
+                set.insert(key, receiver);
+                sender.send(1234567u128).unwrap();
+            }
+            while let Poll::Ready(Some(next)) = set.poll_next(&mut cx) {
+                black_box(next.1.unwrap());
+            }
+        })
+    });
+    c.bench_function("futures_unordered_100", |b| {
+        let mut set = FuturesUnordered::new();
+        b.iter(|| {
+            for key in 0..CHANNELS {
+                let (sender, receiver) = oneshot::channel::<u128>();
+                set.push(FutureExt::map(receiver, move |result| (key, result)).boxed());
+                sender.send(1234567u128).unwrap();
+            }
+            while let Poll::Ready(Some(next)) = set.poll_next_unpin(&mut cx) {
+                black_box(next.1.unwrap());
+            }
+        })
+    });
+}
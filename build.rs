@@ -0,0 +1,6 @@
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    // Declare the custom cfgs used by the crate and its tests, so newer toolchains don't warn
+    // about them. Older toolchains ignore these lines.
+    println!("cargo:rustc-check-cfg=cfg(loom)");
+}
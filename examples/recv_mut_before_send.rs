@@ -3,12 +3,12 @@ fn main() {
     use std::thread;
     use std::time::Duration;
 
-    let (sender, receiver) = oneshot::channel();
+    let (sender, mut receiver) = oneshot::channel();
     let t = thread::spawn(move || {
         thread::sleep(Duration::from_millis(2));
         sender.send(9u128).unwrap();
     });
-    assert_eq!(receiver.recv_ref(), Ok(9));
+    assert_eq!(receiver.recv_mut(), Ok(9));
     t.join().unwrap();
 }
 
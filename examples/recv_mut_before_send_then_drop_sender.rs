@@ -3,12 +3,12 @@ fn main() {
     use std::thread;
     use std::time::Duration;
 
-    let (sender, receiver) = oneshot::channel::<u128>();
+    let (sender, mut receiver) = oneshot::channel::<u128>();
     let t = thread::spawn(move || {
         thread::sleep(Duration::from_millis(2));
         std::mem::drop(sender);
     });
-    assert!(receiver.recv_ref().is_err());
+    assert!(receiver.recv_mut().is_err());
     t.join().unwrap();
 }
 
@@ -0,0 +1,94 @@
+use super::{Receiver, RecvError, RecvTimeoutError, TryRecvError};
+use std::sync::mpsc;
+use std::time::Duration;
+
+/// The blocking receive methods shared by [`Receiver`] and [`std::sync::mpsc::Receiver`].
+///
+/// Lets code written against the standard library channel accept a oneshot receiver as well,
+/// which smooths migrating from one to the other. The errors are the ones of
+/// [`std::sync::mpsc`], so existing error handling keeps working.
+///
+/// ```
+/// use oneshot::BlockingReceive;
+/// use std::sync::mpsc;
+///
+/// fn wait_for_answer<R: BlockingReceive<u32>>(mut receiver: R) -> Option<u32> {
+///     receiver.recv().ok()
+/// }
+///
+/// let (sender, receiver) = mpsc::channel();
+/// sender.send(5).unwrap();
+/// assert_eq!(wait_for_answer(receiver), Some(5));
+///
+/// let (sender, receiver) = oneshot::channel();
+/// sender.send(5).unwrap();
+/// assert_eq!(wait_for_answer(receiver), Some(5));
+/// ```
+pub trait BlockingReceive<T> {
+    /// Blocks until a message arrives, or until no message can arrive anymore.
+    fn recv(&mut self) -> Result<T, mpsc::RecvError>;
+
+    /// Returns a message if there is one, without blocking.
+    fn try_recv(&mut self) -> Result<T, mpsc::TryRecvError>;
+
+    /// Like [`BlockingReceive::recv`], but will not block longer than `timeout`.
+    fn recv_timeout(&mut self, timeout: Duration) -> Result<T, mpsc::RecvTimeoutError>;
+}
+
+/// Receives like [`Receiver::recv_mut`], [`Receiver::try_recv_mut`] and
+/// [`Receiver::recv_timeout`]. Since a oneshot channel carries at most one message, every
+/// receive after the first successful one is disconnected.
+///
+/// The blocking methods work also after the receiver has been polled asynchronously, see
+/// [`Receiver::recv_mut`].
+impl<T> BlockingReceive<T> for Receiver<T> {
+    fn recv(&mut self) -> Result<T, mpsc::RecvError> {
+        Ok(self.recv_mut()?)
+    }
+
+    fn try_recv(&mut self) -> Result<T, mpsc::TryRecvError> {
+        Ok(self.try_recv_mut()?)
+    }
+
+    fn recv_timeout(&mut self, timeout: Duration) -> Result<T, mpsc::RecvTimeoutError> {
+        Ok(Receiver::recv_timeout(self, timeout)?)
+    }
+}
+
+impl<T> BlockingReceive<T> for mpsc::Receiver<T> {
+    fn recv(&mut self) -> Result<T, mpsc::RecvError> {
+        mpsc::Receiver::recv(self)
+    }
+
+    fn try_recv(&mut self) -> Result<T, mpsc::TryRecvError> {
+        mpsc::Receiver::try_recv(self)
+    }
+
+    fn recv_timeout(&mut self, timeout: Duration) -> Result<T, mpsc::RecvTimeoutError> {
+        mpsc::Receiver::recv_timeout(self, timeout)
+    }
+}
+
+impl From<RecvError> for mpsc::RecvError {
+    fn from(_: RecvError) -> Self {
+        mpsc::RecvError
+    }
+}
+
+impl From<TryRecvError> for mpsc::TryRecvError {
+    fn from(error: TryRecvError) -> Self {
+        match error {
+            TryRecvError::Empty => mpsc::TryRecvError::Empty,
+            TryRecvError::Disconnected => mpsc::TryRecvError::Disconnected,
+        }
+    }
+}
+
+impl From<RecvTimeoutError> for mpsc::RecvTimeoutError {
+    fn from(error: RecvTimeoutError) -> Self {
+        match error {
+            RecvTimeoutError::Timeout => mpsc::RecvTimeoutError::Timeout,
+            RecvTimeoutError::Disconnected => mpsc::RecvTimeoutError::Disconnected,
+        }
+    }
+}
@@ -0,0 +1,89 @@
+//! Futures racing an endpoint against a `tokio_util` [`CancellationToken`].
+
+use super::{Receiver, RecvOrCancelError, Sender};
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{self, Poll},
+};
+use tokio_util::sync::{CancellationToken, WaitForCancellationFuture};
+
+/// The future returned from [`Receiver::recv_cancellable`].
+pub(crate) struct RecvCancellable<'a, T> {
+    receiver: Receiver<T>,
+    // Structurally pinned. Never moved out of the pinned future.
+    cancelled: WaitForCancellationFuture<'a>,
+}
+
+impl<'a, T> RecvCancellable<'a, T> {
+    pub(crate) fn new(receiver: Receiver<T>, token: &'a CancellationToken) -> Self {
+        Self {
+            receiver,
+            cancelled: token.cancelled(),
+        }
+    }
+}
+
+impl<T> Future for RecvCancellable<'_, T> {
+    type Output = Result<T, RecvOrCancelError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Self::Output> {
+        // SAFETY: only `cancelled` is pinned, and it is never moved out of the future. The
+        // receiver is `Unpin`, so it's fine to hand out a plain mutable reference to it.
+        let this = unsafe { self.get_unchecked_mut() };
+        // The receiver is polled first, so a message wins over a cancellation at the same time.
+        match this.receiver.poll_recv(cx) {
+            Poll::Ready(Ok(message)) => return Poll::Ready(Ok(message)),
+            Poll::Ready(Err(_)) => return Poll::Ready(Err(RecvOrCancelError::Disconnected)),
+            Poll::Pending => (),
+        }
+
+        // SAFETY: `this` comes from a pinned reference, and `cancelled` is structurally pinned.
+        let cancelled = unsafe { Pin::new_unchecked(&mut this.cancelled) };
+        // The waker stored in the channel is dropped along with the receiver, when this future
+        // is.
+        cancelled
+            .poll(cx)
+            .map(|()| Err(RecvOrCancelError::Cancelled))
+    }
+}
+
+/// The future returned from [`Sender::closed_or_cancelled`].
+pub(crate) struct ClosedOrCancelled<'a, T> {
+    sender: &'a mut Sender<T>,
+    // Structurally pinned. Never moved out of the pinned future.
+    cancelled: WaitForCancellationFuture<'a>,
+}
+
+impl<'a, T> ClosedOrCancelled<'a, T> {
+    pub(crate) fn new(sender: &'a mut Sender<T>, token: &'a CancellationToken) -> Self {
+        Self {
+            sender,
+            cancelled: token.cancelled(),
+        }
+    }
+}
+
+impl<T> Future for ClosedOrCancelled<'_, T> {
+    type Output = bool;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<bool> {
+        // SAFETY: only `cancelled` is pinned, and it is never moved out of the future.
+        let this = unsafe { self.get_unchecked_mut() };
+        if this.sender.poll_closed(cx).is_ready() {
+            return Poll::Ready(true);
+        }
+
+        // SAFETY: `this` comes from a pinned reference, and `cancelled` is structurally pinned.
+        let cancelled = unsafe { Pin::new_unchecked(&mut this.cancelled) };
+        cancelled.poll(cx).map(|()| false)
+    }
+}
+
+impl<T> Drop for ClosedOrCancelled<'_, T> {
+    fn drop(&mut self) {
+        // The sender outlives this future, so the waker registered by `poll_closed` would
+        // otherwise stay in the channel and be woken when the receiver goes away.
+        self.sender.reclaim_closed_waker();
+    }
+}
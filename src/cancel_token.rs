@@ -0,0 +1,142 @@
+//! A cancellation flag that wakes up the threads receiving with it.
+
+use super::{
+    thread::{self, Thread},
+    Arc, Receiver, RecvInterruptedError, RecvOrCancelError,
+};
+use core::{
+    fmt,
+    sync::atomic::{AtomicBool, Ordering::*},
+};
+use std::sync::PoisonError;
+
+#[cfg(loom)]
+use loom::sync::{Mutex, MutexGuard};
+#[cfg(not(loom))]
+use std::sync::{Mutex, MutexGuard};
+
+/// A token for cancelling blocking receives from another thread, created with
+/// [`CancelToken::new`].
+///
+/// Pass it to [`Receiver::recv_until_cancelled`] to block until the message arrives or the token
+/// is cancelled. The token keeps track of the threads blocking on it, and [`CancelToken::cancel`]
+/// unparks them, so no polling with a timeout is needed. Clones share the same flag, and a token
+/// can be used by any number of receives at once. Once cancelled it stays cancelled.
+///
+/// ```
+/// let (_sender, receiver) = oneshot::channel::<u32>();
+/// let token = oneshot::CancelToken::new();
+/// let canceller = token.clone();
+/// std::thread::spawn(move || canceller.cancel());
+/// assert_eq!(
+///     receiver.recv_until_cancelled(&token),
+///     Err(oneshot::RecvOrCancelError::Cancelled)
+/// );
+/// ```
+#[derive(Clone)]
+pub struct CancelToken {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    cancelled: AtomicBool,
+    // The threads currently blocking in `Receiver::recv_until_cancelled` with this token. A
+    // thread is in here once for every receive it blocks in.
+    waiters: Mutex<Vec<Thread>>,
+}
+
+impl CancelToken {
+    /// Creates a token that is not cancelled.
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                cancelled: AtomicBool::new(false),
+                waiters: Mutex::new(Vec::new()),
+            }),
+        }
+    }
+
+    /// Cancels the token, making all receives blocking on it, now or later, return
+    /// [`RecvOrCancelError::Cancelled`] unless their message is already there.
+    pub fn cancel(&self) {
+        // ORDERING: release what the cancelling thread did to the receivers seeing the flag
+        self.inner.cancelled.store(true, Release);
+        // A thread registers before checking the flag, so every thread that might have missed
+        // the store above is in the list now.
+        for thread in self.waiters().iter() {
+            thread.unpark();
+        }
+    }
+
+    /// Returns true if [`CancelToken::cancel`] has been called on this token or a clone of it.
+    pub fn is_cancelled(&self) -> bool {
+        // ORDERING: see `cancel`
+        self.inner.cancelled.load(Acquire)
+    }
+
+    fn waiters(&self) -> MutexGuard<'_, Vec<Thread>> {
+        self.inner
+            .waiters
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Removes the current thread from the waiters of a token when dropped, also if the receive
+/// panics.
+struct Registration<'a> {
+    token: &'a CancelToken,
+}
+
+impl<'a> Registration<'a> {
+    fn new(token: &'a CancelToken) -> Self {
+        token.waiters().push(thread::current());
+        Self { token }
+    }
+}
+
+impl Drop for Registration<'_> {
+    fn drop(&mut self) {
+        let id = thread::current().id();
+        let mut waiters = self.token.waiters();
+        if let Some(index) = waiters.iter().position(|thread| thread.id() == id) {
+            waiters.swap_remove(index);
+        }
+    }
+}
+
+impl<T> Receiver<T> {
+    /// Blocks until a message arrives, the sender is dropped, or `token` is cancelled. Returns:
+    ///  * `Ok(message)` if a message arrived. A message that is already in the channel wins
+    ///    over a cancelled token.
+    ///  * `Err(Cancelled)` if `token` was cancelled before a message arrived. This receiver can
+    ///    then still be used to receive.
+    ///  * `Err(Disconnected)` if the sender was dropped before sending anything or if the message
+    ///    has already been extracted by a previous receive call.
+    ///
+    /// Works like [`Receiver::recv_interruptible`], with the token doing the unpark.
+    ///
+    /// Works after polling, see [`Receiver::recv_mut`].
+    pub fn recv_until_cancelled(&self, token: &CancelToken) -> Result<T, RecvOrCancelError> {
+        let _registration = Registration::new(token);
+        self.recv_interruptible(&token.inner.cancelled)
+            .map_err(|error| match error {
+                RecvInterruptedError::Interrupted => RecvOrCancelError::Cancelled,
+                RecvInterruptedError::Disconnected => RecvOrCancelError::Disconnected,
+            })
+    }
+}
+
+impl Default for CancelToken {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for CancelToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CancelToken")
+            .field("cancelled", &self.is_cancelled())
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,120 @@
+use super::{Receiver, RecvTimeoutError, TryRecvError};
+use core::fmt;
+use std::time::{Duration, Instant};
+
+#[cfg(all(feature = "async", not(loom)))]
+use super::timer::{AsyncTimer, RecvTimeout};
+#[cfg(all(feature = "async", not(loom)))]
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{self, Poll},
+};
+
+/// A receiver with a deadline attached. Created by [`Receiver::with_deadline`].
+///
+/// [`DeadlineReceiver::recv`] blocks like [`Receiver::recv_deadline`] with the attached deadline.
+///
+/// When the `async` feature is enabled it also implements `Future`, resolving to
+/// `Err(RecvTimeoutError::Timeout)` when polled once the deadline has passed. Since this crate
+/// does not depend on any async runtime, the task is only woken when the sender sends or is
+/// dropped, not when the deadline passes. It has to be polled again by other means after the
+/// deadline, like a periodic tick or another future it is raced with.
+#[cfg_attr(
+    all(feature = "async", not(loom)),
+    doc = "[`DeadlineReceiver::recv_with`] instead sleeps with the timer of an async runtime, \
+           which wakes the task at the deadline."
+)]
+pub struct DeadlineReceiver<T> {
+    receiver: Receiver<T>,
+    deadline: Instant,
+}
+
+impl<T> DeadlineReceiver<T> {
+    pub(crate) fn new(receiver: Receiver<T>, deadline: Instant) -> Self {
+        Self { receiver, deadline }
+    }
+
+    /// Returns the attached deadline.
+    pub fn deadline(&self) -> Instant {
+        self.deadline
+    }
+
+    /// Returns how much time is left until the deadline, or zero if it has passed.
+    pub fn remaining(&self) -> Duration {
+        self.deadline.saturating_duration_since(Instant::now())
+    }
+
+    /// Like [`Receiver::recv_deadline`], with the attached deadline. If this receiver has been
+    /// polled before, the waker of that poll is replaced by one for the current thread.
+    pub fn recv(&self) -> Result<T, RecvTimeoutError> {
+        self.receiver.recv_deadline(self.deadline)
+    }
+
+    /// Like [`Receiver::try_recv`]. Does not look at the deadline.
+    pub fn try_recv(&self) -> Result<T, TryRecvError> {
+        self.receiver.try_recv()
+    }
+
+    /// Returns a future receiving like this receiver, but sleeping until the deadline with the
+    /// timer of the async runtime given as `S`, see the [`timer`](crate::timer) module. The
+    /// sleep wakes the task once the deadline has passed, so no other polling is needed.
+    ///
+    /// The sleep lasts for the time left until the deadline when the future is first polled.
+    ///
+    /// ```
+    /// # #[cfg(feature = "tokio")]
+    /// # tokio::runtime::Builder::new_current_thread()
+    /// #     .enable_time()
+    /// #     .build()
+    /// #     .unwrap()
+    /// #     .block_on(async {
+    /// use std::time::{Duration, Instant};
+    ///
+    /// let (_sender, receiver) = oneshot::channel::<u32>();
+    /// let receiver = receiver.with_deadline(Instant::now() + Duration::from_millis(10));
+    /// let result = receiver.recv_with::<oneshot::timer::Tokio>().await;
+    /// assert_eq!(result, Err(oneshot::RecvTimeoutError::Timeout));
+    /// # });
+    /// ```
+    #[cfg(all(feature = "async", not(loom)))]
+    pub fn recv_with<S: AsyncTimer>(self) -> RecvTimeout<T, S> {
+        RecvTimeout::until(self.receiver, self.deadline)
+    }
+
+    /// Removes the deadline and returns the inner receiver.
+    ///
+    /// If this was polled before, the receiver is still registered with the last waker, so it
+    /// can only be received from asynchronously.
+    pub fn into_inner(self) -> Receiver<T> {
+        self.receiver
+    }
+}
+
+#[cfg(all(feature = "async", not(loom)))]
+impl<T> Future for DeadlineReceiver<T> {
+    type Output = Result<T, RecvTimeoutError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        match this.receiver.poll_recv(cx) {
+            Poll::Ready(Ok(message)) => Poll::Ready(Ok(message)),
+            Poll::Ready(Err(_)) => Poll::Ready(Err(RecvTimeoutError::Disconnected)),
+            // The sender wakes the task when it sends or is dropped. Nothing wakes it at the
+            // deadline, see the type level docs.
+            Poll::Pending if Instant::now() >= this.deadline => {
+                Poll::Ready(Err(RecvTimeoutError::Timeout))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl<T> fmt::Debug for DeadlineReceiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DeadlineReceiver")
+            .field("receiver", &self.receiver)
+            .field("deadline", &self.deadline)
+            .finish()
+    }
+}
@@ -1,4 +1,4 @@
-use super::{dealloc, Channel};
+use super::{release_send_error_channel, Channel, FreeOnDrop, Receiver};
 use core::fmt;
 use core::mem;
 use core::ptr::NonNull;
@@ -19,8 +19,10 @@ impl<T> SendError<T> {
     ///
     /// By calling this function, the caller semantically transfers ownership of the
     /// channel's resources to the created `SendError`. Thus the caller must ensure that the
-    /// pointer is not used in a way which would violate this ownership transfer. Moreover,
-    /// the caller must assert that the channel contains a valid, initialized message.
+    /// pointer is not used in a way which would violate this ownership transfer. The only
+    /// exception is a closed receiver that is still alive, which shares the channel but never
+    /// accesses the message. Moreover, the caller must assert that the channel contains a valid,
+    /// initialized message.
     pub(crate) const unsafe fn new(channel_ptr: NonNull<Channel<T>>) -> Self {
         Self { channel_ptr }
     }
@@ -40,8 +42,8 @@ impl<T> SendError<T> {
         // `new`
         let message = unsafe { channel.take_message() };
 
-        // SAFETY: we own the channel
-        unsafe { dealloc(channel_ptr) };
+        // SAFETY: we are done with the message and never access the channel again
+        unsafe { release_send_error_channel(channel_ptr) };
 
         message
     }
@@ -55,12 +57,17 @@ impl<T> SendError<T> {
 
 impl<T> Drop for SendError<T> {
     fn drop(&mut self) {
-        // SAFETY: we have ownership of the channel and require that the message is initialized
-        // upon construction
-        unsafe {
-            self.channel_ptr.as_ref().drop_message();
-            dealloc(self.channel_ptr);
-        }
+        // Release the channel also if the destructor of the message panics.
+        // SAFETY: the guard runs after we are done with the message, and `into_inner` forgets
+        // `self`, so the channel is only released once
+        let _release = FreeOnDrop {
+            channel_ptr: self.channel_ptr,
+            free: release_send_error_channel,
+        };
+
+        // SAFETY: we have ownership of the message and require that it is initialized upon
+        // construction
+        unsafe { self.channel_ptr.as_ref().drop_message() };
     }
 }
 
@@ -79,22 +86,203 @@ impl<T> fmt::Debug for SendError<T> {
 #[cfg(feature = "std")]
 impl<T> std::error::Error for SendError<T> {}
 
+/// An error returned from [`LocalSender::send`] if the [`LocalReceiver`] has been dropped.
+/// Holds the message that could not be sent.
+pub struct LocalSendError<T>(T);
+
+impl<T> LocalSendError<T> {
+    #[cfg(not(loom))]
+    pub(crate) fn new(message: T) -> Self {
+        Self(message)
+    }
+
+    /// Consumes the error and returns the message that failed to be sent.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+
+    /// Get a reference to the message that failed to be sent.
+    pub fn as_inner(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> fmt::Display for LocalSendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        "sending on a closed channel".fmt(f)
+    }
+}
+
+impl<T> fmt::Debug for LocalSendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "LocalSendError<{}>(_)", stringify!(T))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> std::error::Error for LocalSendError<T> {}
+
+/// An error returned from [`StaticSender::send`] if the [`StaticReceiver`] has been dropped.
+/// Holds the message that could not be sent.
+pub struct StaticSendError<T>(T);
+
+impl<T> StaticSendError<T> {
+    #[cfg(not(loom))]
+    pub(crate) fn new(message: T) -> Self {
+        Self(message)
+    }
+
+    /// Consumes the error and returns the message that failed to be sent.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+
+    /// Get a reference to the message that failed to be sent.
+    pub fn as_inner(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> fmt::Display for StaticSendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        "sending on a closed channel".fmt(f)
+    }
+}
+
+impl<T> fmt::Debug for StaticSendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "StaticSendError<{}>(_)", stringify!(T))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> std::error::Error for StaticSendError<T> {}
+
+/// An error returned from [`Sender::send_with`] if the corresponding [`Receiver`] has been
+/// dropped or closed.
+pub enum SendWithError<T> {
+    /// The receiver was gone before the message was built. The closure was never called.
+    Closed,
+
+    /// The receiver went away while the message was being built. Holds the message that could
+    /// not be sent.
+    Send(SendError<T>),
+}
+
+impl<T> SendWithError<T> {
+    /// Consumes the error and returns the message that failed to be sent, if it was built.
+    #[inline]
+    pub fn into_inner(self) -> Option<T> {
+        match self {
+            SendWithError::Closed => None,
+            SendWithError::Send(error) => Some(error.into_inner()),
+        }
+    }
+}
+
+impl<T> fmt::Display for SendWithError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        "sending on a closed channel".fmt(f)
+    }
+}
+
+impl<T> fmt::Debug for SendWithError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SendWithError::Closed => f.write_str("Closed"),
+            SendWithError::Send(error) => f.debug_tuple("Send").field(error).finish(),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> std::error::Error for SendWithError<T> {}
+
+/// An error returned from [`Sender::try_send`], telling why the message could not be sent.
+pub enum TrySendError<T> {
+    /// The [`Receiver`] was dropped or closed. Holds the message that could not be sent.
+    Disconnected(SendError<T>),
+}
+
+impl<T> TrySendError<T> {
+    /// Consumes the error and returns the message that failed to be sent.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        match self {
+            TrySendError::Disconnected(error) => error.into_inner(),
+        }
+    }
+
+    /// Get a reference to the message that failed to be sent.
+    #[inline]
+    pub fn as_inner(&self) -> &T {
+        match self {
+            TrySendError::Disconnected(error) => error.as_inner(),
+        }
+    }
+}
+
+impl<T> fmt::Display for TrySendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        "sending on a closed channel".fmt(f)
+    }
+}
+
+impl<T> fmt::Debug for TrySendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TrySendError::Disconnected(error) => {
+                f.debug_tuple("Disconnected").field(error).finish()
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> std::error::Error for TrySendError<T> {}
+
 /// An error returned from the indefinitely blocking recv functions on a [`Receiver`].
 ///
 /// The recv operation can only fail if the corresponding [`Sender`] was dropped before sending
 /// any message. Or if a message has already been sent and received on the channel.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
-pub struct RecvError;
+pub enum RecvError {
+    /// The [`Sender`] was dropped before sending anything, or the [`Receiver`] was closed.
+    Disconnected,
+
+    /// The message has already been received by an earlier receive call on this receiver.
+    AlreadyReceived,
+}
 
 impl fmt::Display for RecvError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        "receiving on a closed channel".fmt(f)
+        let msg = match self {
+            RecvError::Disconnected => "receiving on a closed channel",
+            RecvError::AlreadyReceived => {
+                "receiving on a channel whose message was already received"
+            }
+        };
+        msg.fmt(f)
     }
 }
 
 #[cfg(feature = "std")]
 impl std::error::Error for RecvError {}
 
+/// Sent by a [`SendGuard`](crate::SendGuard) that is dropped while its thread is panicking,
+/// instead of the message.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct SenderPanicked;
+
+impl fmt::Display for SenderPanicked {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        "the sender panicked before sending".fmt(f)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SenderPanicked {}
+
 /// An error returned when trying a non blocking receive on a [`Receiver`].
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub enum TryRecvError {
@@ -119,6 +307,45 @@ impl fmt::Display for TryRecvError {
 #[cfg(feature = "std")]
 impl std::error::Error for TryRecvError {}
 
+/// An error returned from [`Receiver::into_try_recv`].
+pub enum IntoTryRecvError<T> {
+    /// The channel is still open, but there was no message present in it. The receiver is
+    /// handed back so it can keep waiting.
+    Empty(Receiver<T>),
+
+    /// The channel is closed. Either the sender was dropped before sending any message, or the
+    /// message has already been extracted from the receiver.
+    Disconnected,
+}
+
+impl<T> IntoTryRecvError<T> {
+    /// Returns the [`TryRecvError`] corresponding to this error.
+    pub fn kind(&self) -> TryRecvError {
+        match self {
+            IntoTryRecvError::Empty(_) => TryRecvError::Empty,
+            IntoTryRecvError::Disconnected => TryRecvError::Disconnected,
+        }
+    }
+}
+
+impl<T> fmt::Display for IntoTryRecvError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.kind().fmt(f)
+    }
+}
+
+impl<T> fmt::Debug for IntoTryRecvError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IntoTryRecvError::Empty(receiver) => f.debug_tuple("Empty").field(receiver).finish(),
+            IntoTryRecvError::Disconnected => f.write_str("Disconnected"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> std::error::Error for IntoTryRecvError<T> {}
+
 /// An error returned when trying a time limited blocking receive on a [`Receiver`].
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub enum RecvTimeoutError {
@@ -142,3 +369,134 @@ impl fmt::Display for RecvTimeoutError {
 
 #[cfg(feature = "std")]
 impl std::error::Error for RecvTimeoutError {}
+
+/// An error returned from [`Receiver::recv_or_cancel`] and
+/// [`Receiver::recv_until_cancelled`], and from the future of `Receiver::recv_cancellable`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum RecvOrCancelError {
+    /// The cancel channel fired, or the cancellation token was cancelled, before a message
+    /// arrived. With [`Receiver::recv_or_cancel`] the channel is still open.
+    Cancelled,
+
+    /// The channel is closed. Either the sender was dropped before sending any message, or the
+    /// message has already been extracted from the receiver.
+    Disconnected,
+}
+
+impl fmt::Display for RecvOrCancelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let msg = match self {
+            RecvOrCancelError::Cancelled => "receive was cancelled",
+            RecvOrCancelError::Disconnected => "channel is empty and sending half is closed",
+        };
+        msg.fmt(f)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for RecvOrCancelError {}
+
+/// An error returned from [`Receiver::recv_interruptible`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum RecvInterruptedError {
+    /// The interrupt flag was set before a message arrived. The channel is still open.
+    Interrupted,
+
+    /// The channel is closed. Either the sender was dropped before sending any message, or the
+    /// message has already been extracted from the receiver.
+    Disconnected,
+}
+
+impl fmt::Display for RecvInterruptedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let msg = match self {
+            RecvInterruptedError::Interrupted => "receive was interrupted",
+            RecvInterruptedError::Disconnected => "channel is empty and sending half is closed",
+        };
+        msg.fmt(f)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for RecvInterruptedError {}
+
+/// An error returned when receiving a `Result` with [`Receiver::recv_flat`],
+/// [`Receiver::try_recv_flat`] or [`Receiver::flatten`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum RecvOrError<E> {
+    /// The channel is closed. Either the sender was dropped before sending any message, or the
+    /// message has already been extracted from the receiver.
+    Disconnected,
+
+    /// The sender sent an error.
+    App(E),
+}
+
+impl<E> From<RecvError> for RecvOrError<E> {
+    fn from(_: RecvError) -> Self {
+        RecvOrError::Disconnected
+    }
+}
+
+impl<E> fmt::Display for RecvOrError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // The error sent by the sender is exposed as the source, not repeated here.
+        let msg = match self {
+            RecvOrError::Disconnected => "receiving on a closed channel",
+            RecvOrError::App(_) => "the sender sent an error",
+        };
+        msg.fmt(f)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: std::error::Error + 'static> std::error::Error for RecvOrError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RecvOrError::Disconnected => None,
+            RecvOrError::App(error) => Some(error),
+        }
+    }
+}
+
+/// An error returned from [`Receiver::recv_timeout_owned`] and
+/// [`Receiver::recv_deadline_owned`].
+pub enum IntoRecvTimeoutError<T> {
+    /// No message arrived on the channel before the timeout was reached. The channel is still
+    /// open, and the receiver is handed back so the receive can be retried.
+    Timeout(Receiver<T>),
+
+    /// The channel is closed. Either the sender was dropped before sending any message, or the
+    /// message has already been extracted from the receiver.
+    Disconnected,
+}
+
+impl<T> IntoRecvTimeoutError<T> {
+    /// Returns the [`RecvTimeoutError`] corresponding to this error.
+    pub fn kind(&self) -> RecvTimeoutError {
+        match self {
+            IntoRecvTimeoutError::Timeout(_) => RecvTimeoutError::Timeout,
+            IntoRecvTimeoutError::Disconnected => RecvTimeoutError::Disconnected,
+        }
+    }
+}
+
+impl<T> fmt::Display for IntoRecvTimeoutError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.kind().fmt(f)
+    }
+}
+
+impl<T> fmt::Debug for IntoRecvTimeoutError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IntoRecvTimeoutError::Timeout(receiver) => {
+                f.debug_tuple("Timeout").field(receiver).finish()
+            }
+            IntoRecvTimeoutError::Disconnected => f.write_str("Disconnected"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> std::error::Error for IntoRecvTimeoutError<T> {}
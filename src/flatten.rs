@@ -0,0 +1,44 @@
+use super::{Receiver, RecvOrError};
+use core::{
+    fmt,
+    future::Future,
+    pin::Pin,
+    task::{self, Poll},
+};
+
+/// A future receiving a `Result` and merging a disconnected channel and an error sent by the
+/// [`Sender`](crate::Sender) into one [`RecvOrError`]. Created by [`Receiver::flatten`].
+pub struct Flatten<T, E> {
+    receiver: Receiver<Result<T, E>>,
+}
+
+impl<T, E> Flatten<T, E> {
+    pub(crate) fn new(receiver: Receiver<Result<T, E>>) -> Self {
+        Self { receiver }
+    }
+
+    /// Returns the inner receiver.
+    pub fn into_inner(self) -> Receiver<Result<T, E>> {
+        self.receiver
+    }
+}
+
+impl<T, E> Future for Flatten<T, E> {
+    type Output = Result<T, RecvOrError<E>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Self::Output> {
+        match Pin::new(&mut self.get_mut().receiver).poll(cx) {
+            Poll::Ready(Ok(result)) => Poll::Ready(result.map_err(RecvOrError::App)),
+            Poll::Ready(Err(error)) => Poll::Ready(Err(error.into())),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl<T, E> fmt::Debug for Flatten<T, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Flatten")
+            .field("receiver", &self.receiver)
+            .finish()
+    }
+}
@@ -0,0 +1,54 @@
+use super::{Receiver, SendError, Sender};
+use core::{
+    fmt,
+    future::Future,
+    pin::Pin,
+    task::{self, Poll},
+};
+
+/// A future relaying the message of a [`Receiver`] into a [`Sender`] of another channel.
+/// Created by [`Receiver::forward`].
+pub struct Forward<T> {
+    receiver: Receiver<T>,
+    /// Taken out when the message or the disconnect is relayed.
+    dest: Option<Sender<T>>,
+}
+
+impl<T> Forward<T> {
+    pub(crate) fn new(receiver: Receiver<T>, dest: Sender<T>) -> Self {
+        Self {
+            receiver,
+            dest: Some(dest),
+        }
+    }
+}
+
+impl<T> Future for Forward<T> {
+    type Output = Result<(), SendError<T>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        match Pin::new(&mut this.receiver).poll(cx) {
+            Poll::Ready(Ok(message)) => {
+                // The receiver only ever returns one message, so the sender is always there.
+                let dest = this.dest.take().expect("message already forwarded");
+                Poll::Ready(dest.send(message))
+            }
+            Poll::Ready(Err(_)) => {
+                // Dropping the destination sender relays the disconnect.
+                this.dest = None;
+                Poll::Ready(Ok(()))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl<T> fmt::Debug for Forward<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Forward")
+            .field("receiver", &self.receiver)
+            .field("dest", &self.dest)
+            .finish()
+    }
+}
@@ -0,0 +1,82 @@
+use super::{SendError, Sender, SenderPanicked};
+use core::fmt;
+
+/// A [`Sender`] that tells the receiver if it is dropped by a panic. Created by
+/// [`Sender::send_on_drop_guard`].
+///
+/// A plain `Sender` dropped while its thread unwinds looks the same to the receiver as one
+/// dropped on purpose. If this guard is dropped while the thread is panicking, it sends
+/// `Err(SenderPanicked)` instead. The receiver then gets [`RecvOrError::App`] from
+/// [`Receiver::recv_flat`], while a deliberate drop still gives [`RecvOrError::Disconnected`].
+///
+/// ```
+/// let (sender, receiver) = oneshot::channel::<Result<u32, oneshot::SenderPanicked>>();
+/// let guard = sender.send_on_drop_guard();
+/// let worker = std::thread::spawn(move || {
+///     let _guard = guard;
+///     panic!("worker crashed");
+/// });
+/// assert!(worker.join().is_err());
+/// assert_eq!(
+///     receiver.recv_flat(),
+///     Err(oneshot::RecvOrError::App(oneshot::SenderPanicked))
+/// );
+/// ```
+///
+/// [`Receiver::recv_flat`]: crate::Receiver::recv_flat
+/// [`RecvOrError::App`]: crate::RecvOrError::App
+/// [`RecvOrError::Disconnected`]: crate::RecvOrError::Disconnected
+pub struct SendGuard<T> {
+    sender: Option<Sender<Result<T, SenderPanicked>>>,
+}
+
+impl<T> Sender<Result<T, SenderPanicked>> {
+    /// Wraps this sender in a [`SendGuard`], which sends `Err(SenderPanicked)` if it is dropped
+    /// while the thread is panicking.
+    pub fn send_on_drop_guard(self) -> SendGuard<T> {
+        SendGuard { sender: Some(self) }
+    }
+}
+
+impl<T> SendGuard<T> {
+    /// Sends `Ok(message)` to the receiver. See [`Sender::send`].
+    pub fn send(mut self, message: T) -> Result<(), SendError<Result<T, SenderPanicked>>> {
+        self.take_sender().send(Ok(message))
+    }
+
+    /// Returns true if the receiver has been dropped or closed. See [`Sender::is_closed`].
+    pub fn is_closed(&self) -> bool {
+        self.sender.as_ref().map_or(true, Sender::is_closed)
+    }
+
+    /// Unwraps the guard, returning the plain sender. It no longer reports panics.
+    pub fn into_inner(mut self) -> Sender<Result<T, SenderPanicked>> {
+        self.take_sender()
+    }
+
+    fn take_sender(&mut self) -> Sender<Result<T, SenderPanicked>> {
+        // The sender is only taken by consuming methods and the destructor.
+        self.sender
+            .take()
+            .expect("SendGuard always holds a sender until consumed")
+    }
+}
+
+impl<T> Drop for SendGuard<T> {
+    fn drop(&mut self) {
+        if let Some(sender) = self.sender.take() {
+            if std::thread::panicking() {
+                // The receiver being gone is fine, there is no one to tell.
+                let _ = sender.send(Err(SenderPanicked));
+            }
+        }
+    }
+}
+
+impl<T> fmt::Debug for SendGuard<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SendGuard")
+            .field("sender", &self.sender)
+            .finish()
+    }
+}
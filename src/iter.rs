@@ -0,0 +1,45 @@
+use super::Receiver;
+use core::iter::FusedIterator;
+
+/// An iterator yielding the message of a [`Receiver`], and then ending. Created by the
+/// [`IntoIterator`] implementation of the receiver.
+///
+/// Like the iterator of [`std::sync::mpsc::Receiver`], [`Iterator::next`] blocks until the
+/// message arrives. If the sender is dropped without sending, the iterator ends without yielding
+/// anything.
+#[derive(Debug)]
+pub struct ReceiverIter<T> {
+    receiver: Option<Receiver<T>>,
+}
+
+impl<T> Iterator for ReceiverIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        // `recv` consumes the receiver and frees the channel, so later calls have nothing to
+        // receive from and return `None`.
+        self.receiver.take()?.recv().ok()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.receiver {
+            Some(_) => (0, Some(1)),
+            None => (0, Some(0)),
+        }
+    }
+}
+
+impl<T> FusedIterator for ReceiverIter<T> {}
+
+impl<T> IntoIterator for Receiver<T> {
+    type Item = T;
+    type IntoIter = ReceiverIter<T>;
+
+    /// Returns an iterator that blocks until the message arrives and yields it. Ends without
+    /// yielding anything if the sender is dropped without sending.
+    fn into_iter(self) -> ReceiverIter<T> {
+        ReceiverIter {
+            receiver: Some(self),
+        }
+    }
+}
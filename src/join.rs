@@ -0,0 +1,185 @@
+use super::{Receiver, RecvError, RecvOrError};
+use core::{
+    fmt,
+    future::Future,
+    mem,
+    pin::Pin,
+    task::{self, Poll},
+};
+
+/// Returns a future waiting for the messages of both `a` and `b`.
+///
+/// Unlike joining the two receivers with a generic `join`, the future resolves with the error
+/// as soon as either channel is found disconnected, without waiting for the other one. The other
+/// receiver is then dropped right away, so its sender sees the channel as closed.
+///
+/// ```
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let (sender1, receiver1) = oneshot::channel::<u32>();
+/// let (sender2, receiver2) = oneshot::channel::<&str>();
+/// sender2.send("two").unwrap();
+/// sender1.send(1).unwrap();
+/// assert_eq!(oneshot::join(receiver1, receiver2).await, Ok((1, "two")));
+/// # });
+/// ```
+pub fn join<A, B>(a: Receiver<A>, b: Receiver<B>) -> Join<A, B> {
+    Join {
+        a: Side::Waiting(a),
+        b: Side::Waiting(b),
+    }
+}
+
+/// The future returned from [`join`].
+pub struct Join<A, B> {
+    a: Side<A>,
+    b: Side<B>,
+}
+
+/// The progress of one of the receivers of a [`Join`].
+enum Side<T> {
+    Waiting(Receiver<T>),
+    Received(T),
+    /// The future has completed, or the other side disconnected.
+    Done,
+}
+
+impl<T> Side<T> {
+    /// Polls the receiver if the message has not arrived yet. Returns `Ready(Ok(()))` once the
+    /// message is here.
+    fn poll(&mut self, cx: &mut task::Context<'_>) -> Poll<Result<(), RecvError>> {
+        match self {
+            Side::Waiting(receiver) => match receiver.poll_recv(cx) {
+                Poll::Ready(Ok(message)) => {
+                    *self = Side::Received(message);
+                    Poll::Ready(Ok(()))
+                }
+                Poll::Ready(Err(error)) => Poll::Ready(Err(error)),
+                Poll::Pending => Poll::Pending,
+            },
+            Side::Received(_) => Poll::Ready(Ok(())),
+            Side::Done => panic!("Join polled after completion"),
+        }
+    }
+
+    fn take(&mut self) -> T {
+        match mem::replace(self, Side::Done) {
+            Side::Received(message) => message,
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl<A, B> Future for Join<A, B> {
+    type Output = Result<(A, B), RecvError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        // Both sides are polled even if the first is pending, so a disconnect of the second is
+        // noticed right away.
+        let a = this.a.poll(cx);
+        let b = this.b.poll(cx);
+        match (a, b) {
+            (Poll::Ready(Ok(())), Poll::Ready(Ok(()))) => {
+                Poll::Ready(Ok((this.a.take(), this.b.take())))
+            }
+            (Poll::Ready(Err(error)), _) | (_, Poll::Ready(Err(error))) => {
+                // Free the other channel, and any message already received from it.
+                this.a = Side::Done;
+                this.b = Side::Done;
+                Poll::Ready(Err(error))
+            }
+            _ => Poll::Pending,
+        }
+    }
+}
+
+// The messages are moved out, never pinned.
+impl<A, B> Unpin for Join<A, B> {}
+
+impl<A, B> fmt::Debug for Join<A, B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Join").finish_non_exhaustive()
+    }
+}
+
+/// Like [`join`], but for channels carrying results. Returns a future resolving with both `Ok`
+/// values, or with the first error.
+///
+/// An error sent on either channel resolves the future right away with [`RecvOrError::App`],
+/// like a disconnect does with [`RecvOrError::Disconnected`]. The other receiver is then dropped
+/// at once, so its sender sees the channel as closed.
+///
+/// ```
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let (sender1, receiver1) = oneshot::channel::<Result<u32, &str>>();
+/// let (sender2, receiver2) = oneshot::channel::<Result<u32, &str>>();
+/// sender2.send(Err("failed")).unwrap();
+/// assert_eq!(
+///     oneshot::try_join(receiver1, receiver2).await,
+///     Err(oneshot::RecvOrError::App("failed"))
+/// );
+/// assert!(sender1.is_closed());
+/// # });
+/// ```
+pub fn try_join<A, B, E>(a: Receiver<Result<A, E>>, b: Receiver<Result<B, E>>) -> TryJoin<A, B, E> {
+    TryJoin {
+        a: Side::Waiting(a),
+        b: Side::Waiting(b),
+    }
+}
+
+/// The future returned from [`try_join`].
+pub struct TryJoin<A, B, E> {
+    a: Side<Result<A, E>>,
+    b: Side<Result<B, E>>,
+}
+
+impl<T, E> Side<Result<T, E>> {
+    /// Like [`Side::poll`], but also fails if the message is an error.
+    fn try_poll(&mut self, cx: &mut task::Context<'_>) -> Poll<Result<(), RecvOrError<E>>> {
+        match self.poll(cx) {
+            Poll::Ready(Ok(())) if matches!(self, Side::Received(Err(_))) => match self.take() {
+                Err(error) => Poll::Ready(Err(RecvOrError::App(error))),
+                Ok(_) => unreachable!(),
+            },
+            Poll::Ready(result) => Poll::Ready(result.map_err(RecvOrError::from)),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl<A, B, E> Future for TryJoin<A, B, E> {
+    type Output = Result<(A, B), RecvOrError<E>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        // See `Join::poll`. The second side is not polled if the first has already failed.
+        let a = this.a.try_poll(cx);
+        let b = match a {
+            Poll::Ready(Err(_)) => Poll::Pending,
+            _ => this.b.try_poll(cx),
+        };
+        match (a, b) {
+            (Poll::Ready(Ok(())), Poll::Ready(Ok(()))) => match (this.a.take(), this.b.take()) {
+                (Ok(a), Ok(b)) => Poll::Ready(Ok((a, b))),
+                _ => unreachable!(),
+            },
+            (Poll::Ready(Err(error)), _) | (_, Poll::Ready(Err(error))) => {
+                // Free the other channel, and any message already received from it.
+                this.a = Side::Done;
+                this.b = Side::Done;
+                Poll::Ready(Err(error))
+            }
+            _ => Poll::Pending,
+        }
+    }
+}
+
+// The messages are moved out, never pinned.
+impl<A, B, E> Unpin for TryJoin<A, B, E> {}
+
+impl<A, B, E> fmt::Debug for TryJoin<A, B, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TryJoin").finish_non_exhaustive()
+    }
+}
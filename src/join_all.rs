@@ -0,0 +1,145 @@
+use super::{Receiver, RecvError};
+use alloc::{sync::Arc, task::Wake, vec::Vec};
+use core::{
+    future::Future,
+    pin::Pin,
+    sync::atomic::{AtomicBool, Ordering::*},
+    task::{self, Poll, Waker},
+};
+
+/// Returns a future waiting for the messages of all `receivers`. It resolves with one result per
+/// receiver, in the order of `receivers`.
+///
+/// Each receiver is polled with a waker of its own, which marks it as ready before waking up
+/// the task. When polled, the future then only polls the receivers that were marked, not all of
+/// them. Only if the task's waker changes between polls, like when the task moves to another
+/// executor, are all pending receivers polled again, to hand them the new waker.
+///
+/// ```
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let (senders, receivers): (Vec<_>, Vec<_>) = (0..3).map(|_| oneshot::channel()).unzip();
+/// for (i, sender) in senders.into_iter().enumerate().rev() {
+///     sender.send(i).unwrap();
+/// }
+/// assert_eq!(oneshot::join_all(receivers).await, [Ok(0), Ok(1), Ok(2)]);
+/// # });
+/// ```
+pub fn join_all<T>(receivers: Vec<Receiver<T>>) -> impl Future<Output = Vec<Result<T, RecvError>>> {
+    let ready = receivers.iter().map(|_| AtomicBool::new(false)).collect();
+    JoinAll {
+        remaining: receivers.len(),
+        children: Some(
+            receivers
+                .into_iter()
+                .map(|receiver| Child::Pending {
+                    receiver,
+                    waker: None,
+                })
+                .collect(),
+        ),
+        ready: Arc::new(ready),
+        parent: None,
+    }
+}
+
+/// The future returned from [`join_all`].
+struct JoinAll<T> {
+    // Only `None` once the future has completed.
+    children: Option<Vec<Child<T>>>,
+    /// The number of children still pending.
+    remaining: usize,
+    /// Set by the waker of the child with the same index when woken.
+    ready: Arc<Vec<AtomicBool>>,
+    /// The task waker the child wakers forward to. `None` before the first poll.
+    parent: Option<Waker>,
+}
+
+enum Child<T> {
+    Pending {
+        receiver: Receiver<T>,
+        /// Passed when polling the receiver. `None` until it is first polled.
+        waker: Option<Waker>,
+    },
+    Done(Result<T, RecvError>),
+}
+
+/// The waker a [`join_all`] child is polled with.
+struct ChildWaker {
+    ready: Arc<Vec<AtomicBool>>,
+    index: usize,
+    parent: Waker,
+}
+
+impl Wake for ChildWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref()
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        // ORDERING: make the flag visible to the poll that follows the wake, even if the executor
+        // does not synchronize the two. The receiver synchronizes with its sender on its own.
+        self.ready[self.index].store(true, Release);
+        self.parent.wake_by_ref();
+    }
+}
+
+impl<T> Future for JoinAll<T> {
+    type Output = Vec<Result<T, RecvError>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let children = this
+            .children
+            .as_mut()
+            .expect("join_all polled after completion");
+        let new_parent = match &this.parent {
+            Some(parent) => !parent.will_wake(cx.waker()),
+            None => true,
+        };
+        if new_parent {
+            this.parent = Some(cx.waker().clone());
+        }
+
+        for (index, child) in children.iter_mut().enumerate() {
+            let (receiver, waker) = match child {
+                Child::Pending { receiver, waker } => (receiver, waker),
+                Child::Done(_) => continue,
+            };
+            // Cleared before polling, so a wake during the poll is not missed.
+            // ORDERING: synchronize with `ChildWaker::wake_by_ref`
+            if !this.ready[index].swap(false, Acquire) && !new_parent {
+                continue;
+            }
+            if new_parent {
+                *waker = Some(Waker::from(Arc::new(ChildWaker {
+                    ready: this.ready.clone(),
+                    index,
+                    parent: cx.waker().clone(),
+                })));
+            }
+            let waker = waker.as_ref().unwrap();
+            if let Poll::Ready(result) = receiver.poll_recv(&mut task::Context::from_waker(waker)) {
+                *child = Child::Done(result);
+                this.remaining -= 1;
+            }
+        }
+
+        if this.remaining > 0 {
+            return Poll::Pending;
+        }
+        let results = this
+            .children
+            .take()
+            .unwrap()
+            .into_iter()
+            .map(|child| match child {
+                Child::Done(result) => result,
+                Child::Pending { .. } => unreachable!(),
+            })
+            .collect();
+        Poll::Ready(results)
+    }
+}
+
+// The receivers and results are never pinned.
+impl<T> Unpin for JoinAll<T> {}
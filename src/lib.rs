@@ -73,6 +73,47 @@
 //! }
 //! ```
 //!
+//! # Consuming and non-consuming receive methods
+//!
+//! [`Receiver::recv`] consumes the receiver, and frees the channel as soon as it returns.
+//! [`Receiver::recv_mut`], [`Receiver::recv_timeout`] and [`Receiver::recv_deadline`] take the
+//! receiver by reference instead, so it can be used again after a timeout. With those the
+//! channel is freed when the receiver is dropped. [`Receiver::recv_timeout_owned`] and
+//! [`Receiver::recv_deadline_owned`] combine the two. They consume the receiver, but hand it back
+//! on timeout. [`Receiver::try_recv`] and [`Receiver::into_try_recv`] are the non-blocking
+//! counterparts.
+//!
+//! # Cargo features
+//!
+//! * `std` - enabled by default. Makes the crate depend on `std` and enables the thread blocking
+//!   receive methods ([`Receiver::recv`], [`Receiver::recv_mut`], [`Receiver::recv_timeout`] and
+//!   [`Receiver::recv_deadline`]) as well as the `std::error::Error` implementations for the
+//!   error types. Without this feature the crate is `#![no_std]` and only depends on
+//!   `core` and `alloc`.
+//! * `async` - enabled by default. Implements `Future` for [`Receiver`] so it can be `.await`ed.
+//!   This feature does not require `std`.
+//! * `stream` - adds [`Receiver::into_stream`], turning the receiver into a
+//!   `futures_core::Stream` yielding exactly one item, and [`Receiver::into_message_stream`],
+//!   yielding only a received message. Enables `async` and adds a dependency on `futures-core`.
+//! * `futures-core` - implements `futures_core::FusedFuture` for [`Receiver`] when `async` is
+//!   also enabled, so it can be used directly in `futures::select!` without `.fuse()`. Enabled
+//!   by `stream`.
+//! * `tracing` - emits trace level `tracing` events when a message is sent, an endpoint is
+//!   dropped, the receiver parks or is polled, and when the channel is closed. The events tell
+//!   which endpoint emitted them, and on the sender side whether the receiver was woken up.
+//! * `tokio` and `async-std` - add timer adapters for [`Receiver::recv_timeout_with`] in the
+//!   [`timer`] module, sleeping with the timer of that runtime. `tokio` also adds
+//!   [`Receiver::recv_timeout_async`], the same with the tokio timer, and
+//!   [`Receiver::recv_with_deadline`] and [`Receiver::recv_with_timeout`], measuring the time
+//!   with the tokio clock without boxing the sleep.
+//! * `wasm-bindgen` - adds [`Receiver::into_js_promise`], turning the receiver into a
+//!   JavaScript `Promise` with `wasm-bindgen-futures`. Enables `async`.
+//! * `tokio-util` - adds [`Receiver::recv_cancellable`] and [`Sender::closed_or_cancelled`],
+//!   racing the endpoint against a `tokio_util::sync::CancellationToken`. Only has an effect
+//!   together with `async`.
+//!
+//! With both features disabled [`Receiver::try_recv`] is the only way of receiving.
+//!
 //! # Sync vs async
 //!
 //! The main motivation for writing this library was that there were no (known to me) channel
@@ -88,6 +129,19 @@
 //! in an asynchronous task. This implementation is completely executor/runtime agnostic. It should
 //! be possible to use this library with any executor.
 //!
+//! # WebAssembly
+//!
+//! The crate builds for `wasm32-unknown-unknown`. Without the `atomics` target feature that
+//! target has a single thread that can't be parked, so the receiver has to be `.await`ed or
+//! checked with [`Receiver::try_recv`]. The methods that block the thread, like `recv` and
+//! [`Sender::send_blocking`], panic there when they would have to wait, instead of hanging
+//! forever. `recv` and `recv_mut` still return right away if the channel is already done. The
+//! time limited receive methods can't be used at all, since the standard library has no clock
+//! on that target. Sending never blocks and works the same as on other targets.
+//!
+//! On `wasm32-wasip1-threads` all of the crate works like on other targets. The standard
+//! library parks threads there by waiting on memory with `memory.atomic.wait32`.
+//!
 
 // # Implementation description
 //
@@ -95,10 +149,15 @@
 // containing:
 // * A one byte atomic integer that represents the current channel state,
 // * Uninitialized memory to fit the message,
-// * Uninitialized memory to fit the waker that can wake the receiving task or thread up.
+// * Uninitialized memory to fit the waker that can wake the receiving task or thread up, or run
+//   the callback registered with `Receiver::on_receive`.
+// * With the `async` feature, one more one byte atomic integer and uninitialized memory to fit the
+//   waker of a task waiting in `Sender::closed` for the receiver to be dropped.
+// * With the `std` feature, an optional pointer to the separately allocated `SendAck` of a
+//   sender blocking in `Sender::send_blocking`.
 //
-// The size of the waker depends on which features are activated, it ranges from 0 to 24 bytes[1].
-// So with all features enabled (the default) each channel allocates 25 bytes plus the size of the
+// The size of the waker depends on which features are activated, it ranges from 0 to 16 bytes[1].
+// So with all features enabled (the default) each channel allocates 42 bytes plus the size of the
 // message, plus any padding needed to get correct memory alignment.
 //
 // The Sender and Receiver only holds a raw pointer to the heap channel object. The last endpoint
@@ -118,6 +177,7 @@
 extern crate alloc;
 
 use core::{
+    fmt,
     marker::PhantomData,
     mem::{self, MaybeUninit},
     ptr::{self, NonNull},
@@ -134,11 +194,15 @@ use loom::{
     sync::atomic::{fence, AtomicU8, Ordering::*},
 };
 
-#[cfg(all(feature = "async", not(loom)))]
+#[cfg(all(any(feature = "std", feature = "async"), not(loom)))]
 use core::hint;
-#[cfg(all(feature = "async", loom))]
+#[cfg(all(any(feature = "std", feature = "async"), loom))]
 use loom::hint;
 
+#[cfg(feature = "std")]
+use core::iter::{once_with, FusedIterator};
+#[cfg(feature = "std")]
+use core::sync::atomic::AtomicBool;
 #[cfg(feature = "async")]
 use core::{
     pin::Pin,
@@ -150,10 +214,10 @@ use std::time::{Duration, Instant};
 #[cfg(feature = "std")]
 mod thread {
     #[cfg(not(loom))]
-    pub use std::thread::{current, park, park_timeout, yield_now, Thread};
+    pub use std::thread::{current, park, park_timeout, Thread};
 
     #[cfg(loom)]
-    pub use loom::thread::{current, park, yield_now, Thread};
+    pub use loom::thread::{current, park, Thread};
 
     // loom does not support parking with a timeout. So we just
     // yield. This means that the "park" will "spuriously" wake up
@@ -162,11 +226,30 @@ mod thread {
     // when using loom, since otherwise the looping will cause
     // an overflow in loom.
     #[cfg(loom)]
-    pub fn park_timeout(_timeout: std::time::Duration) {
-        loom::thread::yield_now()
+    pub fn park_timeout(timeout: std::time::Duration) {
+        loom::thread::yield_now();
+        crate::test_hooks::parked_for(timeout);
     }
 }
 
+/// Emits a trace level `tracing` event when the `tracing` feature is enabled, and does nothing
+/// otherwise. Takes the same arguments as `tracing::trace!`.
+macro_rules! trace {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        tracing::trace!($($arg)*);
+    };
+}
+
+#[cfg(feature = "std")]
+#[doc(hidden)]
+pub mod test_hooks;
+
+#[cfg(all(feature = "std", not(loom)))]
+use alloc::sync::Arc;
+#[cfg(all(feature = "std", loom))]
+use loom::sync::Arc;
+
 #[cfg(loom)]
 mod loombox;
 #[cfg(not(loom))]
@@ -175,9 +258,119 @@ use alloc::boxed::Box;
 use loombox::Box;
 
 mod errors;
-pub use errors::{RecvError, RecvTimeoutError, SendError, TryRecvError};
+pub use errors::{
+    IntoRecvTimeoutError, IntoTryRecvError, LocalSendError, RecvError, RecvInterruptedError,
+    RecvOrCancelError, RecvOrError, RecvTimeoutError, SendError, SendWithError, SenderPanicked,
+    StaticSendError, TryRecvError, TrySendError,
+};
+
+#[cfg(not(loom))]
+mod local;
+#[cfg(not(loom))]
+pub use local::{local_channel, LocalReceiver, LocalSender};
+
+#[cfg(not(loom))]
+mod static_channel;
+#[cfg(not(loom))]
+pub use static_channel::{StaticChannel, StaticReceiver, StaticSender};
+
+mod map;
+pub use map::Map;
+
+mod undelivered;
+pub use undelivered::OnUndelivered;
+
+mod pair;
+pub use pair::{channel_pair, PairSender};
+
+#[cfg(feature = "async")]
+mod flatten;
+#[cfg(feature = "async")]
+pub use flatten::Flatten;
+
+#[cfg(feature = "async")]
+mod forward;
+#[cfg(feature = "async")]
+pub use forward::Forward;
+
+#[cfg(feature = "async")]
+mod recv_future;
+#[cfg(feature = "async")]
+pub use recv_future::RecvFuture;
+
+#[cfg(all(feature = "async", not(loom)))]
+pub mod timer;
+
+#[cfg(all(feature = "tokio-util", feature = "async", not(loom)))]
+mod cancel;
+
+#[cfg(feature = "std")]
+mod cancel_token;
+#[cfg(feature = "std")]
+pub use cancel_token::CancelToken;
+
+#[cfg(feature = "std")]
+mod deadline;
+#[cfg(feature = "std")]
+pub use deadline::DeadlineReceiver;
+
+#[cfg(feature = "std")]
+mod blocking;
+#[cfg(feature = "std")]
+pub use blocking::BlockingReceive;
+
+#[cfg(feature = "std")]
+mod guard;
+#[cfg(feature = "std")]
+pub use guard::SendGuard;
+
+#[cfg(feature = "std")]
+mod iter;
+#[cfg(feature = "std")]
+pub use iter::ReceiverIter;
+
+#[cfg(any(feature = "std", feature = "async"))]
+mod select;
+#[cfg(any(feature = "std", feature = "async"))]
+pub use select::Select;
+#[cfg(feature = "std")]
+pub use select::{race_blocking, select_any, select_blocking, select_blocking_timeout, wait_all};
+#[cfg(feature = "async")]
+pub use select::{select, select_any_async, SelectAny};
+
+#[cfg(feature = "async")]
+mod join;
+#[cfg(feature = "async")]
+pub use join::{join, try_join, Join, TryJoin};
+
+#[cfg(all(feature = "async", not(loom)))]
+mod join_all;
+#[cfg(all(feature = "async", not(loom)))]
+pub use join_all::join_all;
+
+#[cfg(all(feature = "std", feature = "async", not(loom)))]
+mod recv_set;
+#[cfg(all(feature = "std", feature = "async", not(loom)))]
+pub use recv_set::RecvSet;
+
+#[cfg(feature = "stream")]
+mod stream;
+#[cfg(feature = "stream")]
+pub use stream::{MessageStream, ReceiverStream};
 
 /// Creates a new oneshot channel and returns the two endpoints, [`Sender`] and [`Receiver`].
+///
+/// The message type does not have to be `'static`, so the message can borrow data from the
+/// stack. The borrow checker then keeps both endpoints from outliving that data. The same holds
+/// for endpoints moved into scoped threads, like the ones of `std::thread::scope`, so they can
+/// send borrowed data to each other without cloning it.
+///
+/// ```
+/// let names = vec![String::from("foo"), String::from("bar")];
+/// let (sender, receiver) = oneshot::channel::<&str>();
+/// sender.send(names.iter().max().unwrap()).unwrap();
+/// assert_eq!(receiver.try_recv(), Ok("foo"));
+/// ```
 pub fn channel<T>() -> (Sender<T>, Receiver<T>) {
     // Allocate the channel on the heap and get the pointer.
     // The last endpoint of the channel to be alive is responsible for freeing the channel
@@ -197,7 +390,6 @@ pub fn channel<T>() -> (Sender<T>, Receiver<T>) {
     )
 }
 
-#[derive(Debug)]
 pub struct Sender<T> {
     channel_ptr: NonNull<Channel<T>>,
     // In reality we want contravariance, however we can't obtain that.
@@ -219,7 +411,6 @@ pub struct Sender<T> {
     _invariant: PhantomData<fn(T) -> T>,
 }
 
-#[derive(Debug)]
 pub struct Receiver<T> {
     // Covariance is the right choice here. Consider the example presented in Sender, and you'll
     // see that if we replaced `rx` instead then we would get the expected behavior
@@ -230,6 +421,78 @@ unsafe impl<T: Send> Send for Sender<T> {}
 unsafe impl<T: Send> Send for Receiver<T> {}
 impl<T> Unpin for Receiver<T> {}
 
+impl<T> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sender")
+            .field("channel_id", &self.channel_id())
+            .field("closed", &self.is_closed())
+            .finish()
+    }
+}
+
+impl<T> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Receiver")
+            .field("channel_id", &self.channel_id())
+            .field("state", &self.state())
+            .finish()
+    }
+}
+
+/// An opaque identifier of a channel, returned from [`Sender::channel_id`] and
+/// [`Receiver::channel_id`]. Both endpoints of a channel have the same id.
+///
+/// The id of a channel is unique among all channels where at least one endpoint is alive.
+/// It can be kept and compared after the channel is gone, but might then be reused by a new
+/// channel.
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+pub struct ChannelId(usize);
+
+impl ChannelId {
+    /// The address of the channel allocation is used as the id, since the channel is not freed
+    /// until both endpoints are gone.
+    fn new<T>(channel_ptr: NonNull<Channel<T>>) -> Self {
+        Self(channel_ptr.as_ptr() as usize)
+    }
+}
+
+impl fmt::Debug for ChannelId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ChannelId")
+            .field(&format_args!("{:#x}", self.0))
+            .finish()
+    }
+}
+
+/// The logical state of a channel, as observed by [`Receiver::state`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ChannelState {
+    /// The [`Sender`] is alive, but has not yet sent a message.
+    Empty,
+
+    /// A message has been sent, and is waiting in the channel to be received.
+    MessageAvailable,
+
+    /// The [`Sender`] was dropped without sending a message.
+    SenderDropped,
+
+    /// The message has already been received by a previous receive call.
+    Consumed,
+
+    /// The receiver has been closed with [`Receiver::close`].
+    Closed,
+}
+
+/// How a message was delivered, as returned by [`Sender::send_status`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum SendStatus {
+    /// The receiver was not waiting yet. The message was left in the channel for it to pick up.
+    Buffered,
+
+    /// The receiver was blocked or polled and waiting, and was woken up to take the message.
+    Woke,
+}
+
 impl<T> Sender<T> {
     /// Sends `message` over the channel to the corresponding [`Receiver`].
     ///
@@ -245,6 +508,51 @@ impl<T> Sender<T> {
     /// the error involves running any drop implementation on the message type, and freeing the
     /// channel's heap allocation, which might or might not be lock-free.
     pub fn send(self, message: T) -> Result<(), SendError<T>> {
+        self.send_inner(message).map(|_| ())
+    }
+
+    /// Async version of [`Sender::send`], for composing a send with other futures. Sending never
+    /// has to wait, so the returned future completes on its first poll, with an error holding
+    /// the message if the receiver is already gone.
+    ///
+    /// The message is only sent when the future is polled. Dropping the future before that
+    /// drops the message along with the sender, so the receiver sees a disconnect, like with
+    /// dropping an unused sender.
+    ///
+    /// ```
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let (sender, receiver) = oneshot::channel::<u32>();
+    /// sender.send_async(5).await.unwrap();
+    /// assert_eq!(receiver.await, Ok(5));
+    /// # });
+    /// ```
+    #[cfg(feature = "async")]
+    pub fn send_async(
+        self,
+        message: T,
+    ) -> impl core::future::Future<Output = Result<(), SendError<T>>> {
+        SendAsync {
+            inner: Some((self, message)),
+        }
+    }
+
+    /// Like [`Sender::send`], but also tells if the receiver was waiting for the message and had
+    /// to be woken up. Meant for measuring how often receivers wait on senders.
+    ///
+    /// ```
+    /// let (sender, receiver) = oneshot::channel::<u32>();
+    /// assert_eq!(sender.send_status(5).unwrap(), oneshot::SendStatus::Buffered);
+    /// assert_eq!(receiver.try_recv(), Ok(5));
+    /// ```
+    pub fn send_status(self, message: T) -> Result<SendStatus, SendError<T>> {
+        self.send_inner(message)
+    }
+
+    /// The implementation of [`Sender::send`] and [`Sender::send_status`]. Always inlined, so
+    /// `send` does not pay for the status it throws away.
+    #[inline(always)]
+    fn send_inner(self, message: T) -> Result<SendStatus, SendError<T>> {
+        assert!(!self.is_spent(), "{}", SENDER_SPENT_ERROR);
         let channel_ptr = self.channel_ptr;
 
         // Don't run our Drop implementation if send was called, any cleanup now happens here
@@ -269,10 +577,14 @@ impl<T> Sender<T> {
         //
         // EMPTY + 1 = MESSAGE
         // RECEIVING + 1 = UNPARKING
-        // DISCONNECTED + 1 = invalid, however this state is never observed
+        // DISCONNECTED + 1 = invalid, however this state is only observed by the SendError
+        // RECEIVER_CLOSED + 1 = CLOSED_SEND_FAILED
         match channel.state.fetch_add(1, Release) {
             // The receiver is alive and has not started waiting. Send done.
-            EMPTY => Ok(()),
+            EMPTY => {
+                trace!(endpoint = "sender", woke = false, "message sent");
+                Ok(SendStatus::Buffered)
+            }
             // The receiver is waiting. Wake it up so it can return the message.
             RECEIVING => {
                 // ORDERING: Synchronizes with the write of the waker to memory, and prevents the
@@ -302,22 +614,385 @@ impl<T> Sender<T> {
                 // waker is wrapped in MaybeUninit. Therefore this data is valid regardless of
                 // whether or not the receive has completed by this point.
                 waker.unpark();
+                trace!(endpoint = "sender", woke = true, "message sent");
 
-                Ok(())
+                Ok(SendStatus::Woke)
             }
             // The receiver was already dropped. The error is responsible for freeing the channel.
             // SAFETY: since the receiver disconnected it will no longer access `channel_ptr`, so
             // we can transfer exclusive ownership of the channel's resources to the error.
             // Moreover, since we just placed the message in the channel, the channel contains a
             // valid message.
-            DISCONNECTED => Err(unsafe { SendError::new(channel_ptr) }),
+            DISCONNECTED => {
+                trace!(endpoint = "sender", "send failed, the receiver is dropped");
+                Err(unsafe { SendError::new(channel_ptr) })
+            }
+            // The receiver has been closed, but is still alive. The error and the receiver
+            // share the channel, and the last one of them to be dropped frees it.
+            // SAFETY: the receiver never accesses the message in the CLOSED_SEND_FAILED state,
+            // and we just placed a valid message in the channel.
+            RECEIVER_CLOSED => {
+                trace!(endpoint = "sender", "send failed, the receiver is closed");
+                Err(unsafe { SendError::new(channel_ptr) })
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    /// Sends `message` like [`Sender::send`], without consuming the sender. For when the sender
+    /// is behind a `&mut`, for example inside a struct.
+    ///
+    /// Afterwards the sender is spent. Dropping it does nothing, [`Sender::is_closed`] returns
+    /// true and [`Sender::poll_closed`] is ready right away.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the sender is already spent. Sending on a spent sender with any of the send
+    /// methods panics as well, except [`Sender::send_with`] which returns
+    /// [`SendWithError::Closed`].
+    pub fn send_ref(&mut self, message: T) -> Result<(), SendError<T>> {
+        let sender = Sender {
+            channel_ptr: mem::replace(&mut self.channel_ptr, spent_channel_ptr()),
+            _invariant: PhantomData,
+        };
+        sender.send(message)
+    }
+
+    /// Returns true if the sender has been spent by [`Sender::send_ref`]. It then has no channel.
+    #[inline(always)]
+    fn is_spent(&self) -> bool {
+        self.channel_ptr == spent_channel_ptr()
+    }
+
+    /// Like [`Sender::send`], but with an error naming the reason of the failure, like
+    /// [`Receiver::try_recv`] does. For code matching on the `try_` errors of both endpoints.
+    ///
+    /// A send never has to wait, so the only possible failure is
+    /// [`TrySendError::Disconnected`], holding the message, if the receiver is dropped or closed.
+    ///
+    /// ```
+    /// let (sender, receiver) = oneshot::channel::<u32>();
+    /// drop(receiver);
+    /// match sender.try_send(5) {
+    ///     Err(oneshot::TrySendError::Disconnected(error)) => assert_eq!(error.into_inner(), 5),
+    ///     Ok(()) => unreachable!(),
+    /// }
+    /// ```
+    pub fn try_send(self, message: T) -> Result<(), TrySendError<T>> {
+        self.send(message).map_err(TrySendError::Disconnected)
+    }
+
+    /// Sends the message built by `f`, but only calls `f` if the [`Receiver`] is still alive.
+    ///
+    /// Useful when the message is expensive to build and pointless to send to a receiver that
+    /// is gone. If the receiver is already dropped or closed, [`SendWithError::Closed`] is
+    /// returned without calling `f`. If it goes away while `f` runs, the built message is
+    /// handed back in [`SendWithError::Send`], just like with [`Sender::send`].
+    pub fn send_with<F: FnOnce() -> T>(self, f: F) -> Result<(), SendWithError<T>> {
+        if self.is_closed() {
+            return Err(SendWithError::Closed);
+        }
+        self.send(f()).map_err(SendWithError::Send)
+    }
+
+    /// Sends `message` like [`Sender::send`], and then blocks the thread until the [`Receiver`]
+    /// has taken the message out of the channel. When this returns `Ok(())` the message has been
+    /// received, not just sent.
+    ///
+    /// Returns an error holding the message if the receiver was already dropped or closed, or if
+    /// it is dropped later without receiving the message. A message sent before the receiver was
+    /// closed can still be received, so then this keeps blocking until the receiver receives it or
+    /// is dropped.
+    ///
+    /// # Deadlocks
+    ///
+    /// This blocks forever if the receiver is kept alive but never receives. Never call it on the
+    /// thread that is supposed to receive the message.
+    #[cfg(feature = "std")]
+    pub fn send_blocking(self, message: T) -> Result<(), SendError<T>> {
+        assert!(!self.is_spent(), "{}", SENDER_SPENT_ERROR);
+        assert_can_block();
+        let channel_ptr = self.channel_ptr;
+
+        let ack = Arc::new(SendAck {
+            state: AtomicU8::new(ACK_WAITING),
+            thread: thread::current(),
+        });
+        // SAFETY: the channel is valid while we are alive. The receiver only accesses the ack
+        // after it has observed the message, which `send` publishes with release ordering.
+        unsafe { channel_ptr.as_ref().write_send_ack(ack.clone()) };
+
+        if let Err(error) = self.send(message) {
+            // SAFETY: the error owns the channel. A closed receiver that is still alive never
+            // accesses the ack.
+            unsafe { channel_ptr.as_ref().take_send_ack() };
+            return Err(error);
+        }
+
+        // We must not access the channel again until the receiver tells us what it did with the
+        // message. It frees the channel if it received it.
+        loop {
+            thread::park();
+
+            // ORDERING: synchronize with all the receiver's accesses of the channel
+            match ack.state.load(Acquire) {
+                // Spurious wakeup, park again.
+                ACK_WAITING => (),
+                ACK_TAKEN => break Ok(()),
+                // SAFETY: the receiver was dropped without receiving, and left the message and
+                // the responsibility of freeing the channel to us.
+                ACK_RETURNED => break Err(unsafe { SendError::new(channel_ptr) }),
+                _ => unreachable!(),
+            }
+        }
+    }
+
+    /// Returns true if the associated [`Receiver`] has been dropped or closed with
+    /// [`Receiver::close`].
+    ///
+    /// If true is returned, a future call to send is guaranteed to return an error.
+    /// If false is returned the receiver might be dropped right after the check, so a
+    /// subsequent send can still fail.
+    pub fn is_closed(&self) -> bool {
+        if self.is_spent() {
+            return true;
+        }
+        // SAFETY: The channel will not be freed while this method is still running.
+        let channel = unsafe { self.channel_ptr.as_ref() };
+
+        // ORDERING: We *chose* a Relaxed ordering here as it is sufficient to enforce the
+        // method's contract: "if true is returned, a future call to send is guaranteed to
+        // return an error."
+        matches!(channel.state.load(Relaxed), DISCONNECTED | RECEIVER_CLOSED)
+    }
+
+    /// Returns true if the associated [`Receiver`] is currently waiting for the message, either
+    /// parked in one of the blocking receive methods or polled as a future and waiting to be
+    /// woken up.
+    ///
+    /// This is only a hint, for example to prioritize producing messages that someone waits for.
+    /// The receiver can start or stop waiting right after the check. The answer is read from the
+    /// channel state, which tells whether the receiver has stored a waker for the sender to wake.
+    pub fn is_receiver_waiting(&self) -> bool {
+        if self.is_spent() {
+            return false;
+        }
+        // SAFETY: The channel will not be freed while this method is still running.
+        let channel = unsafe { self.channel_ptr.as_ref() };
+
+        // ORDERING: this is a hint and we don't access the waker, so no synchronization is needed
+        channel.state.load(Relaxed) == RECEIVING
+    }
+
+    /// Returns true if this sender and `receiver` are the two endpoints of the same channel.
+    ///
+    /// Sending or receiving with a consuming method consumes the endpoint, so this can only be
+    /// asked while both endpoints still exist.
+    pub fn same_channel(&self, receiver: &Receiver<T>) -> bool {
+        self.channel_ptr == receiver.channel_ptr
+    }
+
+    /// Returns the id of the channel this sender belongs to. The same id is returned from
+    /// [`Receiver::channel_id`] on the associated receiver.
+    pub fn channel_id(&self) -> ChannelId {
+        ChannelId::new(self.channel_ptr)
+    }
+
+    /// Consumes the sender and returns a raw pointer to the channel, for example to pass it
+    /// through FFI as an opaque pointer. The sender is not dropped, so the channel is kept alive
+    /// until the sender is reconstructed with [`Sender::from_raw`] and then used or dropped.
+    pub fn into_raw(self) -> *mut () {
+        let raw = self.channel_ptr.as_ptr() as *mut ();
+        mem::forget(self);
+        raw
+    }
+
+    /// Reconstructs a sender from a pointer returned by [`Sender::into_raw`].
+    ///
+    /// # Safety
+    ///
+    /// `raw` must come from a call to [`Sender::into_raw`] on a `Sender<T>` of the same `T`.
+    /// Each raw pointer must only be turned back into a sender once.
+    pub unsafe fn from_raw(raw: *mut ()) -> Self {
+        Self {
+            channel_ptr: NonNull::new_unchecked(raw as *mut Channel<T>),
+            _invariant: PhantomData,
+        }
+    }
+
+    /// Waits for the associated [`Receiver`] to be dropped. Can be used to stop producing a
+    /// message that nobody is going to receive.
+    ///
+    /// The returned future completes once the receiver has been dropped or closed with
+    /// [`Receiver::close`]. If that has already happened it completes immediately.
+    ///
+    /// ```
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let (mut sender, receiver) = oneshot::channel::<u32>();
+    /// drop(receiver);
+    /// sender.closed().await;
+    /// assert!(sender.send(5).is_err());
+    /// # });
+    /// ```
+    #[cfg(feature = "async")]
+    pub fn closed(&mut self) -> impl core::future::Future<Output = ()> + '_ {
+        Closed { sender: self }
+    }
+
+    /// Like [`Sender::closed`], but also completes once `token` is cancelled. Resolves to `true`
+    /// if the receiver was dropped or closed, and to `false` if the token was cancelled first.
+    ///
+    /// When the future completes or is dropped, the waker it registered in the channel is
+    /// dropped, so the task isn't woken up later when the receiver goes away.
+    ///
+    /// ```
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let token = tokio_util::sync::CancellationToken::new();
+    /// let (mut sender, _receiver) = oneshot::channel::<u32>();
+    /// token.cancel();
+    /// assert!(!sender.closed_or_cancelled(&token).await);
+    /// # });
+    /// ```
+    #[cfg(all(feature = "tokio-util", feature = "async", not(loom)))]
+    pub fn closed_or_cancelled<'a>(
+        &'a mut self,
+        token: &'a tokio_util::sync::CancellationToken,
+    ) -> impl core::future::Future<Output = bool> + 'a {
+        cancel::ClosedOrCancelled::new(self, token)
+    }
+
+    /// Polls for the associated [`Receiver`] to be dropped. This is the method backing
+    /// [`Sender::closed`], for usage in manual `Future` implementations.
+    ///
+    /// Returns `Poll::Ready(())` if the receiver has been dropped or closed. Otherwise the waker
+    /// in `cx` is registered to be woken up when that happens and `Poll::Pending` is returned.
+    /// Only the waker from the latest call is woken up.
+    #[cfg(feature = "async")]
+    pub fn poll_closed(&mut self, cx: &mut task::Context<'_>) -> Poll<()> {
+        if self.is_spent() {
+            return Poll::Ready(());
+        }
+        // SAFETY: The channel will not be freed while this method is still running.
+        let channel = unsafe { self.channel_ptr.as_ref() };
+
+        // ORDERING: we do not access anything the receiver has written, so relaxed is enough.
+        if matches!(channel.state.load(Relaxed), DISCONNECTED | RECEIVER_CLOSED) {
+            return Poll::Ready(());
+        }
+
+        // Take back the waker from a previous poll, if any, so it can be replaced.
+        // ORDERING: synchronize with our own previous write of the waker. Only the sender writes
+        // the waker, so nothing else must be synchronized.
+        match channel.closed_waker_state.compare_exchange(
+            CLOSED_WAKER_REGISTERED,
+            CLOSED_WAKER_NONE,
+            Acquire,
+            Relaxed,
+        ) {
+            // SAFETY: we changed the state from REGISTERED, so we have exclusive access to the
+            // waker again.
+            Ok(_) => unsafe { channel.drop_closed_waker() },
+            Err(CLOSED_WAKER_NONE) => (),
+            // The receiver is being dropped or closed right now.
+            Err(CLOSED_WAKER_CLOSING) => {
+                channel.wait_for_receiver_close();
+                return Poll::Ready(());
+            }
+            _ => unreachable!(),
+        }
+
+        // SAFETY: the state is NONE, so the receiver will not access the waker until we change it
+        unsafe { channel.write_closed_waker(cx.waker().clone()) };
+
+        // ORDERING: we use release ordering on success so the receiver can synchronize with
+        // our write of the waker.
+        match channel.closed_waker_state.compare_exchange(
+            CLOSED_WAKER_NONE,
+            CLOSED_WAKER_REGISTERED,
+            Release,
+            Relaxed,
+        ) {
+            Ok(_) => Poll::Pending,
+            // The receiver started dropping or closing while we wrote the waker. It never saw
+            // our waker, so we must drop it ourselves.
+            Err(CLOSED_WAKER_CLOSING) => {
+                // SAFETY: the receiver never accesses the waker in the CLOSING state.
+                unsafe { channel.drop_closed_waker() };
+                channel.wait_for_receiver_close();
+                Poll::Ready(())
+            }
             _ => unreachable!(),
         }
     }
+
+    /// Drops the waker registered by [`Sender::poll_closed`], if any, so the receiver won't wake
+    /// it when it goes away.
+    #[cfg(all(feature = "tokio-util", feature = "async", not(loom)))]
+    fn reclaim_closed_waker(&mut self) {
+        if self.is_spent() {
+            return;
+        }
+        // SAFETY: The channel will not be freed while this method is still running.
+        let channel = unsafe { self.channel_ptr.as_ref() };
+
+        // ORDERING: synchronize with our own previous write of the waker.
+        if channel
+            .closed_waker_state
+            .compare_exchange(CLOSED_WAKER_REGISTERED, CLOSED_WAKER_NONE, Acquire, Relaxed)
+            .is_ok()
+        {
+            // SAFETY: we changed the state from REGISTERED, so we have exclusive access to the
+            // waker again. In the CLOSING state the receiver takes care of it instead.
+            unsafe { channel.drop_closed_waker() };
+        }
+    }
+}
+
+/// The future returned from [`Sender::closed`].
+#[cfg(feature = "async")]
+struct Closed<'a, T> {
+    sender: &'a mut Sender<T>,
+}
+
+#[cfg(feature = "async")]
+impl<T> core::future::Future for Closed<'_, T> {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<()> {
+        self.sender.poll_closed(cx)
+    }
+}
+
+/// The future returned from [`Sender::send_async`].
+#[cfg(feature = "async")]
+struct SendAsync<T> {
+    inner: Option<(Sender<T>, T)>,
+}
+
+#[cfg(feature = "async")]
+impl<T> core::future::Future for SendAsync<T> {
+    type Output = Result<(), SendError<T>>;
+
+    fn poll(self: Pin<&mut Self>, _cx: &mut task::Context<'_>) -> Poll<Self::Output> {
+        // The message is moved out, never pinned, so the future is `Unpin` regardless of `T`.
+        let this = self.get_mut();
+        let (sender, message) = this
+            .inner
+            .take()
+            .expect("SendAsync polled after completion");
+        Poll::Ready(sender.send(message))
+    }
 }
 
+#[cfg(feature = "async")]
+impl<T> Unpin for SendAsync<T> {}
+
 impl<T> Drop for Sender<T> {
     fn drop(&mut self) {
+        if self.is_spent() {
+            return;
+        }
+
         // SAFETY: The receiver only ever frees the channel if we are in the MESSAGE or
         // DISCONNECTED states. If we are in the MESSAGE state, then we called
         // mem::forget(self), so we should not be in this function call. If we are in the
@@ -334,9 +1009,16 @@ impl<T> Drop for Sender<T> {
         // EMPTY ^ 001 = DISCONNECTED
         // RECEIVING ^ 001 = UNPARKING
         // DISCONNECTED ^ 001 = EMPTY (invalid), but this state is never observed
+        // RECEIVER_CLOSED ^ 001 = CLOSED_SENDER_DROPPED
         match channel.state.fetch_xor(0b001, Relaxed) {
             // The receiver has not started waiting, nor is it dropped.
-            EMPTY => (),
+            EMPTY => {
+                trace!(endpoint = "sender", woke = false, "sender dropped");
+            }
+            // The receiver has been closed but is still alive. It will free the channel.
+            RECEIVER_CLOSED => {
+                trace!(endpoint = "sender", woke = false, "sender dropped");
+            }
             // The receiver is waiting. Wake it up so it can detect that the channel disconnected.
             RECEIVING => {
                 // See comments in Sender::send
@@ -353,6 +1035,7 @@ impl<T> Drop for Sender<T> {
                 // The Acquire ordering above ensures that the write of the DISCONNECTED state
                 // happens-before unparking the receiver.
                 waker.unpark();
+                trace!(endpoint = "sender", woke = true, "sender dropped");
             }
             // The receiver was already dropped. We are responsible for freeing the channel.
             DISCONNECTED => {
@@ -361,6 +1044,7 @@ impl<T> Drop for Sender<T> {
                 // observed that the sender is still alive, meaning that we're responsible for
                 // freeing the channel allocation.
                 unsafe { dealloc(self.channel_ptr) };
+                trace!(endpoint = "sender", woke = false, "sender dropped");
             }
             _ => unreachable!(),
         }
@@ -377,34 +1061,100 @@ impl<T> Receiver<T> {
     /// If a message is returned, the channel is disconnected and any subsequent receive operation
     /// using this receiver will return an error.
     ///
-    /// This method is completely lock-free and wait-free. The only thing it does is an atomic
-    /// integer load of the channel state. And if there is a message in the channel it additionally
-    /// performs one atomic integer store and copies the message from the heap to the stack for
-    /// returning it.
+    /// This method is completely lock-free and wait-free. If there is no message it only does an
+    /// atomic load of the channel state, making it cheap to call in a hot polling loop. If there is
+    /// a message, an atomic compare and exchange moves the channel from having a message to having
+    /// it received, and the message is copied from the heap to the stack for returning it. Since
+    /// claiming the message is a single atomic operation, the message is never handed out twice
+    /// even if this is called from several threads at once. Prefer [`Receiver::try_recv_mut`] when
+    /// you have exclusive access to the receiver.
+    #[inline]
     pub fn try_recv(&self) -> Result<T, TryRecvError> {
         // SAFETY: The channel will not be freed while this method is still running.
         let channel = unsafe { self.channel_ptr.as_ref() };
 
+        // Check with a plain load first, so polling an empty channel does not pay for the read
+        // modify write of the exchange below, which has exclusive access to the cache line.
+        // ORDERING: no memory is accessed unless the exchange below succeeds, and it
+        // synchronizes on its own
+        let state = channel.state.load(Relaxed);
+        if state != MESSAGE {
+            return Err(Self::try_recv_error(state));
+        }
+
+        // ORDERING: we use acquire ordering to synchronize with the store of the message. Only
+        // one caller can win the exchange, so only one caller takes the message.
+        match channel
+            .state
+            .compare_exchange(MESSAGE, RECEIVED, Acquire, Acquire)
+        {
+            // SAFETY: we moved the channel out of the MESSAGE state so the message is present,
+            // and nobody else will take it.
+            Ok(_) => Ok(unsafe { channel.take_message() }),
+            Err(state) => Err(Self::try_recv_error(state)),
+        }
+    }
+
+    /// Checks if there is a message in the channel without blocking, like
+    /// [`Receiver::try_recv`].
+    ///
+    /// Borrowing the receiver exclusively means nothing else can be receiving at the same time,
+    /// so this method can claim the message with a plain atomic load and store instead of a
+    /// compare and exchange.
+    pub fn try_recv_mut(&mut self) -> Result<T, TryRecvError> {
+        // SAFETY: The channel will not be freed while this method is still running.
+        let channel = unsafe { self.channel_ptr.as_ref() };
+
         // ORDERING: we use acquire ordering to synchronize with the store of the message.
         match channel.state.load(Acquire) {
             MESSAGE => {
                 // It's okay to break up the load and store since once we're in the message state
-                // the sender no longer modifies the state
+                // the sender no longer modifies the state, and we have exclusive access to the
+                // receiver so no other receive call is racing with us.
                 // ORDERING: at this point the sender has done its job and is no longer active, so
                 // we don't need to make any side effects visible to it
-                channel.state.store(DISCONNECTED, Relaxed);
+                channel.state.store(RECEIVED, Relaxed);
 
                 // SAFETY: we are in the MESSAGE state so the message is present
                 Ok(unsafe { channel.take_message() })
             }
-            EMPTY => Err(TryRecvError::Empty),
-            DISCONNECTED => Err(TryRecvError::Disconnected),
+            state => Err(Self::try_recv_error(state)),
+        }
+    }
+
+    /// Maps a channel state without a message in it to the matching error of a non-blocking
+    /// receive.
+    fn try_recv_error(state: u8) -> TryRecvError {
+        match state {
+            EMPTY => TryRecvError::Empty,
+            DISCONNECTED | RECEIVED => TryRecvError::Disconnected,
+            // The receiver has been closed.
+            RECEIVER_CLOSED | CLOSED_SENDER_DROPPED | CLOSED_SEND_FAILED => {
+                TryRecvError::Disconnected
+            }
             #[cfg(feature = "async")]
-            RECEIVING | UNPARKING => Err(TryRecvError::Empty),
+            RECEIVING | UNPARKING => TryRecvError::Empty,
             _ => unreachable!(),
         }
     }
 
+    /// Consuming version of [`Receiver::try_recv`]. Checks if there is a message in the channel
+    /// without blocking, and hands the receiver back if there is not.
+    ///
+    /// Returns `Err(IntoTryRecvError::Empty(receiver))` if the sender is alive but has not
+    /// sent anything yet. On the other outcomes the receiver is consumed and, if the sender is
+    /// gone, the channel is freed, same as with [`Receiver::recv`].
+    pub fn into_try_recv(mut self) -> Result<T, IntoTryRecvError<T>> {
+        match self.try_recv_mut() {
+            Ok(message) => {
+                self.dealloc_received();
+                Ok(message)
+            }
+            Err(TryRecvError::Empty) => Err(IntoTryRecvError::Empty(self)),
+            Err(TryRecvError::Disconnected) => Err(IntoTryRecvError::Disconnected),
+        }
+    }
+
     /// Attempts to wait for a message from the [`Sender`], returning an error if the channel is
     /// disconnected.
     ///
@@ -440,16 +1190,18 @@ impl<T> Receiver<T> {
         // channel to us, so channel_ptr is valid
         let channel = unsafe { channel_ptr.as_ref() };
 
+        // Give a sender that is about to send the chance to do so before we go through the
+        // expensive parking below.
+        channel.spin_while_empty(None);
+
         // ORDERING: we use acquire ordering to synchronize with the write of the message in the
         // case that it's available
         match channel.state.load(Acquire) {
             // The sender is alive but has not sent anything yet. We prepare to park.
             EMPTY => {
-                // Conditionally add a delay here to help the tests trigger the edge cases where
-                // the sender manages to be dropped or send something before we are able to store
-                // our waker object in the channel.
-                #[cfg(oneshot_test_delay)]
-                std::thread::sleep(std::time::Duration::from_millis(10));
+                // Lets the tests trigger the edge cases where the sender manages to be dropped or
+                // send something before we are able to store our waker object in the channel.
+                test_hooks::pre_park_hook();
 
                 // Write our waker instance to the channel.
                 // SAFETY: we are not yet in the RECEIVING state, meaning that the sender will not
@@ -467,6 +1219,7 @@ impl<T> Receiver<T> {
                 match channel.state.swap(RECEIVING, Release) {
                     // We stored our waker, now we park until the sender has changed the state
                     EMPTY => loop {
+                        trace!(endpoint = "receiver", "receiver parked");
                         thread::park();
 
                         // ORDERING: synchronize with the write of the message
@@ -488,7 +1241,7 @@ impl<T> Receiver<T> {
                                 // its drop implementation if we're receiving
                                 unsafe { dealloc(channel_ptr) };
 
-                                break Err(RecvError);
+                                break Err(RecvError::Disconnected);
                             }
                             // State did not change, spurious wakeup, park again.
                             RECEIVING | UNPARKING => (),
@@ -527,7 +1280,7 @@ impl<T> Receiver<T> {
                         // empty to disconnected so we need to free the allocation
                         unsafe { dealloc(channel_ptr) };
 
-                        Err(RecvError)
+                        Err(RecvError::Disconnected)
                     }
                     _ => unreachable!(),
                 }
@@ -544,12 +1297,21 @@ impl<T> Receiver<T> {
                 Ok(message)
             }
             // The sender was dropped before sending anything, or we already received the message.
-            DISCONNECTED => {
+            state @ (DISCONNECTED | RECEIVED) => {
                 // SAFETY: the sender does not deallocate the channel if it switches from empty to
                 // disconnected so we need to free the allocation
                 unsafe { dealloc(channel_ptr) };
 
-                Err(RecvError)
+                if state == RECEIVED {
+                    Err(RecvError::AlreadyReceived)
+                } else {
+                    Err(RecvError::Disconnected)
+                }
+            }
+            // The receiver has been closed. Clean up just like when dropping the receiver.
+            RECEIVER_CLOSED | CLOSED_SENDER_DROPPED | CLOSED_SEND_FAILED => {
+                mem::drop(Receiver { channel_ptr });
+                Err(RecvError::Disconnected)
             }
             // The receiver must have been `Future::poll`ed prior to this call.
             #[cfg(feature = "async")]
@@ -558,177 +1320,1591 @@ impl<T> Receiver<T> {
         }
     }
 
-    /// Attempts to wait for a message from the [`Sender`], returning an error if the channel is
-    /// disconnected. This is a non consuming version of [`Receiver::recv`], but with a bit
-    /// worse performance. Prefer `[`Receiver::recv`]` if your code allows consuming the receiver.
-    ///
-    /// If a message is returned, the channel is disconnected and any subsequent receive operation
-    /// using this receiver will return an error.
+    /// Same as [`Receiver::recv`], under the name tokio's oneshot receiver uses for its blocking
+    /// receive. Asynchronous code should `.await` the receiver instead, it implements `Future`
+    /// when the `async` feature is enabled.
     ///
     /// # Panics
     ///
     /// Panics if called after this receiver has been polled asynchronously.
     #[cfg(feature = "std")]
-    pub fn recv_ref(&self) -> Result<T, RecvError> {
-        self.start_recv_ref(RecvError, |channel| {
-            loop {
-                thread::park();
+    #[inline]
+    pub fn blocking_recv(self) -> Result<T, RecvError> {
+        self.recv()
+    }
+
+    /// Receives the message by calling `f` with it, without waiting for it. Meant for event loops
+    /// that can neither block nor poll a future.
+    ///
+    /// If the channel is already done, `f` is called right away on this thread with what
+    /// [`Receiver::recv`] would return. Otherwise `f` is stored in the channel, and called on the
+    /// thread of the [`Sender`] with the message when it sends, or with
+    /// [`RecvError::Disconnected`] when it is dropped. `f` is called exactly once, unless the
+    /// sender is leaked, which leaks `f` together with the channel.
+    ///
+    /// A panic in `f` while sending unwinds out of [`Sender::send`] or the drop of the sender.
+    /// The channel has been freed at that point.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called after this receiver has been polled asynchronously.
+    #[cfg(feature = "std")]
+    pub fn on_receive<F>(self, f: F)
+    where
+        T: Send + 'static,
+        F: FnOnce(Result<T, RecvError>) + Send + 'static,
+    {
+        // SAFETY: the receiver is alive, so the channel is valid. It stays valid until we have
+        // handed the receiver over to the sender below.
+        let channel = unsafe { &*self.channel_ptr.as_ptr() };
+
+        // ORDERING: `recv` synchronizes with the write of the message if there is one
+        match channel.state.load(Relaxed) {
+            EMPTY => (),
+            // The receiver must have been `Future::poll`ed prior to this call.
+            #[cfg(feature = "async")]
+            RECEIVING | UNPARKING => panic!("{}", RECEIVER_USED_SYNC_AND_ASYNC_ERROR),
+            // The channel is done, so `recv` returns without blocking.
+            _ => return f(self.recv()),
+        }
+
+        // The callback receives with `recv`, which returns right away since the sender only runs
+        // the callback after it has changed the state to MESSAGE or DISCONNECTED.
+        let receiver = self;
+        let callback = ReceiverWaker::callback(move || f(receiver.recv()));
+
+        // SAFETY: we are not yet in the RECEIVING state, meaning that the sender will not try to
+        // access the waker until it sees the state set to RECEIVING below
+        unsafe { channel.write_waker(callback) };
+
+        // ORDERING: we use release ordering on success so the sender can synchronize with our
+        // write of the waker. On failure `recv` synchronizes with the write of the message.
+        match channel
+            .state
+            .compare_exchange(EMPTY, RECEIVING, Release, Relaxed)
+        {
+            // The sender now owns the callback, and the receiver in it. We must not touch the
+            // channel again, the sender might already have freed it.
+            Ok(_) => (),
+            // The sender sent or was dropped while we stored the callback. It did not see the
+            // RECEIVING state, so the callback is still ours, and we run it here.
+            Err(MESSAGE | DISCONNECTED) => {
+                // SAFETY: we wrote the waker above and the sender never took it
+                unsafe { channel.take_waker() }.unpark();
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    /// Registers `on_wake` to be called once the channel is done, for executors that are not
+    /// built on [`Waker`](core::task::Waker). The [`Sender`] calls it, on its own thread, when it
+    /// sends the message or is dropped. If that has already happened, `on_wake` is called right
+    /// away on this thread. Afterwards [`Receiver::try_recv`] returns the outcome without
+    /// blocking.
+    ///
+    /// Unlike [`Receiver::on_receive`] this does not consume the receiver. The callback takes the
+    /// place of the waker of a poll, so it replaces the waker or callback of an earlier poll or
+    /// `park_with`, which is then dropped without being called. Like after a poll, only the
+    /// receive methods that borrow the receiver can be used until the callback has run.
+    ///
+    /// ```
+    /// let (sender, receiver) = oneshot::channel::<u32>();
+    /// let (woken_sender, woken) = std::sync::mpsc::channel();
+    /// receiver.park_with(move || woken_sender.send(()).unwrap());
+    /// sender.send(5).unwrap();
+    /// woken.recv().unwrap();
+    /// assert_eq!(receiver.try_recv(), Ok(5));
+    /// ```
+    #[cfg(all(feature = "std", feature = "async"))]
+    pub fn park_with<F: FnOnce() + Send + 'static>(&self, on_wake: F) {
+        // SAFETY: The channel will not be freed while this method is still running.
+        let channel = unsafe { self.channel_ptr.as_ref() };
+
+        // Take back the waker of an earlier poll or `park_with`, if any.
+        // SAFETY: we are the receiver, and we are not being polled, since we hold `&self`.
+        unsafe { channel.reclaim_async_waker() };
+
+        // ORDERING: the callback synchronizes on its own when receiving
+        match channel.state.load(Relaxed) {
+            EMPTY => (),
+            // The channel is done, the receive after the callback returns right away.
+            _ => return on_wake(),
+        }
+
+        // SAFETY: we are not in the RECEIVING state, meaning that the sender will not try to
+        // access the waker until it sees the state set to RECEIVING below
+        unsafe { channel.write_waker(ReceiverWaker::callback(on_wake)) };
+
+        // ORDERING: we use release ordering on success so the sender can synchronize with our
+        // write of the waker.
+        match channel
+            .state
+            .compare_exchange(EMPTY, RECEIVING, Release, Relaxed)
+        {
+            // The sender now calls the callback when it's done.
+            Ok(_) => (),
+            // The sender sent or was dropped while we stored the callback. It did not see the
+            // RECEIVING state, so the callback is still ours, and we run it here.
+            Err(MESSAGE | DISCONNECTED) => {
+                // SAFETY: we wrote the waker above and the sender never took it
+                unsafe { channel.take_waker() }.unpark();
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    /// Like [`Receiver::recv`], but busy waits for the message by checking the channel up to
+    /// `spins` times before parking the thread. This can lower the latency when the message is
+    /// expected to be sent very soon, at the cost of burning CPU cycles while spinning. Spinning
+    /// only helps if the sender is running on another CPU core at the same time, otherwise it
+    /// just delays the sender.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called after this receiver has been polled asynchronously.
+    #[cfg(feature = "std")]
+    pub fn recv_spin(self, spins: u32) -> Result<T, RecvError> {
+        // SAFETY: the existence of the `self` parameter serves as a certificate that the receiver
+        // is still alive, meaning that even if the sender was dropped then it would have observed
+        // the fact that we're still alive and left the responsibility of deallocating the
+        // channel to us, so `self.channel` is valid
+        let channel = unsafe { self.channel_ptr.as_ref() };
+
+        for _ in 0..spins {
+            // ORDERING: `recv` synchronizes with the sender, this is only a hint for when to stop
+            // spinning.
+            if channel.state.load(Relaxed) != EMPTY {
+                break;
+            }
+            hint::spin_loop();
+        }
+        self.recv()
+    }
+
+    /// Busy waits for the message without ever parking the thread. Returns:
+    ///  * `Ok(message)` if a message arrived.
+    ///  * `Err(Timeout)` if `max_spin` is given, and no message arrived before it elapsed.
+    ///  * `Err(Disconnected)` if the sender was dropped before sending anything or if the message
+    ///    has already been extracted by a previous receive call.
+    ///
+    /// No waker is stored in the channel, so neither this thread nor the sender ever makes a
+    /// system call to park or unpark. This is meant for latency critical threads pinned to their
+    /// own CPU core. Busy waiting burns CPU cycles the whole time, and if the sender has to run
+    /// on the same core it only delays the message. With `max_spin` set to `None` this spins
+    /// until the sender sends or is dropped.
+    #[cfg(feature = "std")]
+    pub fn recv_busy(&self, max_spin: Option<Duration>) -> Result<T, RecvTimeoutError> {
+        // A budget too large for `Instant` to represent is the same as no budget.
+        let deadline = max_spin.and_then(|max_spin| Instant::now().checked_add(max_spin));
+        loop {
+            match self.try_recv() {
+                Ok(message) => return Ok(message),
+                Err(TryRecvError::Disconnected) => return Err(RecvTimeoutError::Disconnected),
+                Err(TryRecvError::Empty) => {
+                    if matches!(deadline, Some(deadline) if Instant::now() >= deadline) {
+                        return Err(RecvTimeoutError::Timeout);
+                    }
+                    hint::spin_loop();
+                }
+            }
+        }
+    }
+
+    /// Like [`Receiver::recv`], but also returns how long the call waited. The time is measured
+    /// from entering the call until the message was received or the sender was found to be
+    /// dropped, so it is reported on both outcomes. If the message is already in the channel,
+    /// the duration is close to zero.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called after this receiver has been polled asynchronously.
+    #[cfg(feature = "std")]
+    pub fn recv_timed(self) -> (Result<T, RecvError>, Duration) {
+        let start = Instant::now();
+        let result = self.recv();
+        (result, start.elapsed())
+    }
+
+    /// Like [`Receiver::recv`], but writes the message directly into `slot` instead of
+    /// returning it. Avoids moving a large message via the stack.
+    ///
+    /// On `Ok(())` the message has been written to `slot`, so it is initialized, and the caller
+    /// is responsible for dropping the message. Any value previously in `slot` is overwritten
+    /// without being dropped. On an error `slot` is left untouched.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called after this receiver has been polled asynchronously.
+    #[cfg(feature = "std")]
+    pub fn recv_into(mut self, slot: &mut MaybeUninit<T>) -> Result<(), RecvError> {
+        let message: *const T = self.wait_peek()?;
+
+        // SAFETY: the receiver is still alive, so the channel is valid
+        let channel = unsafe { self.channel_ptr.as_ref() };
+
+        // SAFETY: `wait_peek` observed the MESSAGE state, so the message is initialized. The
+        // state is changed to RECEIVED and the channel freed without dropping the message below,
+        // so ownership is moved to `slot`.
+        unsafe { ptr::copy_nonoverlapping(message, slot.as_mut_ptr(), 1) };
+        // SAFETY: the message was just moved out of the channel
+        unsafe { channel.notify_send_ack(ACK_TAKEN) };
+
+        // ORDERING: the sender is gone, this update only needs to be visible to us
+        channel.state.store(RECEIVED, Relaxed);
+
+        self.dealloc_received();
+        Ok(())
+    }
+
+    /// Blocks until a message arrives like [`Receiver::recv_mut`], and receives it by calling
+    /// `f` with a reference to it. The message is then dropped in place, without ever being
+    /// moved out of the channel. Useful for extracting a small part of a large message.
+    ///
+    /// Returns what `f` returns. On an error `f` is not called. If `f` panics the message is
+    /// left in the channel, unreceived.
+    ///
+    /// ```
+    /// let (sender, mut receiver) = oneshot::channel::<[u64; 1024]>();
+    /// sender.send([7; 1024]).unwrap();
+    /// assert_eq!(receiver.recv_map_ref(|message| message[0]), Ok(7));
+    /// assert!(receiver.try_recv().is_err());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if called after this receiver has been polled asynchronously.
+    #[cfg(feature = "std")]
+    pub fn recv_map_ref<R>(&mut self, f: impl FnOnce(&T) -> R) -> Result<R, RecvError> {
+        let result = f(self.wait_peek()?);
+
+        // SAFETY: the receiver is still alive, so the channel is valid
+        let channel = unsafe { self.channel_ptr.as_ref() };
+
+        // ORDERING: the sender is gone, this update only needs to be visible to us. The state is
+        // changed before dropping the message, so a panicking destructor can't make the receiver
+        // drop it a second time.
+        channel.state.store(RECEIVED, Relaxed);
+        // SAFETY: the sender is gone, and the receiver is borrowed by us
+        unsafe { channel.notify_send_ack(ACK_TAKEN) };
+        // SAFETY: `wait_peek` observed the MESSAGE state, so the message is initialized, and the
+        // RECEIVED state makes sure nothing else accesses it again
+        unsafe { channel.drop_message() };
+        Ok(result)
+    }
+
+    /// Attempts to wait for a message from the [`Sender`], returning an error if the channel is
+    /// disconnected. This is a non consuming version of [`Receiver::recv`], but with a bit
+    /// worse performance. Prefer `[`Receiver::recv`]` if your code allows consuming the receiver.
+    ///
+    /// If a message is returned, the channel is disconnected and any subsequent receive operation
+    /// using this receiver will return an error. The channel is freed when the receiver is
+    /// dropped.
+    ///
+    /// If this receiver has been polled asynchronously before, the waker stored by that poll is
+    /// replaced with one for the current thread. The polling task is then no longer woken up when
+    /// the message arrives, and has to poll again if this call returns without it.
+    #[cfg(feature = "std")]
+    pub fn recv_mut(&mut self) -> Result<T, RecvError> {
+        self.recv_by_ref()
+    }
+
+    /// Shared reference version of [`Receiver::recv_mut`].
+    ///
+    /// Works after polling, see [`Receiver::recv_mut`].
+    #[cfg(feature = "std")]
+    #[deprecated(note = "use `Receiver::recv_mut` instead, which borrows the receiver exclusively")]
+    pub fn recv_ref(&self) -> Result<T, RecvError> {
+        self.recv_by_ref()
+    }
+
+    /// The implementation of [`Receiver::recv_mut`], also used by the time limited receive
+    /// methods when the deadline can't be represented.
+    #[cfg(feature = "std")]
+    fn recv_by_ref(&self) -> Result<T, RecvError> {
+        // SAFETY: the receiver is alive, so the channel is valid
+        let channel = unsafe { self.channel_ptr.as_ref() };
+        // See `recv`.
+        channel.spin_while_empty(None);
+
+        self.start_recv_ref(
+            RecvError::Disconnected,
+            RecvError::AlreadyReceived,
+            |channel| {
+                loop {
+                    trace!(endpoint = "receiver", "receiver parked");
+                    thread::park();
+
+                    // ORDERING: we use acquire ordering to synchronize with the write of the message
+                    match channel.state.load(Acquire) {
+                        // The sender sent the message while we were parked.
+                        // We take the message and mark the channel disconnected.
+                        MESSAGE => {
+                            // ORDERING: the sender is inactive at this point so we don't need to make
+                            // any reads or writes visible to the sending thread
+                            channel.state.store(RECEIVED, Relaxed);
+
+                            // SAFETY: we were just in the message state so the message is valid
+                            break Ok(unsafe { channel.take_message() });
+                        }
+                        // The sender was dropped while we were parked.
+                        DISCONNECTED => break Err(RecvError::Disconnected),
+                        // State did not change, spurious wakeup, park again.
+                        RECEIVING | UNPARKING => (),
+                        _ => unreachable!(),
+                    }
+                }
+            },
+        )
+    }
+
+    /// Like [`Receiver::recv`], but will not block longer than `timeout`. Returns:
+    ///  * `Ok(message)` if there was a message in the channel before the timeout was reached.
+    ///  * `Err(Timeout)` if no message arrived on the channel before the timeout was reached.
+    ///  * `Err(Disconnected)` if the sender was dropped before sending anything or if the message
+    ///    has already been extracted by a previous receive call.
+    ///
+    /// If a message is returned, the channel is disconnected and any subsequent receive operation
+    /// using this receiver will return an error.
+    ///
+    /// If the supplied `timeout` is so large that Rust's `Instant` type can't represent this point
+    /// in the future this falls back to an indefinitely blocking receive operation.
+    ///
+    /// Works after polling, see [`Receiver::recv_mut`].
+    #[cfg(feature = "std")]
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<T, RecvTimeoutError> {
+        match Instant::now().checked_add(timeout) {
+            Some(deadline) => self.recv_deadline(deadline),
+            None => self
+                .recv_by_ref()
+                .map_err(|_| RecvTimeoutError::Disconnected),
+        }
+    }
+
+    /// Like [`Receiver::recv_timeout`], but also returns how long the call waited for the
+    /// message, or for the sender to be dropped, or for the timeout. See
+    /// [`Receiver::recv_timed`].
+    ///
+    /// Works after polling, see [`Receiver::recv_mut`].
+    #[cfg(feature = "std")]
+    pub fn recv_timeout_timed(&self, timeout: Duration) -> (Result<T, RecvTimeoutError>, Duration) {
+        let start = Instant::now();
+        let result = self.recv_timeout(timeout);
+        (result, start.elapsed())
+    }
+
+    /// Like [`Receiver::recv`], but will not block longer than until `deadline`. Returns:
+    ///  * `Ok(message)` if there was a message in the channel before the deadline was reached.
+    ///  * `Err(Timeout)` if no message arrived on the channel before the deadline was reached.
+    ///  * `Err(Disconnected)` if the sender was dropped before sending anything or if the message
+    ///    has already been extracted by a previous receive call.
+    ///
+    /// If a message is returned, the channel is disconnected and any subsequent receive operation
+    /// using this receiver will return an error.
+    ///
+    /// Works after polling, see [`Receiver::recv_mut`].
+    #[cfg(feature = "std")]
+    pub fn recv_deadline(&self, deadline: Instant) -> Result<T, RecvTimeoutError> {
+        /// # Safety
+        ///
+        /// If the sender is unparking us after a message send, the message must already have been
+        /// written to the channel and an acquire memory barrier issued before calling this function
+        #[cold]
+        unsafe fn wait_for_unpark<T>(channel: &Channel<T>) -> Result<T, RecvTimeoutError> {
+            loop {
+                trace!(endpoint = "receiver", "receiver parked");
+                thread::park();
+
+                // ORDERING: The callee has already synchronized with any message write
+                match channel.state.load(Relaxed) {
+                    MESSAGE => {
+                        // ORDERING: the sender has been dropped, so this update only
+                        // needs to be visible to us
+                        channel.state.store(RECEIVED, Relaxed);
+                        break Ok(channel.take_message());
+                    }
+                    DISCONNECTED => break Err(RecvTimeoutError::Disconnected),
+                    // The sender is still unparking us. We continue on the empty state here since
+                    // the current implementation eagerly sets the state to EMPTY upon timeout.
+                    EMPTY => (),
+                    _ => unreachable!(),
+                }
+            }
+        }
+
+        // SAFETY: the receiver is alive, so the channel is valid
+        let channel = unsafe { self.channel_ptr.as_ref() };
+
+        // Nothing to wait for if the deadline has passed. Don't store our waker just to take it
+        // back right away. The other states are handled without waiting below.
+        // ORDERING: we don't access the message if we return here
+        if channel.state.load(Relaxed) == EMPTY && deadline <= test_hooks::now() {
+            return Err(RecvTimeoutError::Timeout);
+        }
+
+        // See `recv`.
+        channel.spin_while_empty(Some(deadline));
+
+        self.start_recv_ref(
+            RecvTimeoutError::Disconnected,
+            RecvTimeoutError::Disconnected,
+            |channel| {
+                loop {
+                    match deadline.checked_duration_since(test_hooks::now()) {
+                        Some(timeout) => {
+                            trace!(endpoint = "receiver", ?timeout, "receiver parked");
+                            thread::park_timeout(timeout);
+
+                            // ORDERING: synchronize with the write of the message
+                            match channel.state.load(Acquire) {
+                                // The sender sent the message while we were parked.
+                                MESSAGE => {
+                                    // ORDERING: the sender has been `mem::forget`-ed so this update
+                                    // only needs to be visible to us.
+                                    channel.state.store(RECEIVED, Relaxed);
+
+                                    // SAFETY: we either are in the message state or were just in the
+                                    // message state
+                                    break Ok(unsafe { channel.take_message() });
+                                }
+                                // The sender was dropped while we were parked.
+                                DISCONNECTED => break Err(RecvTimeoutError::Disconnected),
+                                // State did not change, spurious wakeup, park again.
+                                RECEIVING | UNPARKING => (),
+                                _ => unreachable!(),
+                            }
+                        }
+                        None => {
+                            // ORDERING: synchronize with the write of the message
+                            match channel.state.swap(EMPTY, Acquire) {
+                                // We reached the end of the timeout without receiving a message
+                                RECEIVING => {
+                                    // SAFETY: we were in the receiving state and are now in the empty
+                                    // state, so the sender has not and will not try to read the waker,
+                                    // so we have exclusive access to drop it.
+                                    unsafe { channel.drop_waker() };
+
+                                    break Err(RecvTimeoutError::Timeout);
+                                }
+                                // The sender sent the message while we were parked.
+                                MESSAGE => {
+                                    // Same safety and ordering as the Some branch
+
+                                    channel.state.store(RECEIVED, Relaxed);
+                                    break Ok(unsafe { channel.take_message() });
+                                }
+                                // The sender was dropped while we were parked.
+                                DISCONNECTED => {
+                                    // ORDERING: we were originally in the disconnected state meaning
+                                    // that the sender is inactive and no longer observing the state,
+                                    // so we only need to change it back to DISCONNECTED for if the
+                                    // receiver is dropped or a recv* method is called again
+                                    channel.state.store(DISCONNECTED, Relaxed);
+
+                                    break Err(RecvTimeoutError::Disconnected);
+                                }
+                                // The sender sent the message and started unparking us
+                                UNPARKING => {
+                                    // We were in the UNPARKING state and are now in the EMPTY state.
+                                    // We wait to be properly unparked and to observe if the sender
+                                    // sets MESSAGE or DISCONNECTED state.
+                                    // SAFETY: The load above has synchronized with any message write.
+                                    break unsafe { wait_for_unpark(channel) };
+                                }
+                                _ => unreachable!(),
+                            }
+                        }
+                    }
+                }
+            },
+        )
+    }
 
-                // ORDERING: we use acquire ordering to synchronize with the write of the message
+    /// Checks the channel for the message up to `tries` times without blocking, and then blocks
+    /// like [`Receiver::recv_deadline`] until `deadline`. Replaces a loop of
+    /// [`Receiver::try_recv`] calls followed by a blocking receive.
+    ///
+    /// The checks are plain atomic loads, busy waiting between them. Only the final receive
+    /// takes the message, so the channel state is not written to while checking.
+    ///
+    /// ```
+    /// use std::time::{Duration, Instant};
+    ///
+    /// let (sender, receiver) = oneshot::channel::<u32>();
+    /// sender.send(5).unwrap();
+    /// let deadline = Instant::now() + Duration::from_millis(10);
+    /// assert_eq!(receiver.recv_try_then_deadline(100, deadline), Ok(5));
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn recv_try_then_deadline(
+        &self,
+        tries: u32,
+        deadline: Instant,
+    ) -> Result<T, RecvTimeoutError> {
+        // SAFETY: the receiver is alive, so the channel is valid
+        let channel = unsafe { self.channel_ptr.as_ref() };
+
+        for _ in 0..tries {
+            // ORDERING: `recv_deadline` synchronizes with the sender, this is only a hint for when
+            // to stop checking.
+            if channel.state.load(Relaxed) != EMPTY {
+                break;
+            }
+            hint::spin_loop();
+        }
+        self.recv_deadline(deadline)
+    }
+
+    /// Consuming version of [`Receiver::recv_timeout`]. Hands the receiver back on timeout, so
+    /// the receive can be retried. On the other outcomes the receiver is consumed and, if the
+    /// sender is gone, the channel is freed, same as with [`Receiver::recv`].
+    ///
+    /// Works after polling, see [`Receiver::recv_mut`].
+    #[cfg(feature = "std")]
+    pub fn recv_timeout_owned(self, timeout: Duration) -> Result<T, IntoRecvTimeoutError<T>> {
+        match Instant::now().checked_add(timeout) {
+            Some(deadline) => self.recv_deadline_owned(deadline),
+            // Like `recv_timeout`, block by reference so a polled receiver is taken over too.
+            None => match self.recv_by_ref() {
+                Ok(message) => {
+                    self.dealloc_received();
+                    Ok(message)
+                }
+                Err(_) => Err(IntoRecvTimeoutError::Disconnected),
+            },
+        }
+    }
+
+    /// Consuming version of [`Receiver::recv_deadline`]. Hands the receiver back on timeout, so
+    /// the receive can be retried. On the other outcomes the receiver is consumed and, if the
+    /// sender is gone, the channel is freed, same as with [`Receiver::recv`].
+    ///
+    /// Works after polling, see [`Receiver::recv_mut`].
+    #[cfg(feature = "std")]
+    pub fn recv_deadline_owned(self, deadline: Instant) -> Result<T, IntoRecvTimeoutError<T>> {
+        match self.recv_deadline(deadline) {
+            Ok(message) => {
+                self.dealloc_received();
+                Ok(message)
+            }
+            Err(RecvTimeoutError::Timeout) => Err(IntoRecvTimeoutError::Timeout(self)),
+            Err(RecvTimeoutError::Disconnected) => Err(IntoRecvTimeoutError::Disconnected),
+        }
+    }
+
+    /// Blocks until a message arrives, the sender is dropped, or `cancel` fires. `cancel` fires
+    /// when its sender sends the `()` message or is dropped. Returns:
+    ///  * `Ok(message)` if a message arrived. If `cancel` fires at the same time the message
+    ///    still wins.
+    ///  * `Err(Cancelled)` if `cancel` fired before a message arrived. This receiver can then
+    ///    still be used to receive.
+    ///  * `Err(Disconnected)` if the sender was dropped before sending anything or if the message
+    ///    has already been extracted by a previous receive call.
+    ///
+    /// Any message on `cancel` is left in that channel, so the same `cancel` receiver can be
+    /// used with many calls and they all return immediately once it has fired. The thread only
+    /// has to be woken up once, by whichever of the two senders is first.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called after `cancel` has been polled asynchronously. If this receiver has been
+    /// polled before, the waker of that poll is replaced like in [`Receiver::recv_mut`].
+    #[cfg(feature = "std")]
+    pub fn recv_or_cancel(&self, cancel: &Receiver<()>) -> Result<T, RecvOrCancelError> {
+        // SAFETY: the existence of the `cancel` parameter serves as a certificate that the
+        // cancel receiver is still alive, so its channel is valid
+        let cancel_channel = unsafe { cancel.channel_ptr.as_ref() };
+
+        // ORDERING: we never access the message of the cancel channel
+        match cancel_channel.state.load(Relaxed) {
+            EMPTY => (),
+            // The cancel receiver must have been `Future::poll`ed prior to this call.
+            #[cfg(feature = "async")]
+            RECEIVING | UNPARKING => panic!("{}", RECEIVER_USED_SYNC_AND_ASYNC_ERROR),
+            // Already cancelled. Return a message that's already there, but don't wait for one.
+            _ => {
+                return self.try_recv().map_err(|error| match error {
+                    TryRecvError::Empty => RecvOrCancelError::Cancelled,
+                    TryRecvError::Disconnected => RecvOrCancelError::Disconnected,
+                })
+            }
+        }
+
+        self.start_recv_ref(
+            RecvOrCancelError::Disconnected,
+            RecvOrCancelError::Disconnected,
+            |channel| {
+                // Our waker is now stored in the message channel. Store one in the cancel channel
+                // as well. See `start_recv_ref` for safety and ordering.
+                unsafe { cancel_channel.write_waker(ReceiverWaker::current_thread()) };
+                let mut cancelled = match cancel_channel
+                    .state
+                    .compare_exchange(EMPTY, RECEIVING, Release, Relaxed)
+                {
+                    Ok(_) => false,
+                    // The cancel sender sent or was dropped while we prepared to park. It did not
+                    // take our waker.
+                    Err(MESSAGE | DISCONNECTED) => {
+                        // SAFETY: we wrote the waker above and the sender never saw it
+                        unsafe { cancel_channel.drop_waker() };
+                        true
+                    }
+                    _ => unreachable!(),
+                };
+
+                loop {
+                    if !cancelled {
+                        thread::park();
+                    }
+
+                    // ORDERING: synchronize with the write of the message
+                    match channel.state.load(Acquire) {
+                        // The sender sent the message, or was dropped. Deregister from the cancel
+                        // channel before returning.
+                        state @ (MESSAGE | DISCONNECTED) => {
+                            if !cancelled {
+                                // SAFETY: our waker was stored in the cancel channel
+                                unsafe { cancel_channel.remove_thread_waker() };
+                            }
+                            return if state == MESSAGE {
+                                // ORDERING: the sender is gone, so this update only needs to be
+                                // visible to us
+                                channel.state.store(RECEIVED, Relaxed);
+
+                                // SAFETY: we are in the message state so the message is valid
+                                Ok(unsafe { channel.take_message() })
+                            } else {
+                                Err(RecvOrCancelError::Disconnected)
+                            };
+                        }
+                        RECEIVING | UNPARKING => (),
+                        _ => unreachable!(),
+                    }
+
+                    // ORDERING: we never access the message of the cancel channel
+                    if !cancelled && cancel_channel.state.load(Relaxed) != RECEIVING {
+                        // SAFETY: our waker was stored in the cancel channel
+                        unsafe { cancel_channel.remove_thread_waker() };
+                        cancelled = true;
+                    }
+
+                    if cancelled {
+                        // SAFETY: our waker is stored in the message channel, and it is in the
+                        // RECEIVING state or was switched from it by the sender
+                        return match unsafe { channel.remove_thread_waker() } {
+                            MESSAGE => {
+                                // ORDERING: `remove_thread_waker` synchronized with the message
+                                // write, and the sender is gone
+                                channel.state.store(RECEIVED, Relaxed);
+
+                                // SAFETY: we are in the message state so the message is valid
+                                Ok(unsafe { channel.take_message() })
+                            }
+                            DISCONNECTED => Err(RecvOrCancelError::Disconnected),
+                            _ => Err(RecvOrCancelError::Cancelled),
+                        };
+                    }
+                }
+            },
+        )
+    }
+
+    /// Like [`Receiver::recv_mut`], but can be interrupted by another thread. Returns:
+    ///  * `Ok(message)` if a message arrived. A message that is already in the channel wins
+    ///    over a set `interrupt` flag.
+    ///  * `Err(Interrupted)` if `interrupt` was set before a message arrived. This receiver can
+    ///    then still be used to receive.
+    ///  * `Err(Disconnected)` if the sender was dropped before sending anything or if the message
+    ///    has already been extracted by a previous receive call.
+    ///
+    /// The flag is checked before parking and after every unpark of this thread. To interrupt
+    /// the receive, set `interrupt` to `true` and then [`unpark`](std::thread::Thread::unpark) the
+    /// receiving thread. This method never clears the flag.
+    ///
+    /// This can be used to stop waiting on a Unix signal, such as `SIGINT`. Set the flag from
+    /// the signal handler, but do the unpark from another thread that watches the flag, since
+    /// unparking is not async-signal-safe.
+    ///
+    /// Works after polling, see [`Receiver::recv_mut`].
+    #[cfg(feature = "std")]
+    pub fn recv_interruptible(&self, interrupt: &AtomicBool) -> Result<T, RecvInterruptedError> {
+        self.start_recv_ref(
+            RecvInterruptedError::Disconnected,
+            RecvInterruptedError::Disconnected,
+            |channel| loop {
+                // ORDERING: synchronize with whatever the interrupting thread did before setting
+                // the flag
+                if interrupt.load(Acquire) {
+                    // SAFETY: our waker is stored in the channel, and it is in the RECEIVING state
+                    // or was switched from it by the sender
+                    return match unsafe { channel.remove_thread_waker() } {
+                        MESSAGE => {
+                            // ORDERING: `remove_thread_waker` synchronized with the message write,
+                            // and the sender is gone
+                            channel.state.store(RECEIVED, Relaxed);
+
+                            // SAFETY: we are in the message state so the message is valid
+                            Ok(unsafe { channel.take_message() })
+                        }
+                        DISCONNECTED => Err(RecvInterruptedError::Disconnected),
+                        _ => Err(RecvInterruptedError::Interrupted),
+                    };
+                }
+
+                thread::park();
+
+                // ORDERING: synchronize with the write of the message
                 match channel.state.load(Acquire) {
-                    // The sender sent the message while we were parked.
-                    // We take the message and mark the channel disconnected.
                     MESSAGE => {
                         // ORDERING: the sender is inactive at this point so we don't need to make
                         // any reads or writes visible to the sending thread
-                        channel.state.store(DISCONNECTED, Relaxed);
+                        channel.state.store(RECEIVED, Relaxed);
 
                         // SAFETY: we were just in the message state so the message is valid
-                        break Ok(unsafe { channel.take_message() });
+                        return Ok(unsafe { channel.take_message() });
+                    }
+                    DISCONNECTED => return Err(RecvInterruptedError::Disconnected),
+                    // Spurious wakeup, or unparked to check the interrupt flag.
+                    RECEIVING | UNPARKING => (),
+                    _ => unreachable!(),
+                }
+            },
+        )
+    }
+
+    /// Returns true if the associated [`Sender`] was dropped before sending a message. Or if
+    /// the message has already been received. Or if the receiver has been closed with
+    /// [`Receiver::close`].
+    ///
+    /// This method never changes the state of the channel. If a message has been sent but not
+    /// yet received this returns `false`, and the message can still be received afterwards.
+    ///
+    /// If `true` is returned, all future calls to receive methods are guaranteed to return
+    /// a disconnected error. And future calls to this method is guaranteed to also return `true`.
+    /// If `false` is returned the sender might disconnect right after the check, so the result
+    /// should only be seen as a hint.
+    pub fn is_closed(&self) -> bool {
+        // SAFETY: the existence of the `self` parameter serves as a certificate that the receiver
+        // is still alive, meaning that even if the sender was dropped then it would have observed
+        // the fact that we're still alive and left the responsibility of deallocating the
+        // channel to us, so `self.channel` is valid
+        let channel = unsafe { self.channel_ptr.as_ref() };
+
+        // ORDERING: We *chose* a Relaxed ordering here as it is sufficient to enforce the
+        // method's contract. Once true has been observed, it will remain true. However, if false
+        // is observed, the sender might have just disconnected but this thread has not observed
+        // it yet.
+        matches!(
+            channel.state.load(Relaxed),
+            DISCONNECTED | RECEIVED | RECEIVER_CLOSED | CLOSED_SENDER_DROPPED | CLOSED_SEND_FAILED
+        )
+    }
+
+    /// Returns true if this receiver and `sender` are the two endpoints of the same channel.
+    /// See [`Sender::same_channel`].
+    pub fn same_channel(&self, sender: &Sender<T>) -> bool {
+        sender.same_channel(self)
+    }
+
+    /// Returns the id of the channel this receiver belongs to. The same id is returned from
+    /// [`Sender::channel_id`] on the associated sender.
+    pub fn channel_id(&self) -> ChannelId {
+        ChannelId::new(self.channel_ptr)
+    }
+
+    /// Consumes the receiver and returns a raw pointer to the channel, for example to pass it
+    /// through FFI as an opaque pointer. The receiver is not dropped, so the channel is kept
+    /// alive until the receiver is reconstructed with [`Receiver::from_raw`] and then used or
+    /// dropped.
+    pub fn into_raw(self) -> *mut () {
+        let raw = self.channel_ptr.as_ptr() as *mut ();
+        mem::forget(self);
+        raw
+    }
+
+    /// Reconstructs a receiver from a pointer returned by [`Receiver::into_raw`].
+    ///
+    /// # Safety
+    ///
+    /// `raw` must come from a call to [`Receiver::into_raw`] on a `Receiver<T>` of the same `T`.
+    /// Each raw pointer must only be turned back into a receiver once.
+    pub unsafe fn from_raw(raw: *mut ()) -> Self {
+        Self {
+            channel_ptr: NonNull::new_unchecked(raw as *mut Channel<T>),
+        }
+    }
+
+    /// Returns true if there is a message in the channel, ready to be received.
+    ///
+    /// This method never changes the state of the channel, the message stays in the channel
+    /// until one of the receive methods is called.
+    ///
+    /// If `true` is returned, the next call to a receive method is guaranteed to return
+    /// a message.
+    pub fn has_message(&self) -> bool {
+        // SAFETY: the existence of the `self` parameter serves as a certificate that the receiver
+        // is still alive, meaning that even if the sender was dropped then it would have observed
+        // the fact that we're still alive and left the responsibility of deallocating the
+        // channel to us, so `self.channel` is valid
+        let channel = unsafe { self.channel_ptr.as_ref() };
+
+        // ORDERING: An acquire ordering is used to guarantee no subsequent loads is reordered
+        // before this one. This upholds the contract that if true is returned, the next call to
+        // a receive method is guaranteed to also observe the `MESSAGE` state and return a message.
+        channel.state.load(Acquire) == MESSAGE
+    }
+
+    /// Returns true if the [`Sender`] is alive and has not sent anything yet, so a receive would
+    /// have to wait. The counterpart of [`Receiver::has_message`] and [`Receiver::is_closed`],
+    /// exactly one of the three is true at any point in time.
+    ///
+    /// This method never changes the state of the channel. The sender can send or be dropped
+    /// right after the check, so `true` should only be seen as a hint. Once `false` is returned
+    /// it stays `false`.
+    pub fn is_empty(&self) -> bool {
+        // SAFETY: the existence of the `self` parameter serves as a certificate that the receiver
+        // is still alive, meaning that even if the sender was dropped then it would have observed
+        // the fact that we're still alive and left the responsibility of deallocating the
+        // channel to us, so `self.channel` is valid
+        let channel = unsafe { self.channel_ptr.as_ref() };
+
+        // ORDERING: this is a hint and we don't access the message, so relaxed is enough.
+        match channel.state.load(Relaxed) {
+            EMPTY => true,
+            // The receiver has been polled. The sender might be waking it up right now, but it
+            // has not finished sending yet.
+            #[cfg(feature = "async")]
+            RECEIVING | UNPARKING => true,
+            _ => false,
+        }
+    }
+
+    /// Returns a reference to the message if the sender has already sent it, without receiving
+    /// it.
+    ///
+    /// This method never changes the state of the channel, the message stays in the channel
+    /// until one of the receive methods is called, and it is then received exactly once. If the
+    /// receiver is dropped instead, the message is dropped with it.
+    ///
+    /// This method takes `&mut self` since the receive methods taking `&self` move the message
+    /// out of the channel, which must not happen while it is borrowed.
+    pub fn peek(&mut self) -> Option<&T> {
+        // SAFETY: the existence of the `self` parameter serves as a certificate that the receiver
+        // is still alive, meaning that even if the sender was dropped then it would have observed
+        // the fact that we're still alive and left the responsibility of deallocating the
+        // channel to us, so `self.channel` is valid
+        let channel = unsafe { self.channel_ptr.as_ref() };
+
+        // ORDERING: we use acquire ordering to synchronize with the write of the message.
+        match channel.state.load(Acquire) {
+            // SAFETY: we are in the message state so the message is valid. The sender is gone
+            // and only the receiver can move the message out of the channel, which it can't do
+            // while the returned reference borrows it mutably.
+            MESSAGE => Some(unsafe { channel.message().assume_init_ref() }),
+            _ => None,
+        }
+    }
+
+    /// Blocks until a message arrives like [`Receiver::recv_mut`], but leaves the message in the
+    /// channel and returns a reference to it, like [`Receiver::peek`]. The message can later be
+    /// received with any of the receive methods. Returns an error if the sender was dropped
+    /// without sending anything, the message was already received, or the receiver was closed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called after this receiver has been polled asynchronously.
+    #[cfg(feature = "std")]
+    pub fn wait_peek(&mut self) -> Result<&T, RecvError> {
+        // SAFETY: the existence of the `self` parameter serves as a certificate that the receiver
+        // is still alive, meaning that even if the sender was dropped then it would have observed
+        // the fact that we're still alive and left the responsibility of deallocating the
+        // channel to us, so `self.channel` is valid
+        let channel = unsafe { self.channel_ptr.as_ref() };
+
+        // ORDERING: synchronize with the write of the message
+        match channel.state.load(Acquire) {
+            // The sender is alive but has not sent anything yet. We prepare to park.
+            EMPTY => {
+                // Lets the tests trigger the edge cases where the sender manages to be dropped or
+                // send something before we are able to store our waker object in the channel.
+                test_hooks::pre_park_hook();
+
+                // Write our waker instance to the channel.
+                // SAFETY: we are not yet in the RECEIVING state, meaning that the sender will not
+                // try to access the waker until it sees the state set to RECEIVING below
+                unsafe { channel.write_waker(ReceiverWaker::current_thread()) };
+
+                // ORDERING: see `start_recv_ref`
+                match channel
+                    .state
+                    .compare_exchange(EMPTY, RECEIVING, Release, Relaxed)
+                {
+                    // We stored our waker, park until the sender has sent or been dropped. The
+                    // sender takes our waker in both cases.
+                    Ok(_) => loop {
+                        thread::park();
+
+                        // ORDERING: we use acquire ordering to synchronize with the write of the
+                        // message
+                        match channel.state.load(Acquire) {
+                            MESSAGE => break,
+                            DISCONNECTED => return Err(RecvError::Disconnected),
+                            // State did not change, spurious wakeup, park again.
+                            RECEIVING | UNPARKING => (),
+                            _ => unreachable!(),
+                        }
+                    },
+                    // The sender sent the message while we prepared to park.
+                    Err(MESSAGE) => {
+                        // See comments in `recv` for ordering and safety
+                        fence(Acquire);
+                        unsafe { channel.drop_waker() };
+                    }
+                    // The sender was dropped before sending anything while we prepared to park.
+                    Err(DISCONNECTED) => {
+                        // See comments in `recv` for safety
+                        unsafe { channel.drop_waker() };
+                        return Err(RecvError::Disconnected);
+                    }
+                    _ => unreachable!(),
+                }
+            }
+            // The sender already sent the message.
+            MESSAGE => (),
+            // The sender was dropped before sending anything.
+            DISCONNECTED => return Err(RecvError::Disconnected),
+            // We already received the message.
+            RECEIVED => return Err(RecvError::AlreadyReceived),
+            // The receiver has been closed.
+            RECEIVER_CLOSED | CLOSED_SENDER_DROPPED | CLOSED_SEND_FAILED => {
+                return Err(RecvError::Disconnected)
+            }
+            // The receiver must have been `Future::poll`ed prior to this call.
+            #[cfg(feature = "async")]
+            RECEIVING | UNPARKING => panic!("{}", RECEIVER_USED_SYNC_AND_ASYNC_ERROR),
+            _ => unreachable!(),
+        }
+
+        // SAFETY: we observed the message state, so the message is valid. See `peek` for why it
+        // stays valid for as long as the returned reference.
+        Ok(unsafe { channel.message().assume_init_ref() })
+    }
+
+    /// Closes the channel without dropping the receiver. Use this to tell the sender that the
+    /// message is no longer wanted, when the receiver can't be dropped yet, for example because
+    /// it is borrowed.
+    ///
+    /// After this call a future call to [`Sender::send`] will return an error, and a task
+    /// waiting in [`Sender::closed`] is woken up. A message that was sent before the channel was
+    /// closed stays in the channel and can still be received. Otherwise all receive methods
+    /// return a disconnected error. Dropping the receiver after closing it is safe and frees the
+    /// channel if the sender is gone as well. If the channel is already closed this method does
+    /// nothing.
+    pub fn close(&self) {
+        // SAFETY: the existence of the `self` parameter serves as a certificate that the receiver
+        // is still alive, meaning that even if the sender was dropped then it would have observed
+        // the fact that we're still alive and left the responsibility of deallocating the
+        // channel to us, so `self.channel` is valid
+        let channel = unsafe { self.channel_ptr.as_ref() };
+        trace!(endpoint = "receiver", "channel closed");
+
+        // Take the waker of a sender waiting in `Sender::closed` before we signal that we are
+        // closed, so a sender observing the CLOSING closed waker state can wait for our state
+        // change below.
+        // SAFETY: only the receiver calls this
+        #[cfg(feature = "async")]
+        let mut closed_waker = unsafe { channel.take_closed_waker() };
+
+        loop {
+            // ORDERING: we never access the message here, so relaxed is enough.
+            match channel.state.load(Relaxed) {
+                // The sender is alive but has not sent anything yet. The state is changed in one
+                // atomic step, since the sender might send or be dropped concurrently.
+                // ORDERING: nothing has been written that the sender needs to observe
+                EMPTY => {
+                    if channel
+                        .state
+                        .compare_exchange(EMPTY, RECEIVER_CLOSED, Relaxed, Relaxed)
+                        .is_ok()
+                    {
+                        #[cfg(feature = "async")]
+                        if let Some(waker) = closed_waker.take() {
+                            waker.wake();
+                        }
+                        break;
+                    }
+                }
+                // The receiver has been polled and has a waker stored.
+                #[cfg(feature = "async")]
+                RECEIVING => {
+                    if channel
+                        .state
+                        .compare_exchange(RECEIVING, RECEIVER_CLOSED, Relaxed, Relaxed)
+                        .is_ok()
+                    {
+                        // SAFETY: we wrote the waker in a previous call to poll, and the sender
+                        // never accesses the waker in the RECEIVER_CLOSED state.
+                        unsafe { channel.drop_waker() };
+
+                        if let Some(waker) = closed_waker.take() {
+                            waker.wake();
+                        }
+                        break;
+                    }
+                }
+                // The sender is currently waking us up after sending or being dropped. It will
+                // soon be done.
+                #[cfg(feature = "async")]
+                UNPARKING => hint::spin_loop(),
+                // The sender already sent the message and is gone. The message is left in the
+                // channel so it can still be received. It's dropped with the receiver otherwise.
+                MESSAGE => break,
+                // The channel is already closed.
+                DISCONNECTED
+                | RECEIVED
+                | RECEIVER_CLOSED
+                | CLOSED_SENDER_DROPPED
+                | CLOSED_SEND_FAILED => break,
+                _ => unreachable!(),
+            }
+        }
+    }
+
+    /// Reuses the channel allocation for a new message, if the channel is done with the
+    /// previous one. Returns a new [`Sender`] connected to this receiver, or `None` if the
+    /// channel can't be reset yet.
+    ///
+    /// The channel can be reset when the sender is gone and there is no message left in it. That
+    /// is after the message was received with one of the non-consuming receive methods, or
+    /// after the sender was dropped without sending. `None` is returned while the sender is
+    /// alive, while a sent message has not been received, and while a [`SendError`] from a send
+    /// on a closed channel still holds its message.
+    ///
+    /// ```
+    /// let (sender, mut receiver) = oneshot::channel::<u32>();
+    /// sender.send(1).unwrap();
+    /// assert_eq!(receiver.try_recv(), Ok(1));
+    ///
+    /// let sender = receiver.reset().unwrap();
+    /// sender.send(2).unwrap();
+    /// assert_eq!(receiver.try_recv(), Ok(2));
+    /// ```
+    pub fn reset(&mut self) -> Option<Sender<T>> {
+        // SAFETY: the existence of the `self` parameter serves as a certificate that the receiver
+        // is still alive, meaning that even if the sender was dropped then it would have observed
+        // the fact that we're still alive and left the responsibility of deallocating the
+        // channel to us, so `self.channel` is valid
+        let channel = unsafe { self.channel_ptr.as_ref() };
+
+        // ORDERING: synchronize with the last operation of the sender, so all its accesses to
+        // the channel happen-before we overwrite it
+        match channel.state.load(Acquire) {
+            // The sender is gone, and there is no message in the channel.
+            DISCONNECTED | RECEIVED | CLOSED_SENDER_DROPPED => (),
+            _ => return None,
+        }
+
+        // SAFETY: the sender is gone, and we hold the only reference to the receiver, so we have
+        // exclusive access to the channel. The old value is dropped, which also drops a waker
+        // left by `Sender::poll_closed`. There is no message or receiver waker in the channel in
+        // the states above.
+        unsafe { *self.channel_ptr.as_ptr() = Channel::new() };
+
+        Some(Sender {
+            channel_ptr: self.channel_ptr,
+            _invariant: PhantomData,
+        })
+    }
+
+    /// Waits until a message has been sent or the channel is disconnected, without receiving
+    /// the message. After this resolves with `Ok(())` the next call to a receive method is
+    /// guaranteed to return the message.
+    ///
+    /// ```
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let (sender, mut receiver) = oneshot::channel::<u32>();
+    /// sender.send(5).unwrap();
+    /// assert_eq!(receiver.ready().await, Ok(()));
+    /// assert_eq!(receiver.try_recv(), Ok(5));
+    /// # });
+    /// ```
+    #[cfg(feature = "async")]
+    pub fn ready(&mut self) -> impl core::future::Future<Output = Result<(), RecvError>> + '_ {
+        Ready { receiver: self }
+    }
+
+    /// Returns a future receiving the message without consuming the receiver. The future is a
+    /// named type, so it can be stored in a struct field without boxing.
+    ///
+    /// Dropping the future before it completes is fine, the message is then left in the channel
+    /// for the next receive. See [`RecvFuture`] for details.
+    ///
+    /// ```
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let (sender, mut receiver) = oneshot::channel::<u32>();
+    /// sender.send(5).unwrap();
+    /// assert_eq!(receiver.recv_async().await, Ok(5));
+    /// assert_eq!(
+    ///     receiver.recv_async().await,
+    ///     Err(oneshot::RecvError::AlreadyReceived)
+    /// );
+    /// # });
+    /// ```
+    #[cfg(feature = "async")]
+    pub fn recv_async(&mut self) -> RecvFuture<'_, T> {
+        RecvFuture::new(self)
+    }
+
+    /// Polls for the message, like polling the receiver as a `Future` does, but without pinning.
+    /// For usage in manual `Future` implementations holding the receiver.
+    ///
+    /// Returns `Poll::Pending` if the message has not been sent yet, after registering the waker
+    /// in `cx` to be woken when the [`Sender`] sends or is dropped. A waker registered by an
+    /// earlier poll is replaced, unless both wake the same task. A message sent concurrently with
+    /// the poll is never lost, it is either returned or the new waker is woken up.
+    ///
+    /// It is fine to call this again after it has returned `Poll::Ready`. It then returns
+    /// `Poll::Ready(Err(RecvError::AlreadyReceived))` if the message was received, or
+    /// `Poll::Ready(Err(RecvError::Disconnected))` if the sender was dropped without sending.
+    ///
+    /// Unlike [`Receiver::try_recv`] this takes `&mut self`. Registering the waker clones and
+    /// drops wakers, which runs arbitrary code in the middle of updating the channel, and a second
+    /// poll of the same receiver from inside that code must be ruled out.
+    #[cfg(feature = "async")]
+    pub fn poll_recv(&mut self, cx: &mut task::Context<'_>) -> Poll<Result<T, RecvError>> {
+        let poll = self.poll_ready(cx).map_ok(|()| {
+            // SAFETY: the existence of `self` serves as a certificate that the channel
+            // is still valid, see `poll_ready`
+            let channel = unsafe { self.channel_ptr.as_ref() };
+
+            // ORDERING: `poll_ready` has synchronized with the write of the message, and the
+            // sender has been dropped so this update only needs to be visible to us
+            channel.state.store(RECEIVED, Relaxed);
+
+            // SAFETY: `poll_ready` observed the MESSAGE state
+            unsafe { channel.take_message() }
+        });
+
+        #[cfg(feature = "futures-core")]
+        if poll.is_ready() {
+            // SAFETY: the receiver is still alive, so the channel is valid
+            unsafe { self.channel_ptr.as_ref() }.terminated.set(true);
+        }
+        trace!(
+            endpoint = "receiver",
+            ready = poll.is_ready(),
+            "receiver polled"
+        );
+        poll
+    }
+
+    /// Polls for a message to be sent or the channel to be disconnected, without receiving the
+    /// message. This is the method backing [`Receiver::ready`], for usage in manual `Future`
+    /// implementations.
+    ///
+    /// Returns `Poll::Ready(Ok(()))` if there is a message ready to be received, and
+    /// `Poll::Ready(Err(_))` under the same conditions as polling the receiver itself.
+    /// Otherwise the waker in `cx` is registered like when polling the receiver, and
+    /// `Poll::Pending` is returned.
+    #[cfg(feature = "async")]
+    pub fn poll_ready(&mut self, cx: &mut task::Context<'_>) -> Poll<Result<(), RecvError>> {
+        // SAFETY: the existence of the `self` parameter serves as a certificate that the receiver
+        // is still alive, meaning that even if the sender was dropped then it would have observed
+        // the fact that we're still alive and left the responsibility of deallocating the
+        // channel to us, so `self.channel` is valid
+        let channel = unsafe { self.channel_ptr.as_ref() };
+
+        // ORDERING: we use acquire ordering to synchronize with the store of the message.
+        match channel.state.load(Acquire) {
+            // The sender is alive but has not sent anything yet.
+            EMPTY => {
+                // SAFETY: We can't be in the forbidden states, and no waker in the channel.
+                unsafe { channel.write_async_waker(cx) }
+            }
+            // We were polled again while waiting for the sender. Replace the waker with the new one.
+            RECEIVING => {
+                // Keep the stored waker if it wakes the same task as the new one, which is the
+                // common case. Then no waker has to be cloned or dropped.
+                // SAFETY: we wrote the waker in a previous call to poll. The sender might be
+                // moving it out concurrently, but the memory of the waker is neither written to
+                // nor freed while we are alive, and `will_wake` only compares pointers.
+                if unsafe { channel.waker_will_wake(cx) } {
+                    return Poll::Pending;
+                }
+
+                // ORDERING: We use relaxed ordering on both success and failure since we have not
+                // written anything above that must be released, and the individual match arms
+                // handle any additional synchronization.
+                match channel
+                    .state
+                    .compare_exchange(RECEIVING, EMPTY, Relaxed, Relaxed)
+                {
+                    // We successfully changed the state back to EMPTY. Replace the waker.
+                    // This is the most likely branch to be taken, which is why we don't use any
+                    // memory barriers in the compare_exchange above.
+                    Ok(_) => {
+                        // SAFETY: We wrote the waker in a previous call to poll. We do not need
+                        // a memory barrier since the previous write here was by ourselves.
+                        unsafe { channel.drop_waker() };
+                        // SAFETY: We can't be in the forbidden states, and no waker in the channel.
+                        unsafe { channel.write_async_waker(cx) }
+                    }
+                    // The sender sent the message while we prepared to replace the waker.
+                    // The sender has already taken the waker.
+                    Err(MESSAGE) => {
+                        // ORDERING: Synchronize with the write of the message. This branch is
+                        // unlikely to be taken.
+                        fence(Acquire);
+                        Poll::Ready(Ok(()))
+                    }
+                    // The sender was dropped before sending anything while we prepared to park.
+                    // The sender has taken the waker already.
+                    Err(DISCONNECTED) => Poll::Ready(Err(RecvError::Disconnected)),
+                    // The sender is currently waking us up.
+                    Err(UNPARKING) => {
+                        // We can't trust that the old waker that the sender has access to
+                        // is honored by the async runtime at this point. So we wake ourselves
+                        // up to get polled instantly again.
+                        cx.waker().wake_by_ref();
+                        Poll::Pending
                     }
-                    // The sender was dropped while we were parked.
-                    DISCONNECTED => break Err(RecvError),
-                    // State did not change, spurious wakeup, park again.
-                    RECEIVING | UNPARKING => (),
                     _ => unreachable!(),
                 }
             }
-        })
+            // The sender sent the message.
+            MESSAGE => Poll::Ready(Ok(())),
+            // The sender was dropped before sending anything.
+            DISCONNECTED => Poll::Ready(Err(RecvError::Disconnected)),
+            // We already received the message.
+            RECEIVED => Poll::Ready(Err(RecvError::AlreadyReceived)),
+            // The receiver has been closed.
+            RECEIVER_CLOSED | CLOSED_SENDER_DROPPED | CLOSED_SEND_FAILED => {
+                Poll::Ready(Err(RecvError::Disconnected))
+            }
+            // The sender has observed the RECEIVING state and is currently reading the waker from
+            // a previous poll. We need to loop here until we observe the MESSAGE or DISCONNECTED
+            // state. We busy loop here since we know the sender is done very soon.
+            UNPARKING => loop {
+                hint::spin_loop();
+                // ORDERING: The load above has already synchronized with the write of the message.
+                match channel.state.load(Relaxed) {
+                    MESSAGE => break Poll::Ready(Ok(())),
+                    DISCONNECTED => break Poll::Ready(Err(RecvError::Disconnected)),
+                    UNPARKING => (),
+                    _ => unreachable!(),
+                }
+            },
+            _ => unreachable!(),
+        }
     }
 
-    /// Like [`Receiver::recv`], but will not block longer than `timeout`. Returns:
-    ///  * `Ok(message)` if there was a message in the channel before the timeout was reached.
-    ///  * `Err(Timeout)` if no message arrived on the channel before the timeout was reached.
-    ///  * `Err(Disconnected)` if the sender was dropped before sending anything or if the message
-    ///    has already been extracted by a previous receive call.
+    /// Returns an iterator that blocks like [`Receiver::recv`] on the first call to `next`, and
+    /// yields what it returns, message or error. Then it ends.
     ///
-    /// If a message is returned, the channel is disconnected and any subsequent receive operation
-    /// using this receiver will return an error.
+    /// Unlike iterating the receiver itself, which ends without yielding anything when the
+    /// sender is dropped, this always yields exactly one item. Useful for code consuming iterators
+    /// of results.
     ///
-    /// If the supplied `timeout` is so large that Rust's `Instant` type can't represent this point
-    /// in the future this falls back to an indefinitely blocking receive operation.
+    /// ```
+    /// let (sender, receiver) = oneshot::channel::<u32>();
+    /// drop(sender);
+    /// let results: Vec<_> = receiver.into_results().collect();
+    /// assert_eq!(results, [Err(oneshot::RecvError::Disconnected)]);
+    /// ```
     ///
     /// # Panics
     ///
-    /// Panics if called after this receiver has been polled asynchronously.
+    /// Calling `next` panics if this receiver has been polled asynchronously before.
     #[cfg(feature = "std")]
-    pub fn recv_timeout(&self, timeout: Duration) -> Result<T, RecvTimeoutError> {
-        match Instant::now().checked_add(timeout) {
-            Some(deadline) => self.recv_deadline(deadline),
-            None => self.recv_ref().map_err(|_| RecvTimeoutError::Disconnected),
-        }
+    pub fn into_results(self) -> impl FusedIterator<Item = Result<T, RecvError>> {
+        once_with(move || self.recv())
     }
 
-    /// Like [`Receiver::recv`], but will not block longer than until `deadline`. Returns:
-    ///  * `Ok(message)` if there was a message in the channel before the deadline was reached.
-    ///  * `Err(Timeout)` if no message arrived on the channel before the deadline was reached.
-    ///  * `Err(Disconnected)` if the sender was dropped before sending anything or if the message
-    ///    has already been extracted by a previous receive call.
+    /// Returns a receiver that transforms the message with `f` when it is received. `f` is called
+    /// at most once, in the thread or task receiving the message, and not at all if the channel
+    /// is disconnected.
     ///
-    /// If a message is returned, the channel is disconnected and any subsequent receive operation
-    /// using this receiver will return an error.
+    /// ```
+    /// let (sender, receiver) = oneshot::channel::<u32>();
+    /// let mut receiver = receiver.map(|n| n.to_string());
+    /// sender.send(5).unwrap();
+    /// assert_eq!(receiver.try_recv(), Ok(String::from("5")));
+    /// ```
+    pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> Map<T, F> {
+        Map::new(self, f)
+    }
+
+    /// Returns a receiver that passes the message to `hook` if it was sent but is never
+    /// received, instead of dropping it silently. Useful for cleanup, or for tracking payloads
+    /// that got lost. The hook runs when the returned [`OnUndelivered`] is dropped.
+    ///
+    /// ```
+    /// let (sender, receiver) = oneshot::channel::<u32>();
+    /// let lost = std::cell::Cell::new(None);
+    /// let receiver = receiver.on_undelivered(|n| lost.set(Some(n)));
+    /// sender.send(5).unwrap();
+    /// drop(receiver);
+    /// assert_eq!(lost.get(), Some(5));
+    /// ```
+    pub fn on_undelivered<F: FnOnce(T)>(self, hook: F) -> OnUndelivered<T, F> {
+        OnUndelivered::new(self, hook)
+    }
+
+    /// Returns a future relaying the message into `dest`, for bridging two channels without
+    /// writing a task body that does it. Dropping the source [`Sender`] without sending is relayed
+    /// by dropping `dest`, so the final receiver sees the disconnect.
+    ///
+    /// The future resolves once the message, or the disconnect, has been relayed. If the receiver
+    /// of `dest` has been dropped, it resolves with the message in a [`SendError`].
+    #[cfg(feature = "async")]
+    pub fn forward(self, dest: Sender<T>) -> Forward<T> {
+        Forward::new(self, dest)
+    }
+
+    /// Like [`Receiver::forward`], but blocks the current thread until the message, or the
+    /// disconnect, has been relayed into `dest`.
+    ///
+    /// ```
+    /// let (sender, receiver) = oneshot::channel::<u32>();
+    /// let (dest, final_receiver) = oneshot::channel();
+    /// sender.send(5).unwrap();
+    /// receiver.forward_blocking(dest).unwrap();
+    /// assert_eq!(final_receiver.recv(), Ok(5));
+    /// ```
     ///
     /// # Panics
     ///
     /// Panics if called after this receiver has been polled asynchronously.
     #[cfg(feature = "std")]
-    pub fn recv_deadline(&self, deadline: Instant) -> Result<T, RecvTimeoutError> {
-        /// # Safety
-        ///
-        /// If the sender is unparking us after a message send, the message must already have been
-        /// written to the channel and an acquire memory barrier issued before calling this function
-        #[cold]
-        unsafe fn wait_for_unpark<T>(channel: &Channel<T>) -> Result<T, RecvTimeoutError> {
-            loop {
-                thread::park();
-
-                // ORDERING: The callee has already synchronized with any message write
-                match channel.state.load(Relaxed) {
-                    MESSAGE => {
-                        // ORDERING: the sender has been dropped, so this update only
-                        // needs to be visible to us
-                        channel.state.store(DISCONNECTED, Relaxed);
-                        break Ok(channel.take_message());
-                    }
-                    DISCONNECTED => break Err(RecvTimeoutError::Disconnected),
-                    // The sender is still unparking us. We continue on the empty state here since
-                    // the current implementation eagerly sets the state to EMPTY upon timeout.
-                    EMPTY => (),
-                    _ => unreachable!(),
-                }
-            }
+    pub fn forward_blocking(self, dest: Sender<T>) -> Result<(), SendError<T>> {
+        match self.recv() {
+            Ok(message) => dest.send(message),
+            // Dropping `dest` relays the disconnect.
+            Err(_) => Ok(()),
         }
+    }
 
-        self.start_recv_ref(RecvTimeoutError::Disconnected, |channel| {
-            loop {
-                match deadline.checked_duration_since(Instant::now()) {
-                    Some(timeout) => {
-                        thread::park_timeout(timeout);
+    /// Attaches `deadline` to this receiver, so it does not have to be passed along separately.
+    /// Receiving from the returned [`DeadlineReceiver`], blocking or asynchronously, gives up with
+    /// [`RecvTimeoutError::Timeout`] once the deadline has passed. See its docs for what wakes an
+    /// async receive at the deadline.
+    ///
+    /// ```
+    /// use std::time::{Duration, Instant};
+    ///
+    /// let (_sender, receiver) = oneshot::channel::<u32>();
+    /// let receiver = receiver.with_deadline(Instant::now() + Duration::from_millis(10));
+    /// assert_eq!(receiver.recv(), Err(oneshot::RecvTimeoutError::Timeout));
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn with_deadline(self, deadline: Instant) -> DeadlineReceiver<T> {
+        DeadlineReceiver::new(self, deadline)
+    }
 
-                        // ORDERING: synchronize with the write of the message
-                        match channel.state.load(Acquire) {
-                            // The sender sent the message while we were parked.
-                            MESSAGE => {
-                                // ORDERING: the sender has been `mem::forget`-ed so this update
-                                // only needs to be visible to us.
-                                channel.state.store(DISCONNECTED, Relaxed);
+    /// Returns a future receiving the message like the receiver does, but resolving to
+    /// [`RecvTimeoutError::Timeout`] if nothing arrived within `timeout`. Saves wrapping the
+    /// receiver in `tokio::time::timeout`, and the nested `Result` that comes with it.
+    ///
+    /// This is [`Receiver::recv_timeout_with`] with the timer of tokio, [`timer::Tokio`]. The
+    /// returned future creates a `tokio::time::Sleep` when first polled, so it must be polled
+    /// within a tokio runtime with the time driver enabled. With other runtimes, use
+    /// [`Receiver::recv_timeout_with`] with their timer instead.
+    ///
+    /// ```
+    /// # tokio::runtime::Builder::new_current_thread()
+    /// #     .enable_time()
+    /// #     .build()
+    /// #     .unwrap()
+    /// #     .block_on(async {
+    /// use std::time::Duration;
+    ///
+    /// let (_sender, receiver) = oneshot::channel::<u32>();
+    /// let result = receiver.recv_timeout_async(Duration::from_millis(10)).await;
+    /// assert_eq!(result, Err(oneshot::RecvTimeoutError::Timeout));
+    /// # });
+    /// ```
+    #[cfg(all(feature = "tokio", feature = "async", not(loom)))]
+    pub fn recv_timeout_async(
+        self,
+        timeout: core::time::Duration,
+    ) -> timer::RecvTimeout<T, timer::Tokio> {
+        self.recv_timeout_with(timeout)
+    }
 
-                                // SAFETY: we either are in the message state or were just in the
-                                // message state
-                                break Ok(unsafe { channel.take_message() });
-                            }
-                            // The sender was dropped while we were parked.
-                            DISCONNECTED => break Err(RecvTimeoutError::Disconnected),
-                            // State did not change, spurious wakeup, park again.
-                            RECEIVING | UNPARKING => (),
-                            _ => unreachable!(),
-                        }
-                    }
-                    None => {
-                        // ORDERING: synchronize with the write of the message
-                        match channel.state.swap(EMPTY, Acquire) {
-                            // We reached the end of the timeout without receiving a message
-                            RECEIVING => {
-                                // SAFETY: we were in the receiving state and are now in the empty
-                                // state, so the sender has not and will not try to read the waker,
-                                // so we have exclusive access to drop it.
-                                unsafe { channel.drop_waker() };
-
-                                break Err(RecvTimeoutError::Timeout);
-                            }
-                            // The sender sent the message while we were parked.
-                            MESSAGE => {
-                                // Same safety and ordering as the Some branch
+    /// Returns a future receiving the message like the receiver does, but resolving to
+    /// [`RecvTimeoutError::Timeout`] if nothing arrived within `timeout`. The time is measured
+    /// with the sleep of the async runtime given as `S`, see the [`timer`] module.
+    ///
+    /// ```
+    /// # #[cfg(feature = "tokio")]
+    /// # tokio::runtime::Builder::new_current_thread()
+    /// #     .enable_time()
+    /// #     .build()
+    /// #     .unwrap()
+    /// #     .block_on(async {
+    /// use std::time::Duration;
+    ///
+    /// let (_sender, receiver) = oneshot::channel::<u32>();
+    /// let result = receiver
+    ///     .recv_timeout_with::<oneshot::timer::Tokio>(Duration::from_millis(10))
+    ///     .await;
+    /// assert_eq!(result, Err(oneshot::RecvTimeoutError::Timeout));
+    /// # });
+    /// ```
+    #[cfg(all(feature = "async", not(loom)))]
+    pub fn recv_timeout_with<S: timer::AsyncTimer>(
+        self,
+        timeout: core::time::Duration,
+    ) -> timer::RecvTimeout<T, S> {
+        timer::RecvTimeout::new(self, timeout)
+    }
 
-                                channel.state.store(DISCONNECTED, Relaxed);
-                                break Ok(unsafe { channel.take_message() });
-                            }
-                            // The sender was dropped while we were parked.
-                            DISCONNECTED => {
-                                // ORDERING: we were originally in the disconnected state meaning
-                                // that the sender is inactive and no longer observing the state,
-                                // so we only need to change it back to DISCONNECTED for if the
-                                // receiver is dropped or a recv* method is called again
-                                channel.state.store(DISCONNECTED, Relaxed);
+    /// Returns a future receiving the message like the receiver does, but resolving to
+    /// [`RecvOrCancelError::Cancelled`] if `token` is cancelled first. If the message arrives
+    /// at the same time as the cancellation, the message wins.
+    ///
+    /// The receiver is consumed, so when cancellation wins it is dropped along with the waker it
+    /// stored in the channel, and the sender sees the channel as closed.
+    ///
+    /// ```
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let token = tokio_util::sync::CancellationToken::new();
+    /// let (_sender, receiver) = oneshot::channel::<u32>();
+    /// token.cancel();
+    /// let result = receiver.recv_cancellable(&token).await;
+    /// assert_eq!(result, Err(oneshot::RecvOrCancelError::Cancelled));
+    /// # });
+    /// ```
+    #[cfg(all(feature = "tokio-util", feature = "async", not(loom)))]
+    pub fn recv_cancellable<'a>(
+        self,
+        token: &'a tokio_util::sync::CancellationToken,
+    ) -> impl core::future::Future<Output = Result<T, RecvOrCancelError>> + 'a
+    where
+        T: 'a,
+    {
+        cancel::RecvCancellable::new(self, token)
+    }
 
-                                break Err(RecvTimeoutError::Disconnected);
-                            }
-                            // The sender sent the message and started unparking us
-                            UNPARKING => {
-                                // We were in the UNPARKING state and are now in the EMPTY state.
-                                // We wait to be properly unparked and to observe if the sender
-                                // sets MESSAGE or DISCONNECTED state.
-                                // SAFETY: The load above has synchronized with any message write.
-                                break unsafe { wait_for_unpark(channel) };
-                            }
-                            _ => unreachable!(),
-                        }
-                    }
-                }
+    /// Turns this receiver into a JavaScript `Promise`, for handing it to JavaScript code. The
+    /// promise resolves with the message converted into a `JsValue`, or rejects with an `Error`
+    /// describing the [`RecvError`] if the sender is dropped without sending.
+    ///
+    /// The promise is completed by polling the receiver on the JavaScript event loop, so it never
+    /// blocks the thread.
+    #[cfg(all(feature = "wasm-bindgen", not(loom)))]
+    pub fn into_js_promise(self) -> js_sys::Promise
+    where
+        T: Into<wasm_bindgen::JsValue> + 'static,
+    {
+        use alloc::string::ToString;
+
+        wasm_bindgen_futures::future_to_promise(async move {
+            match self.await {
+                Ok(message) => Ok(message.into()),
+                Err(error) => Err(js_sys::Error::new(&error.to_string()).into()),
             }
         })
     }
 
+    /// Returns a future receiving the message like the receiver does, but resolving to
+    /// [`RecvTimeoutError::Timeout`] if nothing arrived by `deadline`. The deadline is on the
+    /// tokio clock, so it follows `tokio::time::pause` in tests. Unlike wrapping the receiver in
+    /// `tokio::time::timeout` or [`Receiver::recv_timeout_with`], the sleep is not boxed.
+    ///
+    /// The returned future must be polled within a tokio runtime with the time driver enabled.
+    #[cfg(all(feature = "tokio", feature = "async", not(loom)))]
+    pub fn recv_with_deadline(self, deadline: tokio::time::Instant) -> timer::TokioRecvTimeout<T> {
+        timer::TokioRecvTimeout::new(self, deadline)
+    }
+
+    /// Like [`Receiver::recv_with_deadline`], with the deadline `timeout` from now on the tokio
+    /// clock.
+    ///
+    /// ```
+    /// # tokio::runtime::Builder::new_current_thread()
+    /// #     .enable_time()
+    /// #     .start_paused(true)
+    /// #     .build()
+    /// #     .unwrap()
+    /// #     .block_on(async {
+    /// use std::time::Duration;
+    ///
+    /// let (_sender, receiver) = oneshot::channel::<u32>();
+    /// let result = receiver.recv_with_timeout(Duration::from_secs(60)).await;
+    /// assert_eq!(result, Err(oneshot::RecvTimeoutError::Timeout));
+    /// # });
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `timeout` is so large that the deadline can't be represented.
+    #[cfg(all(feature = "tokio", feature = "async", not(loom)))]
+    pub fn recv_with_timeout(self, timeout: core::time::Duration) -> timer::TokioRecvTimeout<T> {
+        let deadline = tokio::time::Instant::now()
+            .checked_add(timeout)
+            .expect("timeout too large for the deadline to be represented");
+        self.recv_with_deadline(deadline)
+    }
+
+    /// Turns this receiver into a [`Stream`](futures_core::Stream) that yields the message, or
+    /// the error if the sender is dropped without sending, and then ends. Useful for combining
+    /// receivers with stream combinators.
+    #[cfg(feature = "stream")]
+    pub fn into_stream(self) -> ReceiverStream<T> {
+        ReceiverStream::new(self)
+    }
+
+    /// Turns this receiver into a [`Stream`](futures_core::Stream) that yields the message and
+    /// then ends. If the sender is dropped without sending, the stream ends without yielding
+    /// anything. Use [`Receiver::into_stream`] to get the error instead.
+    #[cfg(feature = "stream")]
+    pub fn into_message_stream(self) -> MessageStream<T> {
+        MessageStream::new(self)
+    }
+
+    /// Returns the current logical state of the channel. Mostly useful for debugging and logging.
+    ///
+    /// This method never changes the state of the channel. Mind that the state can change at any
+    /// time while the [`Sender`] is still alive, so the returned value might be outdated already
+    /// by the time it is returned. [`ChannelState::SenderDropped`], [`ChannelState::Consumed`]
+    /// and [`ChannelState::Closed`] are final and will never change.
+    pub fn state(&self) -> ChannelState {
+        // SAFETY: the existence of the `self` parameter serves as a certificate that the receiver
+        // is still alive, meaning that even if the sender was dropped then it would have observed
+        // the fact that we're still alive and left the responsibility of deallocating the
+        // channel to us, so `self.channel` is valid
+        let channel = unsafe { self.channel_ptr.as_ref() };
+
+        // ORDERING: we do not read anything else from the channel, so there is nothing to
+        // synchronize with.
+        match channel.state.load(Relaxed) {
+            EMPTY | RECEIVING => ChannelState::Empty,
+            // The sender is in the middle of sending or being dropped. Until it is done there
+            // is no message available to receive.
+            #[cfg(any(feature = "std", feature = "async"))]
+            UNPARKING => ChannelState::Empty,
+            MESSAGE => ChannelState::MessageAvailable,
+            DISCONNECTED => ChannelState::SenderDropped,
+            RECEIVED => ChannelState::Consumed,
+            RECEIVER_CLOSED | CLOSED_SENDER_DROPPED | CLOSED_SEND_FAILED => ChannelState::Closed,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Frees the channel without running the `Drop` implementation, saving the atomic swap it
+    /// would do. Must only be called right after a receive by reference returned the message.
+    /// The sender is then always gone, so we are the last endpoint.
+    fn dealloc_received(self) {
+        let channel_ptr = self.channel_ptr;
+
+        // Don't run our Drop implementation, the cleanup happens here instead.
+        mem::forget(self);
+
+        // SAFETY: the message has been received, so the sender has been consumed and left
+        // freeing the channel to us.
+        unsafe { dealloc(channel_ptr) };
+    }
+
     /// Begins the process of receiving on the channel by reference. If the message is already
     /// ready, or the sender has disconnected, then this function will return the appropriate
     /// Result immediately. Otherwise, it will write the waker to memory, check to see if the
@@ -740,6 +2916,7 @@ impl<T> Receiver<T> {
     fn start_recv_ref<E>(
         &self,
         disconnected_error: E,
+        received_error: E,
         finish: impl FnOnce(&Channel<T>) -> Result<T, E>,
     ) -> Result<T, E> {
         // SAFETY: the existence of the `self` parameter serves as a certificate that the receiver
@@ -748,15 +2925,21 @@ impl<T> Receiver<T> {
         // channel to us, so `self.channel` is valid
         let channel = unsafe { self.channel_ptr.as_ref() };
 
+        // If a task polled us before, replace its waker with our thread waker below. Polling
+        // needs exclusive access to the receiver, so it can't be going on concurrently.
+        // SAFETY: we are the receiver, and we are not being polled
+        #[cfg(feature = "async")]
+        unsafe {
+            channel.reclaim_async_waker()
+        };
+
         // ORDERING: synchronize with the write of the message
         match channel.state.load(Acquire) {
             // The sender is alive but has not sent anything yet. We prepare to park.
             EMPTY => {
-                // Conditionally add a delay here to help the tests trigger the edge cases where
-                // the sender manages to be dropped or send something before we are able to store
-                // our waker object in the channel.
-                #[cfg(oneshot_test_delay)]
-                std::thread::sleep(std::time::Duration::from_millis(10));
+                // Lets the tests trigger the edge cases where the sender manages to be dropped or
+                // send something before we are able to store our waker object in the channel.
+                test_hooks::pre_park_hook();
 
                 // Write our waker instance to the channel.
                 // SAFETY: we are not yet in the RECEIVING state, meaning that the sender will not
@@ -784,7 +2967,7 @@ impl<T> Receiver<T> {
 
                         // ORDERING: the sender has been `mem::forget`-ed so this update only
                         // needs to be visible to us
-                        channel.state.store(DISCONNECTED, Relaxed);
+                        channel.state.store(RECEIVED, Relaxed);
 
                         // SAFETY: The MESSAGE state tells us there is a correctly initialized
                         // message
@@ -799,148 +2982,160 @@ impl<T> Receiver<T> {
                     _ => unreachable!(),
                 }
             }
-            // The sender sent the message. We take the message and mark the channel disconnected.
-            MESSAGE => {
-                // ORDERING: the sender has been `mem::forget`-ed so this update only needs to be
-                // visible to us
-                channel.state.store(DISCONNECTED, Relaxed);
-
-                // SAFETY: we are in the message state so the message is valid
-                Ok(unsafe { channel.take_message() })
-            }
-            // The sender was dropped before sending anything, or we already received the message.
-            DISCONNECTED => Err(disconnected_error),
-            // The receiver must have been `Future::poll`ed prior to this call.
-            #[cfg(feature = "async")]
-            RECEIVING | UNPARKING => panic!("{}", RECEIVER_USED_SYNC_AND_ASYNC_ERROR),
-            _ => unreachable!(),
-        }
-    }
-}
-
-#[cfg(feature = "async")]
-impl<T> core::future::Future for Receiver<T> {
-    type Output = Result<T, RecvError>;
-
-    fn poll(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Self::Output> {
-        // SAFETY: the existence of the `self` parameter serves as a certificate that the receiver
-        // is still alive, meaning that even if the sender was dropped then it would have observed
-        // the fact that we're still alive and left the responsibility of deallocating the
-        // channel to us, so `self.channel` is valid
-        let channel = unsafe { self.channel_ptr.as_ref() };
-
-        // ORDERING: we use acquire ordering to synchronize with the store of the message.
-        match channel.state.load(Acquire) {
-            // The sender is alive but has not sent anything yet.
-            EMPTY => {
-                // SAFETY: We can't be in the forbidden states, and no waker in the channel.
-                unsafe { channel.write_async_waker(cx) }
-            }
-            // We were polled again while waiting for the sender. Replace the waker with the new one.
-            RECEIVING => {
-                // ORDERING: We use relaxed ordering on both success and failure since we have not
-                // written anything above that must be released, and the individual match arms
-                // handle any additional synchronization.
-                match channel
-                    .state
-                    .compare_exchange(RECEIVING, EMPTY, Relaxed, Relaxed)
-                {
-                    // We successfully changed the state back to EMPTY. Replace the waker.
-                    // This is the most likely branch to be taken, which is why we don't use any
-                    // memory barriers in the compare_exchange above.
-                    Ok(_) => {
-                        // SAFETY: We wrote the waker in a previous call to poll. We do not need
-                        // a memory barrier since the previous write here was by ourselves.
-                        unsafe { channel.drop_waker() };
-                        // SAFETY: We can't be in the forbidden states, and no waker in the channel.
-                        unsafe { channel.write_async_waker(cx) }
-                    }
-                    // The sender sent the message while we prepared to replace the waker.
-                    // We take the message and mark the channel disconnected.
-                    // The sender has already taken the waker.
-                    Err(MESSAGE) => {
-                        // ORDERING: Synchronize with the write of the message. This branch is
-                        // unlikely to be taken.
-                        channel.state.swap(DISCONNECTED, Acquire);
-                        // SAFETY: The state tells us the sender has initialized the message.
-                        Poll::Ready(Ok(unsafe { channel.take_message() }))
-                    }
-                    // The sender was dropped before sending anything while we prepared to park.
-                    // The sender has taken the waker already.
-                    Err(DISCONNECTED) => Poll::Ready(Err(RecvError)),
-                    // The sender is currently waking us up.
-                    Err(UNPARKING) => {
-                        // We can't trust that the old waker that the sender has access to
-                        // is honored by the async runtime at this point. So we wake ourselves
-                        // up to get polled instantly again.
-                        cx.waker().wake_by_ref();
-                        Poll::Pending
-                    }
-                    _ => unreachable!(),
-                }
-            }
-            // The sender sent the message.
-            MESSAGE => {
-                // ORDERING: the sender has been dropped so this update only needs to be
-                // visible to us
-                channel.state.store(DISCONNECTED, Relaxed);
-                Poll::Ready(Ok(unsafe { channel.take_message() }))
-            }
-            // The sender was dropped before sending anything, or we already received the message.
-            DISCONNECTED => Poll::Ready(Err(RecvError)),
-            // The sender has observed the RECEIVING state and is currently reading the waker from
-            // a previous poll. We need to loop here until we observe the MESSAGE or DISCONNECTED
-            // state. We busy loop here since we know the sender is done very soon.
-            UNPARKING => loop {
-                hint::spin_loop();
-                // ORDERING: The load above has already synchronized with the write of the message.
-                match channel.state.load(Relaxed) {
-                    MESSAGE => {
-                        // ORDERING: the sender has been dropped, so this update only
-                        // needs to be visible to us
-                        channel.state.store(DISCONNECTED, Relaxed);
-                        // SAFETY: We observed the MESSAGE state
-                        break Poll::Ready(Ok(unsafe { channel.take_message() }));
-                    }
-                    DISCONNECTED => break Poll::Ready(Err(RecvError)),
-                    UNPARKING => (),
-                    _ => unreachable!(),
-                }
-            },
+            // The sender sent the message. We take the message and mark the channel disconnected.
+            MESSAGE => {
+                // ORDERING: the sender has been `mem::forget`-ed so this update only needs to be
+                // visible to us
+                channel.state.store(RECEIVED, Relaxed);
+
+                // SAFETY: we are in the message state so the message is valid
+                Ok(unsafe { channel.take_message() })
+            }
+            // The sender was dropped before sending anything.
+            DISCONNECTED => Err(disconnected_error),
+            // We already received the message.
+            RECEIVED => Err(received_error),
+            // The receiver has been closed.
+            RECEIVER_CLOSED | CLOSED_SENDER_DROPPED | CLOSED_SEND_FAILED => Err(disconnected_error),
             _ => unreachable!(),
         }
     }
 }
 
+impl<T, E> Receiver<Result<T, E>> {
+    /// Like [`Receiver::recv`], but merges a disconnected channel and an error sent by the
+    /// [`Sender`] into one [`RecvOrError`], so the result can be handled with a single `?`.
+    ///
+    /// ```
+    /// let (sender, receiver) = oneshot::channel::<Result<u32, &str>>();
+    /// sender.send(Err("failed")).unwrap();
+    /// assert_eq!(receiver.recv_flat(), Err(oneshot::RecvOrError::App("failed")));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if called after this receiver has been polled asynchronously.
+    #[cfg(feature = "std")]
+    pub fn recv_flat(self) -> Result<T, RecvOrError<E>> {
+        self.recv()?.map_err(RecvOrError::App)
+    }
+
+    /// Like [`Receiver::try_recv`], but merges a disconnected channel and an error sent by the
+    /// [`Sender`] into one [`RecvOrError`]. Returns `Ok(None)` if the sender is alive but has not
+    /// sent anything yet.
+    pub fn try_recv_flat(&self) -> Result<Option<T>, RecvOrError<E>> {
+        match self.try_recv() {
+            Ok(result) => result.map(Some).map_err(RecvOrError::App),
+            Err(TryRecvError::Empty) => Ok(None),
+            Err(TryRecvError::Disconnected) => Err(RecvOrError::Disconnected),
+        }
+    }
+
+    /// Returns a future resolving to the message like the receiver does, but merging a
+    /// disconnected channel and an error sent by the [`Sender`] into one [`RecvOrError`].
+    #[cfg(feature = "async")]
+    pub fn flatten(self) -> Flatten<T, E> {
+        Flatten::new(self)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T> core::future::Future for Receiver<T> {
+    type Output = Result<T, RecvError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Self::Output> {
+        self.get_mut().poll_recv(cx)
+    }
+}
+
+/// The receiver is terminated once polling it has returned `Ready`, with the message or with an
+/// error. A disconnect that has not been observed by a poll yet does not terminate it, so
+/// `futures::select!` still polls it once and gets the error.
+#[cfg(all(feature = "async", feature = "futures-core"))]
+impl<T> futures_core::FusedFuture for Receiver<T> {
+    fn is_terminated(&self) -> bool {
+        // SAFETY: the receiver is still alive, so the channel is valid
+        unsafe { self.channel_ptr.as_ref() }.terminated.get()
+    }
+}
+
+/// The future returned from [`Receiver::ready`].
+#[cfg(feature = "async")]
+struct Ready<'a, T> {
+    receiver: &'a mut Receiver<T>,
+}
+
+#[cfg(feature = "async")]
+impl<T> core::future::Future for Ready<'_, T> {
+    type Output = Result<(), RecvError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Self::Output> {
+        self.receiver.poll_ready(cx)
+    }
+}
+
 impl<T> Drop for Receiver<T> {
     fn drop(&mut self) {
+        trace!(endpoint = "receiver", "receiver dropped");
+
         // SAFETY: since the receiving side is still alive the sender would have observed that and
         // left deallocating the channel allocation to us.
         let channel = unsafe { self.channel_ptr.as_ref() };
 
+        // Take the waker of a sender waiting in `Sender::closed` before we signal that we are
+        // gone. After that the sender is free to deallocate the channel at any time.
+        // SAFETY: the receiver is only dropped once
+        #[cfg(feature = "async")]
+        let closed_waker = unsafe { channel.take_closed_waker() };
+
+        // If we have been polled, take the waker back before disconnecting. Once we switch to
+        // DISCONNECTED the sender may free the channel at any time, waker included, so the waker
+        // must be dropped while the channel is still ours.
+        // SAFETY: we are the receiver
+        #[cfg(feature = "async")]
+        unsafe {
+            channel.reclaim_async_waker()
+        };
+
         // Set the channel state to disconnected and read what state the receiver was in
         match channel.state.swap(DISCONNECTED, Acquire) {
             // The sender has not sent anything, nor is it dropped.
-            EMPTY => (),
+            EMPTY => {
+                // Wake up the sender if it is waiting for us to go away.
+                #[cfg(feature = "async")]
+                if let Some(waker) = closed_waker {
+                    waker.wake();
+                }
+            }
             // The sender already sent something. We must drop it, and free the channel.
             MESSAGE => {
-                // SAFETY: we are in the message state so the message is initialized
-                unsafe { channel.drop_message() };
+                // A sender blocking in `send_blocking` gets the message back instead, and frees
+                // the channel itself.
+                // SAFETY: the message has not been received
+                #[cfg(feature = "std")]
+                if unsafe { channel.notify_send_ack(ACK_RETURNED) } {
+                    return;
+                }
 
+                // Free the channel also if the destructor of the message panics.
                 // SAFETY: see safety comment at top of function
-                unsafe { dealloc(self.channel_ptr) };
-            }
-            // The receiver has been polled.
-            #[cfg(feature = "async")]
-            RECEIVING => {
-                // TODO: figure this out when async is fixed
-                unsafe { channel.drop_waker() };
+                let _free = FreeOnDrop {
+                    channel_ptr: self.channel_ptr,
+                    free: dealloc,
+                };
+
+                // SAFETY: we are in the message state so the message is initialized
+                unsafe { channel.drop_message() };
             }
-            // The sender was already dropped. We are responsible for freeing the channel.
-            DISCONNECTED => {
+            // The sender was already dropped, or we already received the message. We are
+            // responsible for freeing the channel.
+            DISCONNECTED | RECEIVED | CLOSED_SENDER_DROPPED => {
                 // SAFETY: see safety comment at top of function
                 unsafe { dealloc(self.channel_ptr) };
             }
+            // The receiver was closed. The sender, or the error from a failed send, is still
+            // alive and will observe the DISCONNECTED state and free the channel.
+            RECEIVER_CLOSED | CLOSED_SEND_FAILED => (),
             _ => unreachable!(),
         }
     }
@@ -965,6 +3160,51 @@ mod states {
     /// channel, it is disconnected after the one message it is supposed to hold has been
     /// transmitted.
     pub const DISCONNECTED: u8 = 0b010;
+    /// The message sent to the channel has been received by a non-consuming receive method.
+    /// The channel is closed just like in the DISCONNECTED state, but this allows the receiver
+    /// to tell a received message apart from a sender that was dropped without sending.
+    /// The sender is always gone once a message has been sent, so only the receiver ever stores
+    /// or observes this state.
+    pub const RECEIVED: u8 = 0b101;
+    /// The receiver has been closed with `Receiver::close` while the sender was still alive.
+    /// The receiver is still alive too. Chosen so that the sender's fetch_add and fetch_xor
+    /// operations map it to the two states below.
+    pub const RECEIVER_CLOSED: u8 = 0b111;
+    /// The sender was dropped after the receiver was closed. The receiver frees the channel.
+    pub const CLOSED_SENDER_DROPPED: u8 = RECEIVER_CLOSED ^ 0b001;
+    /// The sender tried to send after the receiver was closed. The message is owned by the
+    /// returned `SendError`. The last one of the error and the receiver to be dropped frees the
+    /// channel.
+    pub const CLOSED_SEND_FAILED: u8 = RECEIVER_CLOSED + 1;
+
+    // The values below are for the `SendAck::state` field, through which the receiver tells a
+    // sender blocking in `Sender::send_blocking` what happened to its message.
+
+    /// The message has not been received yet.
+    #[cfg(feature = "std")]
+    pub const ACK_WAITING: u8 = 0;
+    /// The receiver took the message out of the channel. The sender must not access the channel
+    /// anymore.
+    #[cfg(feature = "std")]
+    pub const ACK_TAKEN: u8 = 1;
+    /// The receiver was dropped without receiving. The message is still in the channel, and the
+    /// sender is responsible for it and for freeing the channel.
+    #[cfg(feature = "std")]
+    pub const ACK_RETURNED: u8 = 2;
+
+    // The values below are for the `Channel::closed_waker_state` field, that tracks the waker
+    // stored by `Sender::poll_closed`.
+
+    /// No waker is stored for the sender.
+    #[cfg(feature = "async")]
+    pub const CLOSED_WAKER_NONE: u8 = 0;
+    /// The sender has stored a waker that the receiver should wake when it is dropped.
+    #[cfg(feature = "async")]
+    pub const CLOSED_WAKER_REGISTERED: u8 = 1;
+    /// The receiver is being dropped or closed, or has been. Any waker the sender had stored has
+    /// been taken by the receiver. This state is final.
+    #[cfg(feature = "async")]
+    pub const CLOSED_WAKER_CLOSING: u8 = 2;
 }
 use states::*;
 
@@ -974,10 +3214,21 @@ use states::*;
 /// * The message in the channel. This memory is uninitialized until the message is sent.
 /// * The waker instance for the thread or task that is currently receiving on this channel.
 ///   This memory is uninitialized until the receiver starts receiving.
+/// * The waker of a task waiting in `Sender::closed` for the receiver to be dropped, and the
+///   state telling if that waker is initialized. Only with the `async` feature.
 struct Channel<T> {
     state: AtomicU8,
     message: UnsafeCell<MaybeUninit<T>>,
     waker: UnsafeCell<MaybeUninit<ReceiverWaker>>,
+    #[cfg(feature = "async")]
+    closed_waker_state: AtomicU8,
+    #[cfg(feature = "async")]
+    closed_waker: UnsafeCell<MaybeUninit<task::Waker>>,
+    #[cfg(feature = "std")]
+    send_ack: UnsafeCell<Option<Arc<SendAck>>>,
+    /// Set once polling the receiver has returned `Ready`. Only accessed by the receiver.
+    #[cfg(all(feature = "async", feature = "futures-core"))]
+    terminated: core::cell::Cell<bool>,
 }
 
 impl<T> Channel<T> {
@@ -986,6 +3237,14 @@ impl<T> Channel<T> {
             state: AtomicU8::new(EMPTY),
             message: UnsafeCell::new(MaybeUninit::uninit()),
             waker: UnsafeCell::new(MaybeUninit::uninit()),
+            #[cfg(feature = "async")]
+            closed_waker_state: AtomicU8::new(CLOSED_WAKER_NONE),
+            #[cfg(feature = "async")]
+            closed_waker: UnsafeCell::new(MaybeUninit::uninit()),
+            #[cfg(feature = "std")]
+            send_ack: UnsafeCell::new(None),
+            #[cfg(all(feature = "async", feature = "futures-core"))]
+            terminated: core::cell::Cell::new(false),
         }
     }
 
@@ -1043,14 +3302,15 @@ impl<T> Channel<T> {
     #[inline(always)]
     unsafe fn take_message(&self) -> T {
         #[cfg(loom)]
-        {
-            self.message.with(|ptr| ptr::read(ptr)).assume_init()
-        }
+        let message = self.message.with(|ptr| ptr::read(ptr)).assume_init();
 
         #[cfg(not(loom))]
-        {
-            ptr::read(self.message.get()).assume_init()
-        }
+        let message = ptr::read(self.message.get()).assume_init();
+
+        #[cfg(feature = "std")]
+        self.notify_send_ack(ACK_TAKEN);
+
+        message
     }
 
     #[inline(always)]
@@ -1064,6 +3324,23 @@ impl<T> Channel<T> {
         self.with_waker_mut(|slot| slot.as_mut_ptr().write(waker));
     }
 
+    /// Returns true if the stored waker is a task waker that wakes the same task as the waker in
+    /// `cx`.
+    #[cfg(feature = "async")]
+    #[inline(always)]
+    unsafe fn waker_will_wake(&self, cx: &task::Context<'_>) -> bool {
+        #[cfg(loom)]
+        {
+            self.waker
+                .with(|ptr| (*ptr).assume_init_ref().will_wake(cx))
+        }
+
+        #[cfg(not(loom))]
+        {
+            (*self.waker.get()).assume_init_ref().will_wake(cx)
+        }
+    }
+
     #[inline(always)]
     unsafe fn take_waker(&self) -> ReceiverWaker {
         #[cfg(loom)]
@@ -1083,12 +3360,236 @@ impl<T> Channel<T> {
         self.with_waker_mut(|slot| slot.assume_init_drop());
     }
 
+    /// Takes back and drops the waker stored by a previous poll of the receiver, leaving the
+    /// channel in the EMPTY state. If the sender is currently taking that waker to wake the task,
+    /// waits until it is done and the state is MESSAGE or DISCONNECTED. Other states are left
+    /// untouched.
+    ///
+    /// # Safety
+    ///
+    /// Must only be called by the receiver, while it is not being polled.
+    #[cfg(feature = "async")]
+    unsafe fn reclaim_async_waker(&self) {
+        loop {
+            // ORDERING: we only take back the waker we wrote ourselves, and whatever reads the
+            // state next synchronizes with anything the sender did.
+            match self
+                .state
+                .compare_exchange(RECEIVING, EMPTY, Relaxed, Relaxed)
+            {
+                Ok(_) => {
+                    // SAFETY: we wrote the waker when polled, and the sender does not access it
+                    // in the EMPTY state.
+                    self.drop_waker();
+                    break;
+                }
+                // The sender is taking the waker to wake the task. It's done very soon, then the
+                // state is MESSAGE or DISCONNECTED.
+                Err(UNPARKING) => hint::spin_loop(),
+                Err(_) => break,
+            }
+        }
+    }
+
+    #[cfg(feature = "async")]
+    #[inline(always)]
+    unsafe fn with_closed_waker_mut<F>(&self, op: F)
+    where
+        F: FnOnce(&mut MaybeUninit<task::Waker>),
+    {
+        #[cfg(loom)]
+        {
+            self.closed_waker.with_mut(|ptr| op(&mut *ptr))
+        }
+
+        #[cfg(not(loom))]
+        {
+            op(&mut *self.closed_waker.get())
+        }
+    }
+
+    #[cfg(feature = "async")]
+    #[inline(always)]
+    unsafe fn write_closed_waker(&self, waker: task::Waker) {
+        self.with_closed_waker_mut(|slot| slot.as_mut_ptr().write(waker));
+    }
+
+    #[cfg(feature = "async")]
+    #[inline(always)]
+    unsafe fn drop_closed_waker(&self) {
+        self.with_closed_waker_mut(|slot| slot.assume_init_drop());
+    }
+
+    /// Removes the thread waker of a receiver parked on this channel that was woken up for
+    /// another reason than this channel, and returns the state the channel is left in. That is
+    /// EMPTY if the sender has not done anything, and MESSAGE or DISCONNECTED otherwise. If the
+    /// sender is unparking the receiver this waits for it to finish.
+    ///
+    /// # Safety
+    ///
+    /// The receiver must have stored its thread waker and changed the state to RECEIVING. Since
+    /// then only the sender can have changed the state.
+    #[cfg(feature = "std")]
+    unsafe fn remove_thread_waker(&self) -> u8 {
+        // ORDERING: synchronize with the write of the message
+        match self.state.swap(EMPTY, Acquire) {
+            // The sender has not done anything, so it will never access the waker now.
+            RECEIVING => {
+                self.drop_waker();
+                EMPTY
+            }
+            // The sender is gone. Restore the state it left.
+            // ORDERING: the sender is gone, this update only needs to be visible to us
+            state @ (MESSAGE | DISCONNECTED) => {
+                self.state.store(state, Relaxed);
+                state
+            }
+            // The sender has taken the waker and is about to set the MESSAGE or DISCONNECTED
+            // state, starting from the EMPTY state we just set. It unparks us when done.
+            UNPARKING => loop {
+                thread::park();
+
+                // ORDERING: synchronize with the write of the message
+                match self.state.load(Acquire) {
+                    EMPTY => (),
+                    state @ (MESSAGE | DISCONNECTED) => break state,
+                    _ => unreachable!(),
+                }
+            },
+            _ => unreachable!(),
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[inline(always)]
+    unsafe fn with_send_ack_mut<F, R>(&self, op: F) -> R
+    where
+        F: FnOnce(&mut Option<Arc<SendAck>>) -> R,
+    {
+        #[cfg(loom)]
+        {
+            self.send_ack.with_mut(|ptr| op(&mut *ptr))
+        }
+
+        #[cfg(not(loom))]
+        {
+            op(&mut *self.send_ack.get())
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[inline(always)]
+    unsafe fn write_send_ack(&self, ack: Arc<SendAck>) {
+        self.with_send_ack_mut(|slot| *slot = Some(ack));
+    }
+
+    #[cfg(feature = "std")]
+    #[inline(always)]
+    unsafe fn take_send_ack(&self) -> Option<Arc<SendAck>> {
+        self.with_send_ack_mut(Option::take)
+    }
+
+    /// Tells a sender blocking in `Sender::send_blocking`, if there is one, that the message was
+    /// taken or returned, and wakes it up. Returns false if there is no such sender.
+    ///
+    /// # Safety
+    ///
+    /// Must only be called by the receiver, after it has synchronized with the write of the
+    /// message. For `ACK_RETURNED` the message must still be in the channel, and the receiver
+    /// must never access the channel again, since the sender frees it.
+    #[cfg(feature = "std")]
+    #[inline(always)]
+    unsafe fn notify_send_ack(&self, state: u8) -> bool {
+        match self.take_send_ack() {
+            Some(ack) => {
+                // ORDERING: our accesses of the channel must happen-before the sender continues,
+                // which might include freeing the channel
+                ack.state.store(state, Release);
+                // The ack has its own allocation, so this is fine even if the channel is gone.
+                ack.thread.unpark();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Busy waits a short while for the sender to send or be dropped, before a receiver parks
+    /// its thread. Backs off exponentially, and gives up after `RECV_SPIN_LIMIT` rounds. This
+    /// lowers the latency when the message is just about to arrive.
+    ///
+    /// Spinning is skipped on machines with a single CPU core, where the sender can't make
+    /// progress while we spin, and if `deadline` has already passed, since the caller is then
+    /// doing a non-blocking receive. It's also skipped under loom, where it would only multiply
+    /// the number of explored executions.
+    #[cfg(feature = "std")]
+    #[inline]
+    fn spin_while_empty(&self, deadline: Option<Instant>) {
+        if cfg!(loom) || !is_multi_core() {
+            return;
+        }
+        for round in 0..RECV_SPIN_LIMIT {
+            // ORDERING: this is only a hint for when to stop spinning. The caller synchronizes
+            // with the sender when reading the state again.
+            if self.state.load(Relaxed) != EMPTY
+                || matches!(deadline, Some(deadline) if Instant::now() >= deadline)
+            {
+                return;
+            }
+            for _ in 0..1u32 << round {
+                hint::spin_loop();
+            }
+        }
+    }
+
+    /// Busy waits until a receiver that has started dropping or closing, and thus is in the
+    /// CLOSED_WAKER_CLOSING state, has set the channel state to DISCONNECTED or RECEIVER_CLOSED.
+    /// The receiver does this right after taking the closed waker, so this loop is very short.
+    /// Waiting for it guarantees that a send after `Sender::closed` has completed fails.
+    #[cfg(feature = "async")]
+    #[cold]
+    fn wait_for_receiver_close(&self) {
+        // ORDERING: we do not access anything the receiver has written, so relaxed is enough.
+        while !matches!(self.state.load(Relaxed), DISCONNECTED | RECEIVER_CLOSED) {
+            hint::spin_loop();
+        }
+    }
+
+    /// Marks the receiver as closing and returns the waker stored by `Sender::poll_closed`,
+    /// if there is one.
+    ///
+    /// # Safety
+    ///
+    /// Must only be called by the receiver, before it signals that it is gone or closed.
+    #[cfg(feature = "async")]
+    unsafe fn take_closed_waker(&self) -> Option<task::Waker> {
+        // ORDERING: synchronize with the sender's write of the waker
+        match self.closed_waker_state.swap(CLOSED_WAKER_CLOSING, Acquire) {
+            CLOSED_WAKER_REGISTERED => {
+                #[cfg(loom)]
+                {
+                    Some(self.closed_waker.with(|ptr| ptr::read(ptr)).assume_init())
+                }
+
+                #[cfg(not(loom))]
+                {
+                    Some(ptr::read(self.closed_waker.get()).assume_init())
+                }
+            }
+            // No waker stored, or it was already taken by `Receiver::close`.
+            CLOSED_WAKER_NONE | CLOSED_WAKER_CLOSING => None,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Stores the task waker and switches to the RECEIVING state. Returns `Poll::Ready(Ok(()))`
+    /// if the sender sent the message in the meantime. The message is then left in the channel.
+    ///
     /// # Safety
     ///
     /// * `Channel::waker` must not have a waker stored in it when calling this method.
     /// * Channel state must not be RECEIVING or UNPARKING when calling this method.
     #[cfg(feature = "async")]
-    unsafe fn write_async_waker(&self, cx: &mut task::Context<'_>) -> Poll<Result<T, RecvError>> {
+    unsafe fn write_async_waker(&self, cx: &mut task::Context<'_>) -> Poll<Result<(), RecvError>> {
         // Write our thread instance to the channel.
         // SAFETY: we are not yet in the RECEIVING state, meaning that the sender will not
         // try to access the waker until it sees the state set to RECEIVING below
@@ -1105,7 +3606,6 @@ impl<T> Channel<T> {
             // We stored our waker, now we return and let the sender wake us up
             Ok(_) => Poll::Pending,
             // The sender sent the message while we prepared to park.
-            // We take the message and mark the channel disconnected.
             Err(MESSAGE) => {
                 // ORDERING: Synchronize with the write of the message. This branch is
                 // unlikely to be taken, so it's likely more efficient to use a fence here
@@ -1117,11 +3617,7 @@ impl<T> Channel<T> {
                 // responsible for dropping it.
                 self.drop_waker();
 
-                // ORDERING: sender does not exist, so this update only needs to be visible to us
-                self.state.store(DISCONNECTED, Relaxed);
-
-                // SAFETY: The MESSAGE state tells us there is a correctly initialized message
-                Poll::Ready(Ok(self.take_message()))
+                Poll::Ready(Ok(()))
             }
             // The sender was dropped before sending anything while we prepared to park.
             Err(DISCONNECTED) => {
@@ -1129,20 +3625,43 @@ impl<T> Channel<T> {
                 // DISCONNECTED state. This means that it did not take the waker, so we're
                 // responsible for dropping it.
                 self.drop_waker();
-                Poll::Ready(Err(RecvError))
+                Poll::Ready(Err(RecvError::Disconnected))
             }
             _ => unreachable!(),
         }
     }
 }
 
+#[cfg(feature = "async")]
+impl<T> Drop for Channel<T> {
+    fn drop(&mut self) {
+        // The sender might have registered a waker in `Sender::poll_closed` and then sent its
+        // message. If the receiver then consumed itself without being dropped, the waker is
+        // still here.
+        // ORDERING: synchronize with the sender's write of the waker
+        if self.closed_waker_state.load(Acquire) == CLOSED_WAKER_REGISTERED {
+            // SAFETY: we have exclusive access to the channel, and the waker is initialized
+            unsafe { self.drop_closed_waker() };
+        }
+    }
+}
+
+/// Shared by a sender blocking in `Sender::send_blocking` and the receiver. It lives in its own
+/// allocation, since the sender must not access the channel after the message was received.
+#[cfg(feature = "std")]
+struct SendAck {
+    state: AtomicU8,
+    thread: thread::Thread,
+}
+
 enum ReceiverWaker {
     /// The receiver is waiting synchronously. Its thread is parked.
     #[cfg(feature = "std")]
     Thread(thread::Thread),
     /// The receiver is waiting asynchronously. Its task can be woken up with this `Waker`.
-    #[cfg(feature = "async")]
-    Task(task::Waker),
+    /// Also used for the callback of `Receiver::on_receive`, see `CallbackWaker`.
+    #[cfg(any(feature = "std", feature = "async"))]
+    Task(core::task::Waker),
     /// A little hack to not make this enum an uninhibitable type when no features are enabled.
     #[cfg(not(any(feature = "async", feature = "std")))]
     _Uninhabited,
@@ -1151,6 +3670,8 @@ enum ReceiverWaker {
 impl ReceiverWaker {
     #[cfg(feature = "std")]
     pub fn current_thread() -> Self {
+        // Every blocking receive gets its waker from here before it parks.
+        assert_can_block();
         Self::Thread(thread::current())
     }
 
@@ -1159,11 +3680,26 @@ impl ReceiverWaker {
         Self::Task(cx.waker().clone())
     }
 
+    #[cfg(feature = "std")]
+    pub fn callback(callback: impl FnOnce() + Send + 'static) -> Self {
+        let callback = CallbackWaker(std::sync::Mutex::new(Some(std::boxed::Box::new(callback))));
+        Self::Task(std::sync::Arc::new(callback).into())
+    }
+
+    #[cfg(feature = "async")]
+    pub fn will_wake(&self, cx: &task::Context<'_>) -> bool {
+        match self {
+            ReceiverWaker::Task(waker) => waker.will_wake(cx.waker()),
+            #[cfg(feature = "std")]
+            ReceiverWaker::Thread(_) => false,
+        }
+    }
+
     pub fn unpark(self) {
         match self {
             #[cfg(feature = "std")]
             ReceiverWaker::Thread(thread) => thread.unpark(),
-            #[cfg(feature = "async")]
+            #[cfg(any(feature = "std", feature = "async"))]
             ReceiverWaker::Task(waker) => waker.wake(),
             #[cfg(not(any(feature = "async", feature = "std")))]
             ReceiverWaker::_Uninhabited => unreachable!(),
@@ -1171,23 +3707,139 @@ impl ReceiverWaker {
     }
 }
 
+/// Runs the callback of `Receiver::on_receive` when woken. Wrapping the callback in a `Waker`
+/// stores it in the `Task` variant, which keeps `ReceiverWaker` at 16 bytes with the default
+/// features instead of 24 for a separate variant. With only `std` enabled, the `Task` variant
+/// then always exists and grows the waker from 8 to 16 bytes. Wakers can be cloned and woken
+/// more than once, the callback only runs the first time.
+#[cfg(feature = "std")]
+struct CallbackWaker(std::sync::Mutex<Option<std::boxed::Box<dyn FnOnce() + Send>>>);
+
+#[cfg(feature = "std")]
+impl std::task::Wake for CallbackWaker {
+    fn wake(self: std::sync::Arc<Self>) {
+        let callback = self
+            .0
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .take();
+        if let Some(callback) = callback {
+            callback();
+        }
+    }
+}
+
 #[cfg(not(loom))]
 #[test]
 fn receiver_waker_size() {
     let expected: usize = match (cfg!(feature = "std"), cfg!(feature = "async")) {
         (false, false) => 0,
         (false, true) => 16,
-        (true, false) => 8,
-        (true, true) => 24,
+        (true, false) => 16,
+        (true, true) => 16,
     };
     assert_eq!(mem::size_of::<ReceiverWaker>(), expected);
 }
 
+/// The number of rounds the blocking receive methods spin before parking the thread. The spin
+/// loop backs off exponentially, so this is `2^RECV_SPIN_LIMIT - 1` spins in total.
+#[cfg(feature = "std")]
+const RECV_SPIN_LIMIT: u32 = 6;
+
+/// Returns true if the program can run on more than one CPU core. Looked up once and cached.
+#[cfg(feature = "std")]
+fn is_multi_core() -> bool {
+    const UNKNOWN: u8 = 0;
+    const SINGLE: u8 = 1;
+    const MULTI: u8 = 2;
+    // Not a loom atomic even under loom, since those can't be used in a static.
+    static CORES: core::sync::atomic::AtomicU8 = core::sync::atomic::AtomicU8::new(UNKNOWN);
+
+    // ORDERING: the value is not used to synchronize anything
+    match CORES.load(Relaxed) {
+        UNKNOWN => {
+            let multi = std::thread::available_parallelism().map_or(false, |n| n.get() > 1);
+            CORES.store(if multi { MULTI } else { SINGLE }, Relaxed);
+            multi
+        }
+        cores => cores == MULTI,
+    }
+}
+
+const SENDER_SPENT_ERROR: &str = "Invalid to send on oneshot::Sender after Sender::send_ref";
+
+/// Stands in for the channel of a [`Sender`] spent by [`Sender::send_ref`]. No channel can be
+/// allocated at the address of a static, so this never equals a real channel pointer. It is never
+/// dereferenced.
+static SPENT_SENDER: u8 = 0;
+
+#[inline(always)]
+fn spent_channel_ptr<T>() -> NonNull<Channel<T>> {
+    NonNull::from(&SPENT_SENDER).cast()
+}
+
 #[cfg(all(feature = "std", feature = "async"))]
 const RECEIVER_USED_SYNC_AND_ASYNC_ERROR: &str =
     "Invalid to call a blocking receive method on oneshot::Receiver after it has been polled";
 
+#[cfg(feature = "std")]
+const CANNOT_BLOCK_ERROR: &str = "Invalid to block on a oneshot channel on a target without \
+    threads, since nothing could ever unblock it. Receive asynchronously instead";
+
+/// Panics on targets with a single thread that can't be parked, like `wasm32-unknown-unknown`
+/// without the `atomics` target feature. Blocking there would hang forever, since the sender can
+/// only run once the receiving thread returns.
+///
+/// The WASI targets are left alone. `wasm32-wasip1-threads` has threads and the standard library
+/// parks them with `memory.atomic.wait32`, but stable Rust does not expose the `atomics` target
+/// feature to tell it apart from the other WASI targets.
+#[cfg(feature = "std")]
+#[inline(always)]
+fn assert_can_block() {
+    if cfg!(all(
+        target_arch = "wasm32",
+        target_os = "unknown",
+        not(target_feature = "atomics")
+    )) {
+        panic!("{}", CANNOT_BLOCK_ERROR);
+    }
+}
+
 #[inline]
 pub(crate) unsafe fn dealloc<T>(channel: NonNull<Channel<T>>) {
     drop(Box::from_raw(channel.as_ptr()))
 }
+
+/// Frees the channel owned by a `SendError`, after its message has been taken or dropped.
+/// If the send failed because the receiver was closed, the receiver might still be alive and
+/// sharing the channel. Then it's left to the receiver to free it.
+///
+/// # Safety
+///
+/// Must only be called by a `SendError`, once, after it is done accessing the message.
+#[inline]
+pub(crate) unsafe fn release_send_error_channel<T>(channel_ptr: NonNull<Channel<T>>) {
+    // ORDERING: we use release ordering so our access to the message happens-before the receiver
+    // frees the channel, and acquire ordering so any access of the receiver happens-before we
+    // free it.
+    match channel_ptr.as_ref().state.swap(DISCONNECTED, AcqRel) {
+        // The closed receiver is still alive. It will observe DISCONNECTED and free the channel.
+        CLOSED_SEND_FAILED => (),
+        _ => dealloc(channel_ptr),
+    }
+}
+
+/// Calls `free` with the channel when dropped. Used to free the channel after dropping a message
+/// in place, so the channel is freed even if the destructor of the message panics.
+pub(crate) struct FreeOnDrop<T> {
+    pub(crate) channel_ptr: NonNull<Channel<T>>,
+    pub(crate) free: unsafe fn(NonNull<Channel<T>>),
+}
+
+impl<T> Drop for FreeOnDrop<T> {
+    fn drop(&mut self) {
+        // SAFETY: the creator of the guard is responsible for `free` being valid to call once
+        // the guard goes out of scope
+        unsafe { (self.free)(self.channel_ptr) }
+    }
+}
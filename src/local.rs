@@ -0,0 +1,166 @@
+use super::{LocalSendError, RecvError, TryRecvError};
+use alloc::rc::Rc;
+use core::cell::Cell;
+use core::fmt;
+
+#[cfg(feature = "async")]
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{self, Poll, Waker},
+};
+
+/// Creates a new oneshot channel for use within a single thread, and returns the two endpoints,
+/// [`LocalSender`] and [`LocalReceiver`].
+///
+/// Unlike [`channel`](crate::channel), neither endpoint can be sent to another thread. In return
+/// the message does not have to be [`Send`], and the channel uses plain cells instead of atomic
+/// operations. Useful for handing over something like an `Rc` between tasks of a single threaded
+/// async runtime.
+pub fn local_channel<T>() -> (LocalSender<T>, LocalReceiver<T>) {
+    let channel = Rc::new(LocalChannel {
+        message: Cell::new(None),
+        received: Cell::new(false),
+        #[cfg(feature = "async")]
+        waker: Cell::new(None),
+    });
+    (
+        LocalSender {
+            channel: channel.clone(),
+        },
+        LocalReceiver { channel },
+    )
+}
+
+/// The channel shared by the two endpoints. Whether the other endpoint is still alive is told by
+/// the reference count.
+struct LocalChannel<T> {
+    message: Cell<Option<T>>,
+    /// Set once the message has been taken out by the receiver.
+    received: Cell<bool>,
+    /// The waker of the task that last polled the receiver.
+    #[cfg(feature = "async")]
+    waker: Cell<Option<Waker>>,
+}
+
+impl<T> LocalChannel<T> {
+    /// Returns true if the other endpoint of the channel has been dropped or consumed.
+    fn is_alone(self: &Rc<Self>) -> bool {
+        Rc::strong_count(self) == 1
+    }
+}
+
+/// Sending end of a [`local_channel`]. Sends the message on the current thread.
+pub struct LocalSender<T> {
+    channel: Rc<LocalChannel<T>>,
+}
+
+/// Receiving end of a [`local_channel`].
+///
+/// Implements `Future` when the `async` feature is enabled. There is no blocking receive, since
+/// the sender can only send on the thread that would be blocked.
+pub struct LocalReceiver<T> {
+    channel: Rc<LocalChannel<T>>,
+}
+
+impl<T> LocalSender<T> {
+    /// Sends `message` to the receiver and wakes up the task awaiting it, if any.
+    ///
+    /// Returns the message in the error if the [`LocalReceiver`] has already been dropped.
+    pub fn send(self, message: T) -> Result<(), LocalSendError<T>> {
+        if self.channel.is_alone() {
+            return Err(LocalSendError::new(message));
+        }
+        self.channel.message.set(Some(message));
+        // Dropping `self` wakes up the receiver.
+        Ok(())
+    }
+
+    /// Returns true if the associated [`LocalReceiver`] has been dropped. Then sending is
+    /// guaranteed to fail.
+    pub fn is_closed(&self) -> bool {
+        self.channel.is_alone()
+    }
+}
+
+impl<T> Drop for LocalSender<T> {
+    fn drop(&mut self) {
+        // The message was sent, or will never be. Either way the receiver can now complete.
+        #[cfg(feature = "async")]
+        if let Some(waker) = self.channel.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl<T> LocalReceiver<T> {
+    /// Checks if there is a message in the channel. Returns:
+    ///  * `Ok(message)` if there was a message in the channel.
+    ///  * `Err(Empty)` if the [`LocalSender`] is alive, but has not yet sent a message.
+    ///  * `Err(Disconnected)` if the [`LocalSender`] was dropped before sending anything or if
+    ///    the message has already been received.
+    pub fn try_recv(&self) -> Result<T, TryRecvError> {
+        self.take().map_err(|error| match error {
+            Some(_) => TryRecvError::Disconnected,
+            None => TryRecvError::Empty,
+        })
+    }
+
+    /// Returns true if the message has been received, or if the [`LocalSender`] was dropped
+    /// without sending one. Then receiving is guaranteed to fail.
+    pub fn is_closed(&self) -> bool {
+        // A `Cell` can't be looked into, so the message is taken out and put back.
+        let message = self.channel.message.take();
+        let closed = message.is_none() && (self.channel.received.get() || self.channel.is_alone());
+        self.channel.message.set(message);
+        closed
+    }
+
+    /// Takes the message out of the channel. Returns `Err(None)` if it has not been sent yet,
+    /// and `Err(Some(error))` if it never will be.
+    fn take(&self) -> Result<T, Option<RecvError>> {
+        if let Some(message) = self.channel.message.take() {
+            self.channel.received.set(true);
+            Ok(message)
+        } else if self.channel.received.get() {
+            Err(Some(RecvError::AlreadyReceived))
+        } else if self.channel.is_alone() {
+            Err(Some(RecvError::Disconnected))
+        } else {
+            Err(None)
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T> Future for LocalReceiver<T> {
+    type Output = Result<T, RecvError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Self::Output> {
+        match self.take() {
+            Ok(message) => Poll::Ready(Ok(message)),
+            Err(Some(error)) => Poll::Ready(Err(error)),
+            Err(None) => {
+                // Keep the stored waker if it wakes the same task, like `Receiver` does.
+                let waker = match self.channel.waker.take() {
+                    Some(waker) if waker.will_wake(cx.waker()) => waker,
+                    _ => cx.waker().clone(),
+                };
+                self.channel.waker.set(Some(waker));
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<T> fmt::Debug for LocalSender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LocalSender").finish_non_exhaustive()
+    }
+}
+
+impl<T> fmt::Debug for LocalReceiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LocalReceiver").finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,114 @@
+use super::{Receiver, TryRecvError};
+use core::fmt;
+
+#[cfg(any(feature = "std", feature = "async"))]
+use super::RecvError;
+#[cfg(feature = "std")]
+use super::RecvTimeoutError;
+#[cfg(feature = "async")]
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{self, Poll},
+};
+#[cfg(feature = "std")]
+use std::time::Duration;
+
+/// A receiver that transforms the message with a closure when it is received. Created by
+/// [`Receiver::map`].
+///
+/// Implements `Future` like the receiver itself when the `async` feature is enabled. The closure
+/// runs on the receiving side, never when the message is sent, and is dropped without being
+/// called once the channel is known to never deliver a message.
+pub struct Map<T, F> {
+    receiver: Receiver<T>,
+    f: Option<F>,
+}
+
+impl<T, F> Map<T, F> {
+    pub(crate) fn new(receiver: Receiver<T>, f: F) -> Self {
+        Self {
+            receiver,
+            f: Some(f),
+        }
+    }
+}
+
+impl<T, U, F: FnOnce(T) -> U> Map<T, F> {
+    /// Like [`Receiver::try_recv`], but transforms the message with the closure before
+    /// returning it.
+    pub fn try_recv(&mut self) -> Result<U, TryRecvError> {
+        match self.receiver.try_recv_mut() {
+            Ok(message) => Ok(self.apply(message)),
+            Err(TryRecvError::Disconnected) => {
+                self.f = None;
+                Err(TryRecvError::Disconnected)
+            }
+            Err(TryRecvError::Empty) => Err(TryRecvError::Empty),
+        }
+    }
+
+    /// Like [`Receiver::recv`], but transforms the message with the closure before returning
+    /// it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called after this receiver has been polled asynchronously.
+    #[cfg(feature = "std")]
+    pub fn recv(self) -> Result<U, RecvError> {
+        let Self { receiver, f } = self;
+        // The receiver is consumed, and the channel freed, before the closure runs. So a panic in
+        // the closure can't leave the channel behind.
+        let message = receiver.recv()?;
+        let f = f.expect("closure already called");
+        Ok(f(message))
+    }
+
+    /// Like [`Receiver::recv_timeout`], but transforms the message with the closure before
+    /// returning it.
+    #[cfg(feature = "std")]
+    pub fn recv_timeout(&mut self, timeout: Duration) -> Result<U, RecvTimeoutError> {
+        match self.receiver.recv_timeout(timeout) {
+            Ok(message) => Ok(self.apply(message)),
+            Err(RecvTimeoutError::Disconnected) => {
+                self.f = None;
+                Err(RecvTimeoutError::Disconnected)
+            }
+            Err(RecvTimeoutError::Timeout) => Err(RecvTimeoutError::Timeout),
+        }
+    }
+
+    fn apply(&mut self, message: T) -> U {
+        // The receiver only ever returns one message, so the closure is always there.
+        let f = self.f.take().expect("closure already called");
+        f(message)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T, U, F: FnOnce(T) -> U> Future for Map<T, F> {
+    type Output = Result<U, RecvError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        match Pin::new(&mut this.receiver).poll(cx) {
+            Poll::Ready(Ok(message)) => Poll::Ready(Ok(this.apply(message))),
+            Poll::Ready(Err(error)) => {
+                this.f = None;
+                Poll::Ready(Err(error))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+// The closure is never pinned, it's moved out before it is called.
+impl<T, F> Unpin for Map<T, F> {}
+
+impl<T, F> fmt::Debug for Map<T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Map")
+            .field("receiver", &self.receiver)
+            .finish_non_exhaustive()
+    }
+}
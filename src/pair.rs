@@ -0,0 +1,69 @@
+use super::{channel, Receiver, SendError, Sender};
+use core::fmt;
+
+/// Creates a sender that sends a clone of its message to each of two receivers.
+///
+/// A convenience for fanning a single value out to two consumers without managing two channels
+/// by hand. Under the hood these are two separate oneshot channels, so each receiver owns its
+/// own copy of the message and either can be dropped independently of the other.
+///
+/// ```
+/// let (sender, receiver1, receiver2) = oneshot::channel_pair::<String>();
+/// sender.send(String::from("hello")).unwrap();
+/// assert_eq!(receiver1.try_recv().unwrap(), "hello");
+/// assert_eq!(receiver2.try_recv().unwrap(), "hello");
+/// ```
+///
+/// The sender is a [`PairSender`] rather than a [`Sender`], since a `Sender` always belongs
+/// to exactly one channel.
+pub fn channel_pair<T: Clone>() -> (PairSender<T>, Receiver<T>, Receiver<T>) {
+    let (first, first_receiver) = channel();
+    let (second, second_receiver) = channel();
+    (
+        PairSender { first, second },
+        first_receiver,
+        second_receiver,
+    )
+}
+
+/// Sending end of a [`channel_pair`].
+pub struct PairSender<T> {
+    first: Sender<T>,
+    second: Sender<T>,
+}
+
+impl<T: Clone> PairSender<T> {
+    /// Sends a clone of `message` to the first receiver and `message` itself to the second.
+    ///
+    /// Succeeds if at least one of the receivers was alive. If both have been dropped, the
+    /// message is handed back in the error. The message is not cloned if the first receiver is
+    /// already known to be gone.
+    pub fn send(self, message: T) -> Result<(), SendError<T>> {
+        let first_result = if self.first.is_closed() {
+            Err(())
+        } else {
+            self.first.send(message.clone()).map_err(|_| ())
+        };
+        match (first_result, self.second.send(message)) {
+            (Err(()), Err(error)) => Err(error),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl<T> PairSender<T> {
+    /// Returns true if both receivers have been dropped or closed. Then sending is guaranteed to
+    /// fail.
+    pub fn is_closed(&self) -> bool {
+        self.first.is_closed() && self.second.is_closed()
+    }
+}
+
+impl<T> fmt::Debug for PairSender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PairSender")
+            .field("first", &self.first)
+            .field("second", &self.second)
+            .finish()
+    }
+}
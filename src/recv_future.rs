@@ -0,0 +1,51 @@
+use super::{Receiver, RecvError};
+use core::{
+    fmt,
+    future::Future,
+    pin::Pin,
+    task::{self, Poll},
+};
+
+/// A future receiving the message of a borrowed [`Receiver`]. Created by
+/// [`Receiver::recv_async`].
+///
+/// # Cancellation safety
+///
+/// Dropping the future before it completes takes back the waker it registered, so the
+/// [`Sender`](crate::Sender) does not wake a task that stopped waiting. A message sent before or
+/// after the drop stays in the channel, and a new future from [`Receiver::recv_async`] returns
+/// it. This makes `recv_async` safe to use in a `select!` loop.
+pub struct RecvFuture<'a, T> {
+    receiver: &'a mut Receiver<T>,
+}
+
+impl<'a, T> RecvFuture<'a, T> {
+    pub(crate) fn new(receiver: &'a mut Receiver<T>) -> Self {
+        Self { receiver }
+    }
+}
+
+impl<T> Future for RecvFuture<'_, T> {
+    type Output = Result<T, RecvError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Self::Output> {
+        self.get_mut().receiver.poll_recv(cx)
+    }
+}
+
+impl<T> Drop for RecvFuture<'_, T> {
+    fn drop(&mut self) {
+        // SAFETY: the receiver is alive, so the channel is valid
+        let channel = unsafe { self.receiver.channel_ptr.as_ref() };
+        // SAFETY: we borrow the receiver mutably, so it is not being polled by anyone else
+        unsafe { channel.reclaim_async_waker() };
+    }
+}
+
+impl<T> fmt::Debug for RecvFuture<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RecvFuture")
+            .field("receiver", &self.receiver)
+            .finish()
+    }
+}
@@ -0,0 +1,318 @@
+//! A set of receivers yielding their messages in the order they arrive.
+
+use super::{Receiver, RecvError};
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+use std::sync::atomic::{AtomicBool, AtomicPtr, Ordering::*};
+use std::sync::{Arc, Weak};
+use std::task::{self, Poll, Wake, Waker};
+use std::{fmt, ptr};
+
+/// A set of keyed receivers, yielding each message together with its key as it arrives.
+///
+/// A purpose built alternative to a `FuturesUnordered` of receivers, for example for keeping
+/// track of requests that are waiting for their responses. No future is boxed. Each receiver is
+/// polled with a waker of its own, and when woken that waker pushes itself onto a lock-free queue
+/// and wakes the task polling the set. [`RecvSet::poll_next`] then only polls the receivers in
+/// the queue. Only if the task's waker changes between polls are all pending receivers polled
+/// again, to hand them the new waker.
+///
+/// ```
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let mut set = oneshot::RecvSet::new();
+/// let (sender1, receiver1) = oneshot::channel::<&str>();
+/// let (sender2, receiver2) = oneshot::channel::<&str>();
+/// set.insert(1, receiver1);
+/// set.insert(2, receiver2);
+/// sender2.send("second").unwrap();
+/// let next = futures_util::future::poll_fn(|cx| set.poll_next(cx)).await;
+/// assert_eq!(next, Some((2, Ok("second"))));
+/// assert_eq!(set.len(), 1);
+/// # drop(sender1);
+/// # });
+/// ```
+pub struct RecvSet<K, T> {
+    // Indexed by the slot of the entry. `None` for free slots.
+    entries: Vec<Option<Entry<K, T>>>,
+    free: Vec<usize>,
+    slots: HashMap<K, usize>,
+    // Slots to poll before looking at the ready queue. Might hold freed or reused slots, which
+    // are then polled for nothing.
+    to_poll: VecDeque<usize>,
+    queue: Arc<ReadyQueue>,
+    // The waker of the task polling the set. `None` before the first poll.
+    parent: Option<Waker>,
+}
+
+struct Entry<K, T> {
+    key: K,
+    receiver: Receiver<T>,
+    // The node the receiver was last polled with, as a node and as a waker. `None` until the
+    // entry is first polled, or after the task's waker changed.
+    node: Option<(Arc<Node>, Waker)>,
+}
+
+/// A lock-free stack of the nodes whose receivers have been woken. Each pushed node holds a
+/// strong count of its `Arc`.
+struct ReadyQueue {
+    head: AtomicPtr<Node>,
+}
+
+/// The waker of an entry, and its link in the [`ReadyQueue`].
+struct Node {
+    // Weak, so the nodes in the queue don't keep it alive. A node woken after the set was
+    // dropped is not pushed.
+    queue: Weak<ReadyQueue>,
+    slot: usize,
+    parent: Waker,
+    // Set while the node is in the queue.
+    queued: AtomicBool,
+    next: AtomicPtr<Node>,
+}
+
+impl Wake for Node {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref()
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        // ORDERING: the queue synchronizes on its own, this only keeps the node from being pushed
+        // twice.
+        if self.queued.swap(true, Relaxed) {
+            return;
+        }
+        let queue = match self.queue.upgrade() {
+            Some(queue) => queue,
+            None => return,
+        };
+        let node = Arc::into_raw(self.clone()) as *mut Node;
+        let mut head = queue.head.load(Relaxed);
+        loop {
+            self.next.store(head, Relaxed);
+            // ORDERING: release the write of `next` to the consumer taking the stack
+            match queue
+                .head
+                .compare_exchange_weak(head, node, Release, Relaxed)
+            {
+                Ok(_) => break,
+                Err(current) => head = current,
+            }
+        }
+        self.parent.wake_by_ref();
+    }
+}
+
+impl ReadyQueue {
+    /// Takes all nodes in the queue, in the order they were pushed.
+    fn take_all(&self) -> Vec<Arc<Node>> {
+        // ORDERING: synchronize with the pushes of the nodes
+        let mut node = self.head.swap(ptr::null_mut(), Acquire);
+        let mut nodes = Vec::new();
+        while !node.is_null() {
+            // SAFETY: every pointer in the stack came from `Arc::into_raw` when it was pushed,
+            // and we own the stack after taking it out of `head`.
+            let arc = unsafe { Arc::from_raw(node) };
+            node = arc.next.load(Relaxed);
+            nodes.push(arc);
+        }
+        nodes.reverse();
+        nodes
+    }
+}
+
+impl Drop for ReadyQueue {
+    fn drop(&mut self) {
+        // Release the nodes still in the queue.
+        drop(self.take_all());
+    }
+}
+
+impl<K: Hash + Eq + Clone, T> RecvSet<K, T> {
+    /// Creates an empty set.
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            free: Vec::new(),
+            slots: HashMap::new(),
+            to_poll: VecDeque::new(),
+            queue: Arc::new(ReadyQueue {
+                head: AtomicPtr::new(ptr::null_mut()),
+            }),
+            parent: None,
+        }
+    }
+
+    /// Returns the number of receivers in the set.
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Returns true if the set holds no receivers.
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    /// Returns true if the set holds a receiver for `key`.
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.slots.contains_key(key)
+    }
+
+    /// Adds `receiver` to the set under `key`. If the set already had a receiver for `key`, it is
+    /// replaced and returned.
+    pub fn insert(&mut self, key: K, receiver: Receiver<T>) -> Option<Receiver<T>> {
+        let replaced = self.remove(&key);
+        let entry = Entry {
+            key: key.clone(),
+            receiver,
+            node: None,
+        };
+        let slot = match self.free.pop() {
+            Some(slot) => {
+                self.entries[slot] = Some(entry);
+                slot
+            }
+            None => {
+                self.entries.push(Some(entry));
+                self.entries.len() - 1
+            }
+        };
+        self.slots.insert(key, slot);
+        self.to_poll.push_back(slot);
+        // The task polling the set has to poll the new receiver.
+        if let Some(parent) = &self.parent {
+            parent.wake_by_ref();
+        }
+        replaced
+    }
+
+    /// Removes the receiver for `key` from the set and returns it, whether its message has
+    /// arrived or not. Its sender is not affected.
+    pub fn remove(&mut self, key: &K) -> Option<Receiver<T>> {
+        let slot = self.slots.remove(key)?;
+        let entry = self.entries[slot].take().unwrap();
+        self.free.push(slot);
+        // The receiver might still hold the waker of its entry. Take it back, so the set is
+        // not woken up for a receiver it no longer has.
+        // SAFETY: the receiver is alive, so the channel is valid, and we own it, so it is not
+        // being polled.
+        unsafe { entry.receiver.channel_ptr.as_ref().reclaim_async_waker() };
+        Some(entry.receiver)
+    }
+
+    /// Polls for the next message or disconnect of any receiver in the set. The receiver is
+    /// then removed, and its key is returned with the result.
+    ///
+    /// Returns `Poll::Ready(None)` if the set is empty. Otherwise `Poll::Pending` is returned
+    /// until a receiver completes, and the task is woken up when one does or when a receiver is
+    /// inserted.
+    pub fn poll_next(
+        &mut self,
+        cx: &mut task::Context<'_>,
+    ) -> Poll<Option<(K, Result<T, RecvError>)>> {
+        let new_parent = match &self.parent {
+            Some(parent) => !parent.will_wake(cx.waker()),
+            None => true,
+        };
+        if new_parent {
+            self.parent = Some(cx.waker().clone());
+            // The nodes of the entries wake the old waker. Poll everything with new nodes.
+            for (slot, entry) in self.entries.iter_mut().enumerate() {
+                if let Some(entry) = entry {
+                    entry.node = None;
+                    self.to_poll.push_back(slot);
+                }
+            }
+        }
+
+        loop {
+            let slot = match self.to_poll.pop_front() {
+                Some(slot) => slot,
+                None => {
+                    let nodes = self.queue.take_all();
+                    if nodes.is_empty() {
+                        break;
+                    }
+                    for node in nodes {
+                        // Cleared before polling, so a wake during the poll is not missed.
+                        // ORDERING: see `Node::wake_by_ref`
+                        node.queued.store(false, Relaxed);
+                        // Nodes of removed entries, or from before the task's waker changed,
+                        // are skipped.
+                        let current = match &self.entries[node.slot] {
+                            Some(Entry {
+                                node: Some((current, _)),
+                                ..
+                            }) => Arc::ptr_eq(current, &node),
+                            _ => false,
+                        };
+                        if current {
+                            self.to_poll.push_back(node.slot);
+                        }
+                    }
+                    continue;
+                }
+            };
+            let entry = match &mut self.entries[slot] {
+                Some(entry) => entry,
+                None => continue,
+            };
+            let queue = &self.queue;
+            let (_, waker) = entry.node.get_or_insert_with(|| {
+                let node = Arc::new(Node {
+                    queue: Arc::downgrade(queue),
+                    slot,
+                    parent: cx.waker().clone(),
+                    queued: AtomicBool::new(false),
+                    next: AtomicPtr::new(ptr::null_mut()),
+                });
+                (node.clone(), Waker::from(node))
+            });
+            let poll = entry
+                .receiver
+                .poll_recv(&mut task::Context::from_waker(waker));
+            if let Poll::Ready(result) = poll {
+                let entry = self.entries[slot].take().unwrap();
+                self.free.push(slot);
+                self.slots.remove(&entry.key);
+                return Poll::Ready(Some((entry.key, result)));
+            }
+        }
+
+        if self.slots.is_empty() {
+            Poll::Ready(None)
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+// Neither the keys nor the receivers are ever pinned.
+impl<K, T> Unpin for RecvSet<K, T> {}
+
+impl<K: Hash + Eq + Clone, T> Default for RecvSet<K, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: fmt::Debug, T> fmt::Debug for RecvSet<K, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.slots.keys()).finish()
+    }
+}
+
+#[cfg(feature = "stream")]
+impl<K: Hash + Eq + Clone, T> futures_core::Stream for RecvSet<K, T> {
+    type Item = (K, Result<T, RecvError>);
+
+    fn poll_next(
+        self: core::pin::Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        RecvSet::poll_next(self.get_mut(), cx)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len(), Some(self.len()))
+    }
+}
@@ -0,0 +1,539 @@
+use super::{Receiver, RecvError};
+
+#[cfg(feature = "std")]
+use super::{assert_can_block, states::*, thread, Channel, ReceiverWaker};
+#[cfg(feature = "std")]
+#[cfg(not(loom))]
+use core::sync::atomic::Ordering::*;
+#[cfg(feature = "std")]
+#[cfg(loom)]
+use loom::sync::atomic::Ordering::*;
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "async")]
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{self, Poll},
+};
+
+/// Blocks until the first of `receivers` completes, and returns its index together with the
+/// message, or the error if its sender was dropped. Messages arriving on the other receivers are
+/// left in their channels.
+///
+/// The thread only parks once, and is woken up by whichever sender is first. If several
+/// receivers are already complete when called, the one with the lowest index is picked.
+///
+/// If a receiver has been polled asynchronously before, the waker stored by that poll is
+/// removed, like with [`Receiver::recv_mut`].
+///
+/// # Panics
+///
+/// Panics if `receivers` is empty.
+#[cfg(feature = "std")]
+pub fn select_any<T>(receivers: &[Receiver<T>]) -> (usize, Result<T, RecvError>) {
+    assert!(!receivers.is_empty(), "select_any called without receivers");
+
+    // See `select_blocking_inner`.
+    // SAFETY: `Receiver` is not `Sync` and polling it needs a mutable reference, so the
+    // receivers are not being polled while we borrow them
+    #[cfg(feature = "async")]
+    for receiver in receivers {
+        unsafe { receiver.channel_ptr.as_ref().reclaim_async_waker() };
+    }
+
+    // Store our thread waker in every channel, until one is found to be complete already.
+    // The wakers are stored in `receivers[..registered]`.
+    let mut registered = 0;
+    let mut complete = None;
+    for (index, receiver) in receivers.iter().enumerate() {
+        // SAFETY: the receiver is alive, so the channel is valid
+        let channel = unsafe { receiver.channel_ptr.as_ref() };
+
+        // ORDERING: the message is only accessed by `try_recv` below, which synchronizes
+        match channel.state.load(Relaxed) {
+            EMPTY => {
+                // See `Receiver::start_recv_ref` for safety and ordering.
+                unsafe { channel.write_waker(ReceiverWaker::current_thread()) };
+                match channel
+                    .state
+                    .compare_exchange(EMPTY, RECEIVING, Release, Relaxed)
+                {
+                    Ok(_) => registered += 1,
+                    // The sender sent or was dropped while we prepared to park. It did not take
+                    // our waker.
+                    Err(MESSAGE | DISCONNECTED) => {
+                        // SAFETY: we wrote the waker above and the sender never saw it
+                        unsafe { channel.drop_waker() };
+                        complete = Some(index);
+                        break;
+                    }
+                    _ => unreachable!(),
+                }
+            }
+            // Has a message, or can only return an error.
+            _ => {
+                complete = Some(index);
+                break;
+            }
+        }
+    }
+
+    let index = loop {
+        if let Some(index) = complete {
+            break index;
+        }
+        thread::park();
+
+        // ORDERING: the message is only accessed by `try_recv` below, which synchronizes
+        complete = receivers[..registered].iter().position(|receiver| {
+            // SAFETY: the receiver is alive, so the channel is valid
+            let channel = unsafe { receiver.channel_ptr.as_ref() };
+            channel.state.load(Relaxed) != RECEIVING
+        });
+    };
+
+    for receiver in &receivers[..registered] {
+        // SAFETY: the receiver is alive, so the channel is valid. We stored our waker in it
+        // and changed the state to RECEIVING above.
+        unsafe { receiver.channel_ptr.as_ref().remove_thread_waker() };
+    }
+
+    let result = receivers[index].try_recv().map_err(|_| {
+        // SAFETY: the receiver is alive, so the channel is valid
+        let channel = unsafe { receivers[index].channel_ptr.as_ref() };
+        // ORDERING: the state no longer changes once `try_recv` found no message
+        match channel.state.load(Relaxed) {
+            RECEIVED => RecvError::AlreadyReceived,
+            _ => RecvError::Disconnected,
+        }
+    });
+    (index, result)
+}
+
+/// Blocks until the first of `a` and `b` receives its message, and returns it together with
+/// the other receiver, which can then be received from or kept for later. The thread only
+/// parks once at a time, and is woken up by whichever sender is first.
+///
+#[cfg_attr(feature = "async", doc = "Behaves like the async [`select`].")]
+#[cfg_attr(not(feature = "async"), doc = "Behaves like the async `select`.")]
+/// Nothing is ever received from the returned receiver, so a message sent to it while or after
+/// this returns stays in its channel. If one sender is dropped without sending, this keeps
+/// waiting on the other receiver. It returns [`Select::Disconnected`] only when both are. If both
+/// messages are already there, `a` wins.
+///
+/// If a receiver has been polled asynchronously before, the waker stored by that poll is
+/// removed, like with [`Receiver::recv_mut`].
+///
+/// ```
+/// let (sender1, receiver1) = oneshot::channel::<u32>();
+/// let (sender2, receiver2) = oneshot::channel::<&str>();
+/// std::thread::spawn(move || sender2.send("first").unwrap());
+/// let receiver1 = match oneshot::select_blocking(receiver1, receiver2) {
+///     oneshot::Select::Right(message, receiver1) => {
+///         assert_eq!(message, "first");
+///         receiver1
+///     }
+///     _ => unreachable!(),
+/// };
+/// sender1.send(2).unwrap();
+/// assert_eq!(receiver1.recv(), Ok(2));
+/// ```
+#[cfg(feature = "std")]
+pub fn select_blocking<A, B>(a: Receiver<A>, b: Receiver<B>) -> Select<A, B> {
+    match select_blocking_inner(a, b, None) {
+        Ok(select) => select,
+        Err(_) => unreachable!(),
+    }
+}
+
+/// Like [`select_blocking`], but does not block longer than `timeout`. If neither receiver
+/// got its message by then, both receivers are handed back in the error.
+///
+/// If the supplied `timeout` is so large that Rust's `Instant` type can't represent this point
+/// in the future this falls back to blocking without a timeout.
+#[cfg(feature = "std")]
+pub fn select_blocking_timeout<A, B>(
+    a: Receiver<A>,
+    b: Receiver<B>,
+    timeout: Duration,
+) -> Result<Select<A, B>, (Receiver<A>, Receiver<B>)> {
+    select_blocking_inner(a, b, Instant::now().checked_add(timeout))
+}
+
+/// Blocks until every one of `receivers` has its message or is disconnected, and returns one
+/// result per receiver, in the order of `receivers`.
+///
+/// The receivers are received from one after the other. Messages that are already there are
+/// taken without parking, and the thread parks at most once for each message that is still
+/// missing when its turn comes. Messages arriving on receivers further down the list while
+/// waiting are taken without parking once their turn comes. So this works even on targets that
+/// can't block, as long as every channel is already complete.
+///
+#[cfg_attr(
+    all(feature = "async", not(loom)),
+    doc = "The blocking counterpart of [`join_all`](crate::join_all)."
+)]
+#[cfg_attr(
+    not(all(feature = "async", not(loom))),
+    doc = "The blocking counterpart of `join_all`."
+)]
+///
+/// If a receiver has been polled asynchronously before, the waker stored by that poll is
+/// removed, like with [`Receiver::recv_mut`].
+///
+/// ```
+/// let (senders, receivers): (Vec<_>, Vec<_>) = (0..3).map(|_| oneshot::channel()).unzip();
+/// std::thread::spawn(move || {
+///     for (i, sender) in senders.into_iter().enumerate().rev() {
+///         sender.send(i).unwrap();
+///     }
+/// });
+/// assert_eq!(oneshot::wait_all(receivers), [Ok(0), Ok(1), Ok(2)]);
+/// ```
+#[cfg(feature = "std")]
+pub fn wait_all<T>(receivers: Vec<Receiver<T>>) -> Vec<Result<T, RecvError>> {
+    // `recv_mut` only checks that the thread can block once it has to park.
+    receivers
+        .into_iter()
+        .map(|mut receiver| receiver.recv_mut())
+        .collect()
+}
+
+/// Blocks until the first of `receivers` receives its message, and returns its index and the
+/// message together with all the other receivers, in their original order. Dropping them closes
+/// their channels, so their senders can stop early. Useful for hedged requests, where the same
+/// request goes to several servers and the first response wins.
+///
+/// Works like [`select_blocking`] for any number of receivers. The thread only parks once at a
+/// time, and is woken up by whichever sender is first. Receivers whose sender is dropped are
+/// skipped, and `Err(Disconnected)` is returned only if no message can arrive on any of them,
+/// or if `receivers` is empty. If several messages are already there, the lowest index wins.
+///
+/// If a receiver has been polled asynchronously before, the waker stored by that poll is
+/// removed, like with [`Receiver::recv_mut`].
+///
+/// ```
+/// let (senders, receivers): (Vec<_>, Vec<_>) = (0..3).map(|_| oneshot::channel()).unzip();
+/// let mut senders = senders.into_iter();
+/// drop(senders.next());
+/// senders.next().unwrap().send("fast").unwrap();
+/// let (index, message, others) = oneshot::race_blocking(receivers).unwrap();
+/// assert_eq!((index, message, others.len()), (1, "fast", 2));
+/// ```
+#[cfg(feature = "std")]
+pub fn race_blocking<T>(
+    mut receivers: Vec<Receiver<T>>,
+) -> Result<(usize, T, Vec<Receiver<T>>), RecvError> {
+    assert_can_block();
+
+    let winner = {
+        // SAFETY: the receivers are alive, so the channels are valid
+        let channels: Vec<&Channel<T>> = receivers
+            .iter()
+            .map(|receiver| unsafe { receiver.channel_ptr.as_ref() })
+            .collect();
+
+        // See `select_blocking_inner`.
+        // SAFETY: we own the receivers, so they are not being polled
+        #[cfg(feature = "async")]
+        for channel in &channels {
+            unsafe { channel.reclaim_async_waker() };
+        }
+
+        // No need to register in the channels after one that already has its message.
+        let mut registered = Vec::with_capacity(channels.len());
+        for channel in &channels {
+            let is_registered = register_thread_waker(channel);
+            registered.push(is_registered);
+            // ORDERING: the message is only accessed by `try_recv` below, which synchronizes
+            if !is_registered && channel.state.load(Relaxed) == MESSAGE {
+                break;
+            }
+        }
+
+        loop {
+            let mut message = false;
+            let mut finished = true;
+            for channel in &channels {
+                // ORDERING: see above
+                let state = channel.state.load(Relaxed);
+                message |= state == MESSAGE;
+                finished &= is_finished(state);
+            }
+            if message || finished {
+                break;
+            }
+            thread::park();
+        }
+
+        // Take our waker back from every channel we registered in. Senders that did not
+        // send yet leave their channel EMPTY.
+        let mut winner = None;
+        for (index, (channel, is_registered)) in channels.iter().zip(registered).enumerate() {
+            let state = if is_registered {
+                // SAFETY: we stored our waker in this channel and changed its state to
+                // RECEIVING above
+                unsafe { channel.remove_thread_waker() }
+            } else {
+                // ORDERING: the state no longer changes once it is not EMPTY
+                channel.state.load(Relaxed)
+            };
+            if winner.is_none() && state == MESSAGE {
+                winner = Some(index);
+            }
+        }
+        winner
+    };
+
+    match winner {
+        Some(index) => {
+            let message = receivers.remove(index).try_recv().unwrap();
+            Ok((index, message, receivers))
+        }
+        None => Err(RecvError::Disconnected),
+    }
+}
+
+#[cfg(feature = "std")]
+fn select_blocking_inner<A, B>(
+    a: Receiver<A>,
+    b: Receiver<B>,
+    deadline: Option<Instant>,
+) -> Result<Select<A, B>, (Receiver<A>, Receiver<B>)> {
+    assert_can_block();
+
+    // SAFETY: the receivers are alive, so the channels are valid
+    let (channel_a, channel_b) = unsafe { (a.channel_ptr.as_ref(), b.channel_ptr.as_ref()) };
+
+    // If a task polled a receiver before, replace its waker with our thread waker below.
+    // SAFETY: we own the receivers, so they are not being polled
+    #[cfg(feature = "async")]
+    unsafe {
+        channel_a.reclaim_async_waker();
+        channel_b.reclaim_async_waker();
+    }
+
+    let registered_a = register_thread_waker(channel_a);
+    let registered_b = register_thread_waker(channel_b);
+
+    loop {
+        // ORDERING: the message is only accessed by `try_recv` below, which synchronizes
+        let (state_a, state_b) = (channel_a.state.load(Relaxed), channel_b.state.load(Relaxed));
+        if state_a == MESSAGE
+            || state_b == MESSAGE
+            || (is_finished(state_a) && is_finished(state_b))
+        {
+            break;
+        }
+        match deadline {
+            Some(deadline) => {
+                let now = Instant::now();
+                if now >= deadline {
+                    break;
+                }
+                thread::park_timeout(deadline - now);
+            }
+            None => thread::park(),
+        }
+    }
+
+    // Take our waker back from the losing channel, and from the winner in case the sender has
+    // not done anything with it yet. A channel that neither sent nor disconnected is left EMPTY.
+    // SAFETY: we stored our waker in the registered channels and changed their state to
+    // RECEIVING above
+    let state_a = match registered_a {
+        true => unsafe { channel_a.remove_thread_waker() },
+        // ORDERING: the state no longer changes once it is not EMPTY
+        false => channel_a.state.load(Relaxed),
+    };
+    let state_b = match registered_b {
+        true => unsafe { channel_b.remove_thread_waker() },
+        // ORDERING: see above
+        false => channel_b.state.load(Relaxed),
+    };
+
+    if state_a == MESSAGE {
+        let message = a.try_recv().unwrap();
+        Ok(Select::Left(message, b))
+    } else if state_b == MESSAGE {
+        let message = b.try_recv().unwrap();
+        Ok(Select::Right(message, a))
+    } else if is_finished(state_a) && is_finished(state_b) {
+        Ok(Select::Disconnected)
+    } else {
+        Err((a, b))
+    }
+}
+
+/// Stores a waker for the current thread in `channel` if the sender has not sent or been
+/// dropped yet. Returns true if it was stored and the channel changed to RECEIVING.
+#[cfg(feature = "std")]
+fn register_thread_waker<T>(channel: &Channel<T>) -> bool {
+    // ORDERING: the message is only accessed by `try_recv`, which synchronizes
+    if channel.state.load(Relaxed) != EMPTY {
+        return false;
+    }
+    // See `Receiver::start_recv_ref` for safety and ordering.
+    unsafe { channel.write_waker(ReceiverWaker::current_thread()) };
+    match channel
+        .state
+        .compare_exchange(EMPTY, RECEIVING, Release, Relaxed)
+    {
+        Ok(_) => true,
+        // The sender sent or was dropped while we prepared to park. It did not take our waker.
+        Err(MESSAGE | DISCONNECTED) => {
+            // SAFETY: we wrote the waker above and the sender never saw it
+            unsafe { channel.drop_waker() };
+            false
+        }
+        _ => unreachable!(),
+    }
+}
+
+/// Returns true if a channel in `state` will never get a message.
+#[cfg(feature = "std")]
+fn is_finished(state: u8) -> bool {
+    !matches!(state, EMPTY | RECEIVING | UNPARKING | MESSAGE)
+}
+
+#[cfg_attr(feature = "std", doc = "Async counterpart of [`select_any`].")]
+#[cfg_attr(not(feature = "std"), doc = "Async counterpart of `select_any`.")]
+/// Returns a future resolving to the index and result of the first of `receivers` to complete.
+///
+/// Every receiver that is not complete is polled with the task's waker. Afterwards they are
+/// considered to have been polled asynchronously, so the thread blocking receive methods must
+/// not be used on them.
+///
+/// # Panics
+///
+/// The future panics when polled if `receivers` is empty.
+#[cfg(feature = "async")]
+pub fn select_any_async<T>(receivers: &mut [Receiver<T>]) -> SelectAny<'_, T> {
+    SelectAny { receivers }
+}
+
+/// The future returned from [`select_any_async`].
+#[cfg(feature = "async")]
+#[derive(Debug)]
+pub struct SelectAny<'a, T> {
+    receivers: &'a mut [Receiver<T>],
+}
+
+#[cfg(feature = "async")]
+impl<T> Future for SelectAny<'_, T> {
+    type Output = (usize, Result<T, RecvError>);
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Self::Output> {
+        assert!(
+            !self.receivers.is_empty(),
+            "select_any_async called without receivers"
+        );
+        for (index, receiver) in self.receivers.iter_mut().enumerate() {
+            if let Poll::Ready(result) = Pin::new(receiver).poll(cx) {
+                return Poll::Ready((index, result));
+            }
+        }
+        Poll::Pending
+    }
+}
+
+/// Returns a future resolving with the message of whichever of `a` and `b` completes first,
+/// together with the other receiver, which can then be awaited or kept for later.
+///
+/// Nothing is ever received from the returned receiver, so a message sent to it while or after
+/// the future completes stays in its channel. If one sender is dropped without sending, the
+/// future keeps waiting on the other receiver. It resolves with [`Select::Disconnected`] only
+/// when both are. If both messages are already there, `a` wins.
+///
+/// ```
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let (fast_sender, fast) = oneshot::channel::<u32>();
+/// let (slow_sender, slow) = oneshot::channel::<&str>();
+/// fast_sender.send(1).unwrap();
+/// let slow = match oneshot::select(fast, slow).await {
+///     oneshot::Select::Left(message, slow) => {
+///         assert_eq!(message, 1);
+///         slow
+///     }
+///     _ => unreachable!(),
+/// };
+/// slow_sender.send("later").unwrap();
+/// assert_eq!(slow.await, Ok("later"));
+/// # });
+/// ```
+#[cfg(feature = "async")]
+pub fn select<A, B>(a: Receiver<A>, b: Receiver<B>) -> impl Future<Output = Select<A, B>> {
+    SelectTwo {
+        receivers: Some((a, b)),
+    }
+}
+
+#[cfg_attr(
+    all(feature = "async", feature = "std"),
+    doc = "The output of [`select`] and [`select_blocking`]."
+)]
+#[cfg_attr(not(feature = "std"), doc = "The output of [`select`].")]
+#[cfg_attr(not(feature = "async"), doc = "The output of [`select_blocking`].")]
+#[derive(Debug)]
+pub enum Select<A, B> {
+    /// The first receiver got a message. Holds it and the second receiver.
+    Left(A, Receiver<B>),
+    /// The second receiver got a message. Holds it and the first receiver.
+    Right(B, Receiver<A>),
+    /// Both senders were dropped without sending.
+    Disconnected,
+}
+
+/// The future returned from [`select`].
+#[cfg(feature = "async")]
+struct SelectTwo<A, B> {
+    // Only `None` once the future has completed.
+    receivers: Option<(Receiver<A>, Receiver<B>)>,
+}
+
+/// Prepares the receiver that lost a [`select`] for being handed back to the caller.
+#[cfg(feature = "async")]
+fn reclaimed<T>(loser: Receiver<T>) -> Receiver<T> {
+    // The loser has been polled with the waker of the selecting task. Take it back, so the task
+    // is not woken up by a message it no longer waits for.
+    // SAFETY: the receiver is alive, so the channel is valid, and we own it, so it is not being
+    // polled.
+    unsafe { loser.channel_ptr.as_ref().reclaim_async_waker() };
+    loser
+}
+
+#[cfg(feature = "async")]
+impl<A, B> Future for SelectTwo<A, B> {
+    type Output = Select<A, B>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let (a, b) = this
+            .receivers
+            .as_mut()
+            .expect("select polled after completion");
+
+        let a_result = a.poll_recv(cx);
+        if let Poll::Ready(Ok(message)) = a_result {
+            let (_, b) = this.receivers.take().unwrap();
+            return Poll::Ready(Select::Left(message, reclaimed(b)));
+        }
+        match b.poll_recv(cx) {
+            Poll::Ready(Ok(message)) => {
+                let (a, _) = this.receivers.take().unwrap();
+                Poll::Ready(Select::Right(message, reclaimed(a)))
+            }
+            Poll::Ready(Err(_)) if a_result.is_ready() => {
+                this.receivers = None;
+                Poll::Ready(Select::Disconnected)
+            }
+            _ => Poll::Pending,
+        }
+    }
+}
+
+// The receivers are moved out, never pinned.
+#[cfg(feature = "async")]
+impl<A, B> Unpin for SelectTwo<A, B> {}
@@ -0,0 +1,383 @@
+use super::{StaticSendError, TryRecvError};
+use core::cell::UnsafeCell;
+use core::fmt;
+use core::mem::MaybeUninit;
+use core::sync::atomic::{AtomicBool, AtomicU8, Ordering::*};
+
+#[cfg(any(feature = "std", feature = "async"))]
+use super::{ReceiverWaker, RecvError};
+#[cfg(any(feature = "std", feature = "async"))]
+use core::hint;
+#[cfg(feature = "async")]
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{self, Poll},
+};
+
+/// No message has been sent, and both endpoints are alive or not yet created.
+const EMPTY: u8 = 0;
+/// The receiver has stored a waker and waits for the sender to wake it.
+#[cfg(any(feature = "std", feature = "async"))]
+const RECEIVING: u8 = 1;
+/// The sender has taken the waker and is about to store MESSAGE or SENDER_DROPPED.
+#[cfg(any(feature = "std", feature = "async"))]
+const UNPARKING: u8 = 2;
+/// A message is waiting in the channel.
+const MESSAGE: u8 = 3;
+/// The sender was dropped without sending anything.
+const SENDER_DROPPED: u8 = 4;
+/// The receiver was dropped. The sender can no longer send.
+const RECEIVER_DROPPED: u8 = 5;
+/// The message has been taken out by the receiver.
+const RECEIVED: u8 = 6;
+
+/// A oneshot channel that lives in place instead of on the heap, for example in a `static`.
+///
+/// [`StaticChannel::new`] is a `const fn`, so the channel can initialize a global singleton. The
+/// endpoints, [`StaticSender`] and [`StaticReceiver`], borrow the channel and are created once with
+/// [`StaticChannel::split`]. The message is stored inline, so no allocation happens.
+///
+/// A channel can only be used once. After both endpoints are gone the channel stays closed.
+pub struct StaticChannel<T> {
+    split: AtomicBool,
+    state: AtomicU8,
+    message: UnsafeCell<MaybeUninit<T>>,
+    #[cfg(any(feature = "std", feature = "async"))]
+    waker: UnsafeCell<MaybeUninit<ReceiverWaker>>,
+}
+
+// SAFETY: the channel is only accessed through the endpoints, one of each, which hand the message
+// from one to the other, same as `Sender` and `Receiver` do.
+unsafe impl<T: Send> Sync for StaticChannel<T> {}
+
+impl<T> StaticChannel<T> {
+    /// Creates a new empty channel.
+    pub const fn new() -> Self {
+        Self {
+            split: AtomicBool::new(false),
+            state: AtomicU8::new(EMPTY),
+            message: UnsafeCell::new(MaybeUninit::uninit()),
+            #[cfg(any(feature = "std", feature = "async"))]
+            waker: UnsafeCell::new(MaybeUninit::uninit()),
+        }
+    }
+
+    /// Returns the two endpoints of the channel.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the channel has been split before. Each channel only has one sender and one
+    /// receiver.
+    pub fn split(&self) -> (StaticSender<'_, T>, StaticReceiver<'_, T>) {
+        // ORDERING: the flag guards no other memory
+        if self.split.swap(true, Relaxed) {
+            panic!("StaticChannel::split called more than once");
+        }
+        (
+            StaticSender { channel: self },
+            StaticReceiver { channel: self },
+        )
+    }
+
+    /// # Safety
+    ///
+    /// Must only be called by the receiver, after observing MESSAGE with acquire ordering.
+    unsafe fn take_message(&self) -> T {
+        // ORDERING: the receiver owns the message from now on, nobody reads this store but us
+        self.state.store(RECEIVED, Relaxed);
+        (*self.message.get()).assume_init_read()
+    }
+
+    /// # Safety
+    ///
+    /// Must only be called by the receiver, and only before publishing RECEIVING.
+    #[cfg(any(feature = "std", feature = "async"))]
+    unsafe fn write_waker(&self, waker: ReceiverWaker) {
+        (*self.waker.get()).write(waker);
+    }
+
+    /// # Safety
+    ///
+    /// The waker must have been written, and the caller must own it. That is the sender after
+    /// switching from RECEIVING to UNPARKING, or the receiver after switching away from RECEIVING
+    /// or failing to publish it.
+    #[cfg(any(feature = "std", feature = "async"))]
+    unsafe fn take_waker(&self) -> ReceiverWaker {
+        (*self.waker.get()).assume_init_read()
+    }
+
+    /// Switches from RECEIVING to `to` and drops the waker the receiver stored. Waits for the
+    /// sender if it is taking the waker. Returns the state it found, if not RECEIVING.
+    ///
+    /// # Safety
+    ///
+    /// Must only be called by the receiver.
+    #[cfg(any(feature = "std", feature = "async"))]
+    unsafe fn reclaim_waker(&self, to: u8) -> u8 {
+        loop {
+            // ORDERING: acquire for the message in case the sender is already done
+            match self.state.compare_exchange(RECEIVING, to, Acquire, Acquire) {
+                Ok(_) => {
+                    // SAFETY: the sender does not access the waker outside of RECEIVING
+                    drop(self.take_waker());
+                    return to;
+                }
+                // The sender is taking the waker, it's done very soon.
+                Err(UNPARKING) => hint::spin_loop(),
+                Err(state) => return state,
+            }
+        }
+    }
+
+    /// Stores `waker` and publishes RECEIVING. Returns false, and drops the waker, if the sender
+    /// finished in the meantime.
+    ///
+    /// # Safety
+    ///
+    /// Must only be called by the receiver, in the EMPTY state.
+    #[cfg(any(feature = "std", feature = "async"))]
+    unsafe fn register(&self, waker: ReceiverWaker) -> bool {
+        self.write_waker(waker);
+        // ORDERING: release for the waker written above. Acquire on failure for the message.
+        match self
+            .state
+            .compare_exchange(EMPTY, RECEIVING, Release, Acquire)
+        {
+            Ok(_) => true,
+            Err(_) => {
+                // SAFETY: the waker was never published
+                drop(self.take_waker());
+                false
+            }
+        }
+    }
+
+    /// Moves from the sender's current state to `to`, waking the receiver if it waits.
+    /// Returns false without changing anything if the receiver is gone.
+    fn finish_sending(&self, to: u8) -> bool {
+        // ORDERING: the exchanges below do the synchronization
+        let mut state = self.state.load(Relaxed);
+        loop {
+            match state {
+                // ORDERING: release for the message written by `send`
+                EMPTY => match self.state.compare_exchange(EMPTY, to, Release, Relaxed) {
+                    Ok(_) => return true,
+                    Err(actual) => state = actual,
+                },
+                #[cfg(any(feature = "std", feature = "async"))]
+                RECEIVING => {
+                    // ORDERING: acquire for the waker written by the receiver
+                    match self
+                        .state
+                        .compare_exchange(RECEIVING, UNPARKING, Acquire, Relaxed)
+                    {
+                        Ok(_) => {
+                            // SAFETY: in UNPARKING the waker is ours, the receiver waits for us
+                            let waker = unsafe { self.take_waker() };
+                            // ORDERING: release for the message written by `send`
+                            self.state.store(to, Release);
+                            waker.unpark();
+                            return true;
+                        }
+                        Err(actual) => state = actual,
+                    }
+                }
+                RECEIVER_DROPPED => return false,
+                _ => unreachable!(),
+            }
+        }
+    }
+}
+
+impl<T> Drop for StaticChannel<T> {
+    fn drop(&mut self) {
+        if *self.state.get_mut() == MESSAGE {
+            // SAFETY: the message was sent and never received
+            unsafe { self.message.get_mut().assume_init_drop() };
+        }
+    }
+}
+
+impl<T> Default for StaticChannel<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> fmt::Debug for StaticChannel<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StaticChannel").finish_non_exhaustive()
+    }
+}
+
+/// Sending end of a [`StaticChannel`].
+pub struct StaticSender<'a, T> {
+    channel: &'a StaticChannel<T>,
+}
+
+/// Receiving end of a [`StaticChannel`].
+///
+/// Implements `Future` when the `async` feature is enabled.
+pub struct StaticReceiver<'a, T> {
+    channel: &'a StaticChannel<T>,
+}
+
+impl<T> StaticSender<'_, T> {
+    /// Sends `message` to the receiver and wakes it up if it waits.
+    ///
+    /// Returns the message in the error if the [`StaticReceiver`] has already been dropped.
+    pub fn send(self, message: T) -> Result<(), StaticSendError<T>> {
+        let channel = self.channel;
+        core::mem::forget(self);
+
+        // SAFETY: the receiver only reads the message in the MESSAGE state, which we set below
+        unsafe { (*channel.message.get()).write(message) };
+        if channel.finish_sending(MESSAGE) {
+            Ok(())
+        } else {
+            // SAFETY: the receiver is gone, so the message we just wrote is still ours
+            Err(StaticSendError::new(unsafe {
+                (*channel.message.get()).assume_init_read()
+            }))
+        }
+    }
+
+    /// Returns true if the [`StaticReceiver`] has been dropped. Then sending is guaranteed to
+    /// fail.
+    pub fn is_closed(&self) -> bool {
+        // ORDERING: the state guards no memory the sender reads here
+        self.channel.state.load(Relaxed) == RECEIVER_DROPPED
+    }
+}
+
+impl<T> Drop for StaticSender<'_, T> {
+    fn drop(&mut self) {
+        self.channel.finish_sending(SENDER_DROPPED);
+    }
+}
+
+impl<T> StaticReceiver<'_, T> {
+    /// Checks if there is a message in the channel, without waiting. Returns:
+    ///  * `Ok(message)` if there was a message in the channel.
+    ///  * `Err(Empty)` if the [`StaticSender`] is alive, but has not yet sent a message.
+    ///  * `Err(Disconnected)` if the [`StaticSender`] was dropped before sending anything or if
+    ///    the message has already been received.
+    pub fn try_recv(&self) -> Result<T, TryRecvError> {
+        // ORDERING: acquire for the message
+        match self.channel.state.load(Acquire) {
+            // SAFETY: we are the receiver and observed MESSAGE
+            MESSAGE => Ok(unsafe { self.channel.take_message() }),
+            SENDER_DROPPED | RECEIVED => Err(TryRecvError::Disconnected),
+            _ => Err(TryRecvError::Empty),
+        }
+    }
+
+    /// Blocks the current thread until a message arrives or the [`StaticSender`] is dropped.
+    ///
+    /// If this receiver has been polled before, the waker of that poll is replaced by one for
+    /// the current thread.
+    #[cfg(feature = "std")]
+    pub fn recv(self) -> Result<T, RecvError> {
+        let channel = self.channel;
+        // SAFETY: we are the receiver
+        let mut state = unsafe { channel.reclaim_waker(EMPTY) };
+        // SAFETY: we are the receiver and the state is EMPTY
+        if state == EMPTY && unsafe { channel.register(ReceiverWaker::current_thread()) } {
+            loop {
+                std::thread::park();
+                // ORDERING: acquire for the message
+                state = channel.state.load(Acquire);
+                if !matches!(state, RECEIVING | UNPARKING) {
+                    break;
+                }
+            }
+        } else {
+            // ORDERING: acquire for the message
+            state = channel.state.load(Acquire);
+        }
+        match state {
+            // SAFETY: we are the receiver and observed MESSAGE
+            MESSAGE => Ok(unsafe { channel.take_message() }),
+            SENDER_DROPPED => Err(RecvError::Disconnected),
+            RECEIVED => Err(RecvError::AlreadyReceived),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Returns true if a message is waiting in the channel.
+    pub fn has_message(&self) -> bool {
+        // ORDERING: the message is not accessed here
+        self.channel.state.load(Relaxed) == MESSAGE
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T> Future for StaticReceiver<'_, T> {
+    type Output = Result<T, RecvError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Self::Output> {
+        let channel = self.channel;
+        loop {
+            // ORDERING: acquire for the message
+            match channel.state.load(Acquire) {
+                // SAFETY: we are the receiver and observed MESSAGE
+                MESSAGE => return Poll::Ready(Ok(unsafe { channel.take_message() })),
+                SENDER_DROPPED => return Poll::Ready(Err(RecvError::Disconnected)),
+                RECEIVED => return Poll::Ready(Err(RecvError::AlreadyReceived)),
+                EMPTY => {
+                    // SAFETY: we are the receiver and the state is EMPTY
+                    if unsafe { channel.register(ReceiverWaker::task_waker(cx)) } {
+                        return Poll::Pending;
+                    }
+                }
+                RECEIVING => {
+                    // SAFETY: we are the receiver and wrote the waker when polled last time
+                    if unsafe { (*channel.waker.get()).assume_init_ref() }.will_wake(cx) {
+                        return Poll::Pending;
+                    }
+                    // SAFETY: we are the receiver
+                    unsafe { channel.reclaim_waker(EMPTY) };
+                }
+                // The sender is about to wake the old waker. Wait for it to finish instead.
+                UNPARKING => hint::spin_loop(),
+                _ => unreachable!(),
+            }
+        }
+    }
+}
+
+impl<T> Drop for StaticReceiver<'_, T> {
+    fn drop(&mut self) {
+        let channel = self.channel;
+
+        // If we have been polled, take the waker back so the sender never wakes it.
+        // SAFETY: we are the receiver
+        #[cfg(any(feature = "std", feature = "async"))]
+        unsafe {
+            channel.reclaim_waker(RECEIVER_DROPPED);
+        }
+
+        // ORDERING: acquire for the message, in case we have to drop it
+        if let Err(MESSAGE) =
+            channel
+                .state
+                .compare_exchange(EMPTY, RECEIVER_DROPPED, Acquire, Acquire)
+        {
+            // SAFETY: we are the receiver and observed MESSAGE
+            drop(unsafe { channel.take_message() });
+        }
+    }
+}
+
+impl<T> fmt::Debug for StaticSender<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StaticSender").finish_non_exhaustive()
+    }
+}
+
+impl<T> fmt::Debug for StaticReceiver<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StaticReceiver").finish_non_exhaustive()
+    }
+}
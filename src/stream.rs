@@ -0,0 +1,90 @@
+use super::{Receiver, RecvError};
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{self, Poll};
+use futures_core::{FusedStream, Stream};
+
+/// A [`Stream`] yielding the message of a [`Receiver`], or the error if the sender was dropped,
+/// and then ending. Created by [`Receiver::into_stream`].
+#[derive(Debug)]
+pub struct ReceiverStream<T> {
+    receiver: Option<Receiver<T>>,
+}
+
+impl<T> ReceiverStream<T> {
+    pub(crate) fn new(receiver: Receiver<T>) -> Self {
+        Self {
+            receiver: Some(receiver),
+        }
+    }
+}
+
+impl<T> Stream for ReceiverStream<T> {
+    type Item = Result<T, RecvError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Option<Self::Item>> {
+        let receiver = match self.receiver.as_mut() {
+            Some(receiver) => receiver,
+            None => return Poll::Ready(None),
+        };
+        match Pin::new(receiver).poll(cx) {
+            Poll::Ready(result) => {
+                // The item has been yielded, dropping the receiver frees the channel right away.
+                self.receiver = None;
+                Poll::Ready(Some(result))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.receiver {
+            Some(_) => (1, Some(1)),
+            None => (0, Some(0)),
+        }
+    }
+}
+
+impl<T> FusedStream for ReceiverStream<T> {
+    fn is_terminated(&self) -> bool {
+        self.receiver.is_none()
+    }
+}
+
+/// A [`Stream`] yielding the message of a [`Receiver`] and then ending. Ends without yielding
+/// anything if the sender is dropped without sending. Created by
+/// [`Receiver::into_message_stream`].
+#[derive(Debug)]
+pub struct MessageStream<T> {
+    inner: ReceiverStream<T>,
+}
+
+impl<T> MessageStream<T> {
+    pub(crate) fn new(receiver: Receiver<T>) -> Self {
+        Self {
+            inner: ReceiverStream::new(receiver),
+        }
+    }
+}
+
+impl<T> Stream for MessageStream<T> {
+    type Item = T;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.inner)
+            .poll_next(cx)
+            .map(|item| item.and_then(Result::ok))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // Whether the message comes is not known until the receiver resolves.
+        let (_, upper) = self.inner.size_hint();
+        (0, upper)
+    }
+}
+
+impl<T> FusedStream for MessageStream<T> {
+    fn is_terminated(&self) -> bool {
+        self.inner.is_terminated()
+    }
+}
@@ -0,0 +1,71 @@
+//! Hooks letting the tests run code at the points of the blocking receive methods where races
+//! with the sender are the most interesting. They only exist when debug assertions are enabled,
+//! and are no-ops otherwise. Not part of the public API.
+//!
+//! Under loom there is instead a virtual clock for the deadline of `Receiver::recv_deadline`,
+//! since loom does not control time.
+
+#[cfg(any(loom, debug_assertions))]
+use core::cell::Cell;
+#[cfg(loom)]
+use std::time::Duration;
+use std::time::Instant;
+
+#[cfg(all(debug_assertions, not(loom)))]
+std::thread_local! {
+    static PRE_PARK_HOOK: Cell<Option<fn()>> = const { Cell::new(None) };
+}
+
+/// Sets the hook that blocking receives on the current thread call when they found the channel
+/// empty, right before they store their waker and park. Returns the previous hook.
+///
+/// The sender can act freely while the hook runs, so a hook that sleeps, or that sends or drops
+/// the sender itself, lets the tests hit the edge cases where the state changes between the two.
+#[cfg(all(debug_assertions, not(loom)))]
+pub fn set_pre_park_hook(hook: Option<fn()>) -> Option<fn()> {
+    PRE_PARK_HOOK.with(|slot| slot.replace(hook))
+}
+
+#[inline]
+pub(crate) fn pre_park_hook() {
+    #[cfg(all(debug_assertions, not(loom)))]
+    if let Some(hook) = PRE_PARK_HOOK.with(Cell::get) {
+        hook();
+    }
+}
+
+#[cfg(loom)]
+loom::thread_local! {
+    // How far the virtual clock of the current thread is ahead of the real one. `None` while it
+    // is disabled.
+    static CLOCK_OFFSET: Cell<Option<Duration>> = Cell::new(None);
+}
+
+/// Enables the virtual clock on the current loom thread. From then on parking with a timeout
+/// advances the clock by the full timeout, so a deadline receive on this thread times out right
+/// after its first park. This makes the receive reach its timeout handling in every execution,
+/// while the sender can still act at any point of it.
+#[cfg(loom)]
+pub fn enable_virtual_clock() {
+    CLOCK_OFFSET.with(|offset| offset.set(Some(offset.get().unwrap_or_default())));
+}
+
+/// Returns the current time, as seen by `Receiver::recv_deadline`.
+#[inline]
+pub(crate) fn now() -> Instant {
+    #[cfg(loom)]
+    if let Some(offset) = CLOCK_OFFSET.with(Cell::get) {
+        return Instant::now() + offset;
+    }
+    Instant::now()
+}
+
+/// Called by the loom `park_timeout` after the thread is resumed.
+#[cfg(loom)]
+pub(crate) fn parked_for(timeout: Duration) {
+    CLOCK_OFFSET.with(|offset| {
+        if let Some(current) = offset.get() {
+            offset.set(Some(current + timeout));
+        }
+    });
+}
@@ -0,0 +1,235 @@
+//! Receiving with a timeout measured by the timer of an async runtime.
+//!
+//! [`Receiver::recv_timeout_with`] races the receiver against a sleep created by an
+//! [`AsyncTimer`], and maps the outcome to one [`RecvTimeoutError`]. Adapters for the timers of
+//! tokio and async-std are included behind the `tokio` and `async-std` features. Any other
+//! runtime can be plugged in by implementing [`AsyncTimer`].
+
+use super::{Receiver, RecvTimeoutError};
+use alloc::boxed::Box;
+use core::{
+    fmt,
+    future::Future,
+    pin::Pin,
+    task::{self, Poll},
+    time::Duration,
+};
+
+/// A timer of an async runtime, creating futures that complete after a duration.
+pub trait AsyncTimer {
+    /// The future returned from [`AsyncTimer::sleep`].
+    type Sleep: Future<Output = ()>;
+
+    /// Returns a future completing once `duration` has passed.
+    fn sleep(duration: Duration) -> Self::Sleep;
+}
+
+/// The timer of the tokio runtime. `tokio::time::sleep` must be called from within a tokio
+/// runtime with the time driver enabled, so the future has to be polled there.
+#[cfg(feature = "tokio")]
+#[derive(Debug)]
+pub enum Tokio {}
+
+#[cfg(feature = "tokio")]
+impl AsyncTimer for Tokio {
+    type Sleep = tokio::time::Sleep;
+
+    fn sleep(duration: Duration) -> Self::Sleep {
+        tokio::time::sleep(duration)
+    }
+}
+
+/// The timer of the async-std runtime.
+#[cfg(feature = "async-std")]
+#[derive(Debug)]
+pub enum AsyncStd {}
+
+#[cfg(feature = "async-std")]
+impl AsyncTimer for AsyncStd {
+    type Sleep = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+    fn sleep(duration: Duration) -> Self::Sleep {
+        Box::pin(async_std::task::sleep(duration))
+    }
+}
+
+/// A future receiving the message of a [`Receiver`], or giving up once a timeout has passed.
+/// Created by [`Receiver::recv_timeout_with`].
+#[cfg_attr(
+    feature = "std",
+    doc = "Also created by [`DeadlineReceiver::recv_with`](crate::DeadlineReceiver::recv_with), \
+           giving up at a deadline."
+)]
+///
+/// The timeout starts when the future is first polled, since creating the sleep can require
+/// being within the runtime. If the message arrives in the same poll as the timeout fires, the
+/// message is returned.
+pub struct RecvTimeout<T, S: AsyncTimer> {
+    receiver: Receiver<T>,
+    limit: Limit,
+    sleep: Option<Pin<Box<S::Sleep>>>,
+}
+
+/// How long the sleep of a [`RecvTimeout`] lasts.
+enum Limit {
+    Timeout(Duration),
+    /// Sleeps for the time left until the deadline when the sleep is created.
+    #[cfg(feature = "std")]
+    Deadline(std::time::Instant),
+}
+
+impl Limit {
+    fn duration(&self) -> Duration {
+        match *self {
+            Limit::Timeout(timeout) => timeout,
+            #[cfg(feature = "std")]
+            Limit::Deadline(deadline) => {
+                deadline.saturating_duration_since(std::time::Instant::now())
+            }
+        }
+    }
+}
+
+impl<T, S: AsyncTimer> RecvTimeout<T, S> {
+    pub(crate) fn new(receiver: Receiver<T>, timeout: Duration) -> Self {
+        Self {
+            receiver,
+            limit: Limit::Timeout(timeout),
+            sleep: None,
+        }
+    }
+
+    #[cfg(feature = "std")]
+    pub(crate) fn until(receiver: Receiver<T>, deadline: std::time::Instant) -> Self {
+        Self {
+            receiver,
+            limit: Limit::Deadline(deadline),
+            sleep: None,
+        }
+    }
+
+    /// Removes the timeout and returns the inner receiver.
+    pub fn into_inner(self) -> Receiver<T> {
+        self.receiver
+    }
+}
+
+impl<T, S: AsyncTimer> Future for RecvTimeout<T, S> {
+    type Output = Result<T, RecvTimeoutError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        // The receiver is polled first, so a message wins over a timeout firing at the same time.
+        match this.receiver.poll_recv(cx) {
+            Poll::Ready(Ok(message)) => return Poll::Ready(Ok(message)),
+            Poll::Ready(Err(_)) => return Poll::Ready(Err(RecvTimeoutError::Disconnected)),
+            Poll::Pending => (),
+        }
+
+        let sleep = this
+            .sleep
+            .get_or_insert_with(|| Box::pin(S::sleep(this.limit.duration())));
+        match sleep.as_mut().poll(cx) {
+            Poll::Ready(()) => Poll::Ready(Err(RecvTimeoutError::Timeout)),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl<T, S: AsyncTimer> fmt::Debug for RecvTimeout<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut f = f.debug_struct("RecvTimeout");
+        f.field("receiver", &self.receiver);
+        match &self.limit {
+            Limit::Timeout(timeout) => f.field("timeout", timeout),
+            #[cfg(feature = "std")]
+            Limit::Deadline(deadline) => f.field("deadline", deadline),
+        };
+        f.finish_non_exhaustive()
+    }
+}
+
+/// A future receiving the message of a [`Receiver`], or giving up at a deadline of the tokio
+/// clock. Created by [`Receiver::recv_with_deadline`] and [`Receiver::recv_with_timeout`].
+///
+/// The `tokio::time::Sleep` is stored inline instead of boxed, so this future is not `Unpin` and
+/// has to be pinned before it is polled, for example with `tokio::pin!`. Like with
+/// [`RecvTimeout`], the sleep is created on the first poll, and a message wins over the deadline
+/// passing in the same poll. Since the deadline is measured with the tokio clock, it follows
+/// `tokio::time::pause` and `tokio::time::advance` in tests.
+///
+/// Dropping the future before it completes drops the receiver, like dropping the receiver
+/// itself. Polling it again after a `Pending` never loses the message.
+///
+/// [`Receiver::recv_with_deadline`]: crate::Receiver::recv_with_deadline
+/// [`Receiver::recv_with_timeout`]: crate::Receiver::recv_with_timeout
+#[cfg(feature = "tokio")]
+pub struct TokioRecvTimeout<T> {
+    receiver: Receiver<T>,
+    deadline: tokio::time::Instant,
+    // Structurally pinned. Never moved out of the pinned future.
+    sleep: Option<tokio::time::Sleep>,
+}
+
+#[cfg(feature = "tokio")]
+impl<T> TokioRecvTimeout<T> {
+    pub(crate) fn new(receiver: Receiver<T>, deadline: tokio::time::Instant) -> Self {
+        Self {
+            receiver,
+            deadline,
+            sleep: None,
+        }
+    }
+
+    /// Returns the deadline, on the tokio clock.
+    pub fn deadline(&self) -> tokio::time::Instant {
+        self.deadline
+    }
+
+    /// Removes the deadline and returns the inner receiver.
+    pub fn into_inner(self) -> Receiver<T> {
+        self.receiver
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<T> Future for TokioRecvTimeout<T> {
+    type Output = Result<T, RecvTimeoutError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Self::Output> {
+        // SAFETY: only `sleep` is pinned, and it is never moved out of the future. The receiver
+        // is `Unpin`, so it's fine to hand out a plain mutable reference to it.
+        let this = unsafe { self.get_unchecked_mut() };
+        // The receiver is polled first, so a message wins over a deadline passing at the same
+        // time.
+        match this.receiver.poll_recv(cx) {
+            Poll::Ready(Ok(message)) => return Poll::Ready(Ok(message)),
+            Poll::Ready(Err(_)) => return Poll::Ready(Err(RecvTimeoutError::Disconnected)),
+            Poll::Pending => (),
+        }
+
+        // SAFETY: `this` comes from a pinned reference, and `sleep` is structurally pinned.
+        let mut sleep = unsafe { Pin::new_unchecked(&mut this.sleep) };
+        if sleep.is_none() {
+            // Nothing was pinned in the empty slot, so it's fine to overwrite it.
+            sleep.set(Some(tokio::time::sleep_until(this.deadline)));
+        }
+        match sleep.as_pin_mut() {
+            Some(sleep) => match sleep.poll(cx) {
+                Poll::Ready(()) => Poll::Ready(Err(RecvTimeoutError::Timeout)),
+                Poll::Pending => Poll::Pending,
+            },
+            None => unreachable!(),
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<T> fmt::Debug for TokioRecvTimeout<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TokioRecvTimeout")
+            .field("receiver", &self.receiver)
+            .field("deadline", &self.deadline)
+            .finish_non_exhaustive()
+    }
+}
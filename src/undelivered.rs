@@ -0,0 +1,90 @@
+use super::{Receiver, TryRecvError};
+use core::fmt;
+
+#[cfg(any(feature = "std", feature = "async"))]
+use super::RecvError;
+#[cfg(feature = "async")]
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{self, Poll},
+};
+
+/// A receiver that hands a sent but never received message to a hook when it is dropped.
+/// Created by [`Receiver::on_undelivered`].
+///
+/// A plain receiver drops such a message silently. This one closes the channel first, so no
+/// send can slip in between, then calls the hook with the message if there is one. The hook is
+/// not called if the message was received or never sent. Implements `Future` like the receiver
+/// itself when the `async` feature is enabled.
+pub struct OnUndelivered<T, F: FnOnce(T)> {
+    // Only `None` once consumed by `recv` or `into_inner`, or while being dropped.
+    receiver: Option<Receiver<T>>,
+    hook: Option<F>,
+}
+
+impl<T, F: FnOnce(T)> OnUndelivered<T, F> {
+    pub(crate) fn new(receiver: Receiver<T>, hook: F) -> Self {
+        Self {
+            receiver: Some(receiver),
+            hook: Some(hook),
+        }
+    }
+
+    /// Like [`Receiver::try_recv`]. A received message is not passed to the hook.
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        self.receiver_mut().try_recv_mut()
+    }
+
+    /// Like [`Receiver::recv`]. A received message is not passed to the hook. Also works after
+    /// this receiver has been polled asynchronously, like [`Receiver::recv_mut`].
+    #[cfg(feature = "std")]
+    pub fn recv(mut self) -> Result<T, RecvError> {
+        // The receiver is only taken once the message is ours, the destructor handles the rest.
+        self.receiver_mut().recv_mut()
+    }
+
+    /// Removes the hook and returns the inner receiver. An unreceived message is dropped
+    /// silently again from then on.
+    pub fn into_inner(mut self) -> Receiver<T> {
+        self.hook = None;
+        self.receiver.take().expect("receiver already taken")
+    }
+
+    fn receiver_mut(&mut self) -> &mut Receiver<T> {
+        self.receiver.as_mut().expect("receiver already taken")
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T, F: FnOnce(T)> Future for OnUndelivered<T, F> {
+    type Output = Result<T, RecvError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Self::Output> {
+        self.get_mut().receiver_mut().poll_recv(cx)
+    }
+}
+
+// Neither the receiver nor the hook is ever pinned.
+impl<T, F: FnOnce(T)> Unpin for OnUndelivered<T, F> {}
+
+impl<T, F: FnOnce(T)> Drop for OnUndelivered<T, F> {
+    fn drop(&mut self) {
+        if let (Some(mut receiver), Some(hook)) = (self.receiver.take(), self.hook.take()) {
+            // Closing first means a message can't arrive after the check below and be dropped
+            // along with the receiver.
+            receiver.close();
+            if let Ok(message) = receiver.try_recv_mut() {
+                hook(message);
+            }
+        }
+    }
+}
+
+impl<T, F: FnOnce(T)> fmt::Debug for OnUndelivered<T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OnUndelivered")
+            .field("receiver", &self.receiver)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,263 @@
+//! Checks that the channel allocation is freed at the expected time, by counting the live
+//! allocations made by the current thread.
+
+#![cfg(all(feature = "std", not(loom)))]
+
+use oneshot::IntoRecvTimeoutError;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::panic::{self, AssertUnwindSafe};
+use std::time::{Duration, Instant};
+
+struct CountingAllocator;
+
+thread_local! {
+    static LIVE_ALLOCATIONS: Cell<isize> = const { Cell::new(0) };
+    static TOTAL_ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = LIVE_ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        let _ = TOTAL_ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        let _ = LIVE_ALLOCATIONS.try_with(|count| count.set(count.get() - 1));
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn live_allocations() -> isize {
+    LIVE_ALLOCATIONS.with(|count| count.get())
+}
+
+fn total_allocations() -> usize {
+    TOTAL_ALLOCATIONS.with(|count| count.get())
+}
+
+#[test]
+fn recv_timeout_owned_frees_channel() {
+    // Message
+    let before = live_allocations();
+    let (sender, receiver) = oneshot::channel::<u128>();
+    sender.send(19).unwrap();
+    assert_eq!(
+        receiver
+            .recv_timeout_owned(Duration::from_millis(1))
+            .unwrap(),
+        19
+    );
+    assert_eq!(live_allocations(), before);
+
+    // Disconnected
+    let (sender, receiver) = oneshot::channel::<u128>();
+    drop(sender);
+    assert!(matches!(
+        receiver.recv_timeout_owned(Duration::from_millis(1)),
+        Err(IntoRecvTimeoutError::Disconnected)
+    ));
+    assert_eq!(live_allocations(), before);
+
+    // Timeout, then retry with the returned receiver
+    let (sender, receiver) = oneshot::channel::<u128>();
+    let receiver = match receiver.recv_timeout_owned(Duration::from_millis(1)) {
+        Err(IntoRecvTimeoutError::Timeout(receiver)) => receiver,
+        _ => panic!("Receive should time out"),
+    };
+    assert_eq!(live_allocations(), before + 1);
+    sender.send(19).unwrap();
+    assert_eq!(
+        receiver
+            .recv_timeout_owned(Duration::from_millis(1))
+            .unwrap(),
+        19
+    );
+    assert_eq!(live_allocations(), before);
+}
+
+#[test]
+fn recv_mut_frees_channel_once() {
+    // Message, then drop
+    let before = live_allocations();
+    let (sender, mut receiver) = oneshot::channel::<u128>();
+    sender.send(19).unwrap();
+    assert_eq!(receiver.recv_mut(), Ok(19));
+    assert_eq!(live_allocations(), before + 1);
+    drop(receiver);
+    assert_eq!(live_allocations(), before);
+
+    // Disconnected, then drop
+    let (sender, mut receiver) = oneshot::channel::<u128>();
+    drop(sender);
+    assert!(receiver.recv_mut().is_err());
+    assert!(receiver.recv_mut().is_err());
+    drop(receiver);
+    assert_eq!(live_allocations(), before);
+
+    // Timeout, then a message
+    let (sender, mut receiver) = oneshot::channel::<u128>();
+    assert!(receiver.recv_timeout(Duration::from_millis(1)).is_err());
+    sender.send(19).unwrap();
+    assert_eq!(receiver.recv_mut(), Ok(19));
+    drop(receiver);
+    assert_eq!(live_allocations(), before);
+
+    // Closed, then drop
+    let (sender, mut receiver) = oneshot::channel::<u128>();
+    receiver.close();
+    assert!(receiver.recv_mut().is_err());
+    drop(receiver);
+    assert!(sender.send(19).is_err());
+    assert_eq!(live_allocations(), before);
+}
+
+#[test]
+fn on_receive_frees_channel_and_callback() {
+    let before = live_allocations();
+    let (sender, receiver) = oneshot::channel::<u128>();
+    receiver.on_receive(|result| assert_eq!(result, Ok(19)));
+    assert!(live_allocations() > before + 1);
+    sender.send(19).unwrap();
+    assert_eq!(live_allocations(), before);
+
+    let (sender, receiver) = oneshot::channel::<u128>();
+    receiver.on_receive(|result| assert!(result.is_err()));
+    drop(sender);
+    assert_eq!(live_allocations(), before);
+
+    let (sender, receiver) = oneshot::channel::<u128>();
+    sender.send(19).unwrap();
+    receiver.on_receive(|result| assert_eq!(result, Ok(19)));
+    assert_eq!(live_allocations(), before);
+}
+
+#[test]
+fn recv_deadline_in_the_past_does_not_allocate() {
+    let (sender, receiver) = oneshot::channel::<u128>();
+    let past = Instant::now();
+    let before = total_allocations();
+    assert_eq!(
+        receiver.recv_deadline(past),
+        Err(oneshot::RecvTimeoutError::Timeout)
+    );
+    assert_eq!(
+        receiver.recv_timeout(Duration::ZERO),
+        Err(oneshot::RecvTimeoutError::Timeout)
+    );
+    assert_eq!(total_allocations(), before);
+
+    // A message that is already there is still received
+    sender.send(19).unwrap();
+    assert_eq!(receiver.recv_deadline(past), Ok(19));
+}
+
+#[test]
+fn into_try_recv_frees_channel() {
+    let before = live_allocations();
+    let (sender, receiver) = oneshot::channel::<u128>();
+    let receiver = match receiver.into_try_recv() {
+        Err(oneshot::IntoTryRecvError::Empty(receiver)) => receiver,
+        _ => panic!("Channel should be empty"),
+    };
+    sender.send(19).unwrap();
+    assert_eq!(receiver.into_try_recv().unwrap(), 19);
+    assert_eq!(live_allocations(), before);
+}
+
+struct PanicOnDrop;
+
+impl Drop for PanicOnDrop {
+    fn drop(&mut self) {
+        panic!("PanicOnDrop dropped");
+    }
+}
+
+/// Runs `f`, which must panic, and returns the live allocations before and after it ran.
+fn allocations_around_panic(f: impl FnOnce()) -> (isize, isize) {
+    // The default hook prints the panic to the captured test output, and the allocations made for
+    // that live on.
+    panic::set_hook(Box::new(|_| ()));
+    let before = live_allocations();
+    assert!(panic::catch_unwind(AssertUnwindSafe(f)).is_err());
+    let after = live_allocations();
+    // Restores the default hook
+    drop(panic::take_hook());
+    (before, after)
+}
+
+#[test]
+fn panicking_message_drop_frees_channel() {
+    // Dropping the receiver with an unreceived message
+    let (before, after) = allocations_around_panic(|| {
+        let (sender, receiver) = oneshot::channel();
+        sender.send(PanicOnDrop).unwrap();
+        drop(receiver);
+    });
+    assert_eq!(after, before);
+
+    // Dropping the error of a send to a dropped receiver
+    let (before, after) = allocations_around_panic(|| {
+        let (sender, receiver) = oneshot::channel();
+        drop(receiver);
+        drop(sender.send(PanicOnDrop).unwrap_err());
+    });
+    assert_eq!(after, before);
+
+    // Dropping the error of a send to a closed receiver. The receiver is dropped while unwinding.
+    let (before, after) = allocations_around_panic(|| {
+        let (sender, receiver) = oneshot::channel();
+        receiver.close();
+        drop(sender.send(PanicOnDrop).unwrap_err());
+    });
+    assert_eq!(after, before);
+}
+
+#[test]
+fn channel_pair_frees_both_channels() {
+    let before = live_allocations();
+    let (sender, receiver1, receiver2) = oneshot::channel_pair::<Vec<u8>>();
+    sender.send(vec![1, 2, 3]).unwrap();
+    assert_eq!(receiver1.recv().unwrap(), vec![1, 2, 3]);
+    // The second copy is dropped together with its channel.
+    drop(receiver2);
+    assert_eq!(live_allocations(), before);
+
+    // Both receivers gone, the message is handed back and freed with the error.
+    let (sender, receiver1, receiver2) = oneshot::channel_pair::<Vec<u8>>();
+    drop(receiver1);
+    drop(receiver2);
+    assert!(sender.send(vec![1, 2, 3]).is_err());
+    assert_eq!(live_allocations(), before);
+}
+
+#[test]
+fn panicking_map_closure_frees_channel() {
+    let (before, after) = allocations_around_panic(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        let receiver = receiver.map(|_| -> u128 { panic!("mapping failed") });
+        sender.send(19).unwrap();
+        let _ = receiver.recv();
+    });
+    assert_eq!(after, before);
+
+    let (before, after) = allocations_around_panic(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        let mut receiver = receiver.map(|_| -> u128 { panic!("mapping failed") });
+        sender.send(19).unwrap();
+        let _ = receiver.try_recv();
+    });
+    assert_eq!(after, before);
+
+    let (before, after) = allocations_around_panic(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        let mut receiver = receiver.map(|_| -> u128 { panic!("mapping failed") });
+        sender.send(19).unwrap();
+        let _ = receiver.recv_timeout(Duration::from_millis(1));
+    });
+    assert_eq!(after, before);
+}
@@ -116,6 +116,17 @@ async fn poll_future_and_then_try_recv() {
     t.await.unwrap();
 }
 
+#[tokio::test]
+async fn sender_is_receiver_waiting() {
+    let (sender, receiver) = oneshot::channel::<u128>();
+    let t = tokio::spawn(receiver);
+    while !sender.is_receiver_waiting() {
+        tokio::time::sleep(Duration::from_millis(1)).await;
+    }
+    sender.send(19).unwrap();
+    assert_eq!(t.await.unwrap(), Ok(19));
+}
+
 #[tokio::test]
 async fn poll_receiver_then_drop_it() {
     let (sender, receiver) = oneshot::channel::<()>();
@@ -126,3 +137,982 @@ async fn poll_receiver_then_drop_it() {
     // Make sure the receiver has been dropped by the runtime.
     assert!(sender.send(()).is_err());
 }
+
+#[tokio::test]
+async fn receiver_state_after_await() {
+    let (sender, mut receiver) = oneshot::channel::<u128>();
+    let t = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        sender.send(19).unwrap();
+    });
+    assert_eq!(receiver.state(), oneshot::ChannelState::Empty);
+    assert_eq!((&mut receiver).await, Ok(19));
+    assert_eq!(receiver.state(), oneshot::ChannelState::Consumed);
+    t.await.unwrap();
+}
+
+#[tokio::test]
+async fn await_after_receiving() {
+    let (sender, mut receiver) = oneshot::channel::<u128>();
+    sender.send(19).unwrap();
+    assert_eq!((&mut receiver).await, Ok(19));
+    assert_eq!(
+        (&mut receiver).await,
+        Err(oneshot::RecvError::AlreadyReceived)
+    );
+    assert_eq!(
+        receiver.ready().await,
+        Err(oneshot::RecvError::AlreadyReceived)
+    );
+
+    let (sender, receiver) = oneshot::channel::<u128>();
+    mem::drop(sender);
+    assert_eq!(receiver.await, Err(oneshot::RecvError::Disconnected));
+}
+
+#[tokio::test]
+async fn sender_closed_tokio() {
+    let (mut sender, receiver) = oneshot::channel::<u128>();
+    let t = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        mem::drop(receiver);
+    });
+    sender.closed().await;
+    assert!(sender.is_closed());
+    assert!(sender.send(19).is_err());
+    t.await.unwrap();
+}
+
+#[async_std::test]
+async fn sender_closed_async_std() {
+    let (mut sender, receiver) = oneshot::channel::<u128>();
+    let t = async_std::task::spawn(async move {
+        async_std::task::sleep(Duration::from_millis(10)).await;
+        mem::drop(receiver);
+    });
+    sender.closed().await;
+    assert!(sender.send(19).is_err());
+    t.await;
+}
+
+#[tokio::test]
+async fn sender_closed_in_select() {
+    let (mut sender, receiver) = oneshot::channel::<u128>();
+    let t = tokio::spawn(async move {
+        tokio::select! {
+            _ = sender.closed() => panic!("Receiver not dropped"),
+            _ = tokio::time::sleep(Duration::from_millis(10)) => (),
+        }
+        sender.send(19).unwrap();
+    });
+    assert_eq!(receiver.await, Ok(19));
+    t.await.unwrap();
+}
+
+#[tokio::test]
+async fn ready_before_send_tokio() {
+    let (sender, mut receiver) = oneshot::channel();
+    let t = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        sender.send(19u128)
+    });
+    assert_eq!(receiver.ready().await, Ok(()));
+    assert_eq!(receiver.ready().await, Ok(()));
+    assert_eq!(receiver.try_recv(), Ok(19));
+    assert!(receiver.ready().await.is_err());
+    t.await.unwrap().unwrap();
+}
+
+#[async_std::test]
+async fn ready_before_send_then_drop_sender_async_std() {
+    let (sender, mut receiver) = oneshot::channel::<u128>();
+    let t = async_std::task::spawn(async move {
+        async_std::task::sleep(Duration::from_millis(10)).await;
+        mem::drop(sender);
+    });
+    assert!(receiver.ready().await.is_err());
+    assert!(receiver.await.is_err());
+    t.await;
+}
+
+#[tokio::test]
+async fn ready_then_await() {
+    let (sender, mut receiver) = oneshot::channel();
+    let (message, counter) = DropCounter::new(79u128);
+    sender.send(message).unwrap();
+    assert_eq!(receiver.ready().await, Ok(()));
+    assert_eq!(counter.count(), 0);
+    assert_eq!(*receiver.await.unwrap().value(), 79u128);
+    assert_eq!(counter.count(), 1);
+}
+
+#[tokio::test]
+async fn map_await() {
+    let (sender, receiver) = oneshot::channel::<u128>();
+    let t = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        sender.send(19)
+    });
+    assert_eq!(
+        receiver.map(|n| n.to_string()).await,
+        Ok(String::from("19"))
+    );
+    t.await.unwrap().unwrap();
+
+    let (sender, receiver) = oneshot::channel::<u128>();
+    mem::drop(sender);
+    assert!(receiver.map(|n| n + 1).await.is_err());
+}
+
+#[tokio::test]
+async fn map_drops_closure_on_disconnect() {
+    let (sender, receiver) = oneshot::channel::<u128>();
+    let (captured, counter) = DropCounter::new(());
+    let mut receiver = receiver.map(move |n| {
+        let _captured = &captured;
+        n
+    });
+    mem::drop(sender);
+    assert!((&mut receiver).await.is_err());
+    assert_eq!(counter.count(), 1);
+}
+
+#[tokio::test]
+async fn select_any_async() {
+    let (_sender1, receiver1) = oneshot::channel::<u128>();
+    let (sender2, receiver2) = oneshot::channel::<u128>();
+    let t = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        sender2.send(19)
+    });
+    let mut receivers = [receiver1, receiver2];
+    assert_eq!(oneshot::select_any_async(&mut receivers).await, (1, Ok(19)));
+    t.await.unwrap().unwrap();
+    assert_eq!(receivers[0].try_recv(), Err(oneshot::TryRecvError::Empty));
+}
+
+#[tokio::test]
+async fn reset_after_poll_closed() {
+    let (mut sender, mut receiver) = oneshot::channel::<u128>();
+    // Leaves a waker registered in the channel
+    tokio::select! {
+        biased;
+        _ = sender.closed() => unreachable!(),
+        _ = std::future::ready(()) => (),
+    }
+    sender.send(19).unwrap();
+    assert_eq!(receiver.try_recv(), Ok(19));
+
+    let mut sender = receiver.reset().unwrap();
+    let closed = tokio::spawn(async move {
+        sender.closed().await;
+        sender
+    });
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    receiver.close();
+    let sender = closed.await.unwrap();
+    assert!(sender.send(20).is_err());
+}
+
+// Awaiting the receiver directly only notices the deadline when polled again
+#[cfg(feature = "std")]
+#[test]
+fn deadline_receiver_times_out_when_polled_after_deadline() {
+    use core::future::Future;
+    use core::pin::Pin;
+    use core::task::{self, Poll};
+
+    let (waker, handle) = helpers::waker::waker();
+    let mut context = task::Context::from_waker(&waker);
+    let (_sender, receiver) = oneshot::channel::<u128>();
+    let mut receiver =
+        receiver.with_deadline(std::time::Instant::now() + Duration::from_millis(10));
+    assert!(Pin::new(&mut receiver).poll(&mut context).is_pending());
+    // Only the channel holds on to the waker, no timer is started
+    assert_eq!(handle.clone_count() - handle.drop_count(), 1);
+    std::thread::sleep(Duration::from_millis(20));
+    assert_eq!(handle.wake_count(), 0);
+    assert_eq!(
+        Pin::new(&mut receiver).poll(&mut context),
+        Poll::Ready(Err(oneshot::RecvTimeoutError::Timeout))
+    );
+}
+
+#[cfg(feature = "std")]
+#[tokio::test]
+async fn deadline_receiver_before_deadline_tokio() {
+    let (sender, receiver) = oneshot::channel::<u128>();
+    let receiver = receiver.with_deadline(std::time::Instant::now() + Duration::from_secs(10));
+    let t = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        sender.send(19).unwrap();
+    });
+    assert_eq!(receiver.await, Ok(19));
+    t.await.unwrap();
+
+    let (sender, receiver) = oneshot::channel::<u128>();
+    let receiver = receiver.with_deadline(std::time::Instant::now() + Duration::from_secs(10));
+    let t = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        mem::drop(sender);
+    });
+    assert_eq!(receiver.await, Err(oneshot::RecvTimeoutError::Disconnected));
+    t.await.unwrap();
+}
+
+#[tokio::test]
+async fn flatten_tokio() {
+    let (sender, receiver) = oneshot::channel::<Result<u128, &str>>();
+    let t = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        sender.send(Ok(19)).unwrap();
+    });
+    assert_eq!(receiver.flatten().await, Ok(19));
+    t.await.unwrap();
+
+    let (sender, receiver) = oneshot::channel::<Result<u128, &str>>();
+    let t = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        sender.send(Err("failed")).unwrap();
+    });
+    assert_eq!(
+        receiver.flatten().await,
+        Err(oneshot::RecvOrError::App("failed"))
+    );
+    t.await.unwrap();
+
+    let (sender, receiver) = oneshot::channel::<Result<u128, &str>>();
+    let t = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        mem::drop(sender);
+    });
+    assert_eq!(
+        receiver.flatten().await,
+        Err(oneshot::RecvOrError::Disconnected)
+    );
+    t.await.unwrap();
+}
+
+#[async_std::test]
+async fn flatten_async_std() {
+    let (sender, receiver) = oneshot::channel::<Result<u128, &str>>();
+    sender.send(Err("failed")).unwrap();
+    assert_eq!(
+        receiver.flatten().await,
+        Err(oneshot::RecvOrError::App("failed"))
+    );
+
+    let (sender, receiver) = oneshot::channel::<Result<u128, &str>>();
+    mem::drop(sender);
+    assert_eq!(
+        receiver.flatten().await,
+        Err(oneshot::RecvOrError::Disconnected)
+    );
+}
+
+#[tokio::test]
+async fn forward_through_three_channels_tokio() {
+    let (sender, receiver1) = oneshot::channel::<u128>();
+    let (sender2, receiver2) = oneshot::channel();
+    let (sender3, receiver3) = oneshot::channel();
+    let t1 = tokio::spawn(receiver1.forward(sender2));
+    let t2 = tokio::spawn(receiver2.forward(sender3));
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    sender.send(19).unwrap();
+    assert_eq!(receiver3.await, Ok(19));
+    assert!(t1.await.unwrap().is_ok());
+    assert!(t2.await.unwrap().is_ok());
+
+    let (sender, receiver1) = oneshot::channel::<u128>();
+    let (sender2, receiver2) = oneshot::channel();
+    let (sender3, receiver3) = oneshot::channel();
+    let t1 = tokio::spawn(receiver1.forward(sender2));
+    let t2 = tokio::spawn(receiver2.forward(sender3));
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    mem::drop(sender);
+    assert_eq!(receiver3.await, Err(oneshot::RecvError::Disconnected));
+    assert!(t1.await.unwrap().is_ok());
+    assert!(t2.await.unwrap().is_ok());
+}
+
+#[async_std::test]
+async fn forward_to_dropped_receiver_async_std() {
+    let (sender, receiver) = oneshot::channel::<u128>();
+    let (dest, dest_receiver) = oneshot::channel();
+    mem::drop(dest_receiver);
+    sender.send(19).unwrap();
+    let error = receiver.forward(dest).await.unwrap_err();
+    assert_eq!(error.into_inner(), 19);
+}
+
+#[tokio::test]
+async fn send_status_woke_tokio() {
+    let (sender, receiver) = oneshot::channel::<u128>();
+    let t = tokio::spawn(async move {
+        while !sender.is_receiver_waiting() {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+        sender.send_status(19).unwrap()
+    });
+    assert_eq!(receiver.await, Ok(19));
+    assert_eq!(t.await.unwrap(), oneshot::SendStatus::Woke);
+}
+
+#[tokio::test]
+async fn send_async_tokio() {
+    let (sender, receiver) = oneshot::channel::<u128>();
+    let t = tokio::spawn(async move { sender.send_async(19).await });
+    assert_eq!(receiver.await, Ok(19));
+    t.await.unwrap().unwrap();
+
+    let (sender, receiver) = oneshot::channel::<u128>();
+    mem::drop(receiver);
+    let error = sender.send_async(19).await.unwrap_err();
+    assert_eq!(error.into_inner(), 19);
+}
+
+#[tokio::test]
+async fn send_async_dropped_before_poll() {
+    let (sender, receiver) = oneshot::channel();
+    let (message, counter) = DropCounter::new(19u128);
+    let future = sender.send_async(message);
+    assert_eq!(counter.count(), 0);
+    mem::drop(future);
+    assert_eq!(counter.count(), 1);
+    assert!(matches!(
+        receiver.await,
+        Err(oneshot::RecvError::Disconnected)
+    ));
+}
+
+#[cfg(feature = "std")]
+#[tokio::test]
+async fn await_times_out_then_blocking_recv_timeout_on_another_thread() {
+    let (sender, mut receiver) = oneshot::channel::<u128>();
+    // Leaves the waker of this task in the channel
+    assert!(
+        tokio::time::timeout(Duration::from_millis(10), &mut receiver)
+            .await
+            .is_err()
+    );
+    let t = std::thread::spawn(move || receiver.recv_timeout(Duration::from_secs(10)));
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    sender.send(19).unwrap();
+    assert_eq!(t.join().unwrap(), Ok(19));
+
+    let (sender, mut receiver) = oneshot::channel::<u128>();
+    assert!(
+        tokio::time::timeout(Duration::from_millis(10), &mut receiver)
+            .await
+            .is_err()
+    );
+    let t = std::thread::spawn(move || receiver.recv_timeout(Duration::from_secs(10)));
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    mem::drop(sender);
+    assert_eq!(
+        t.join().unwrap(),
+        Err(oneshot::RecvTimeoutError::Disconnected)
+    );
+}
+
+#[cfg(feature = "std")]
+#[tokio::test]
+async fn blocking_recv_timeout_between_awaits() {
+    let (sender, mut receiver) = oneshot::channel::<u128>();
+    assert!(
+        tokio::time::timeout(Duration::from_millis(10), &mut receiver)
+            .await
+            .is_err()
+    );
+    assert_eq!(
+        receiver.recv_timeout(Duration::from_millis(10)),
+        Err(oneshot::RecvTimeoutError::Timeout)
+    );
+    // The blocking receive gave up and left no waker behind, so awaiting works again
+    let t = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        sender.send(19).unwrap();
+    });
+    assert_eq!(receiver.await, Ok(19));
+    t.await.unwrap();
+}
+
+// A timeout too large for a deadline blocks without one, and still takes over a polled receiver
+#[cfg(feature = "std")]
+#[test]
+fn poll_then_recv_timeout_owned_without_deadline() {
+    use core::task;
+
+    let waker = helpers::waker::noop_waker();
+    let (sender, mut receiver) = oneshot::channel::<u128>();
+    assert!(receiver
+        .poll_recv(&mut task::Context::from_waker(&waker))
+        .is_pending());
+    let t = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(10));
+        sender.send(19).unwrap();
+    });
+    assert!(matches!(receiver.recv_timeout_owned(Duration::MAX), Ok(19)));
+    t.join().unwrap();
+
+    let (sender, mut receiver) = oneshot::channel::<u128>();
+    assert!(receiver
+        .poll_recv(&mut task::Context::from_waker(&waker))
+        .is_pending());
+    drop(sender);
+    assert!(matches!(
+        receiver.recv_timeout_owned(Duration::MAX),
+        Err(oneshot::IntoRecvTimeoutError::Disconnected)
+    ));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn poll_then_recv_on_undelivered() {
+    use core::future::Future;
+    use core::pin::Pin;
+    use core::task;
+
+    let waker = helpers::waker::noop_waker();
+    let (sender, receiver) = oneshot::channel::<u128>();
+    let mut receiver = receiver.on_undelivered(|_| panic!("Should not be called"));
+    assert!(Pin::new(&mut receiver)
+        .poll(&mut task::Context::from_waker(&waker))
+        .is_pending());
+    let t = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(10));
+        sender.send(19).unwrap();
+    });
+    assert_eq!(receiver.recv(), Ok(19));
+    t.join().unwrap();
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn poll_then_select_any() {
+    use core::task;
+
+    let waker = helpers::waker::noop_waker();
+    let (sender1, mut receiver1) = oneshot::channel::<u128>();
+    let (sender2, mut receiver2) = oneshot::channel::<u128>();
+    for receiver in [&mut receiver1, &mut receiver2] {
+        assert!(receiver
+            .poll_recv(&mut task::Context::from_waker(&waker))
+            .is_pending());
+    }
+    let t = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(10));
+        sender2.send(19).unwrap();
+    });
+    let receivers = [receiver1, receiver2];
+    assert_eq!(oneshot::select_any(&receivers), (1, Ok(19)));
+    t.join().unwrap();
+
+    // The other receiver is left ready for the next receive.
+    sender1.send(20).unwrap();
+    assert_eq!(receivers[0].try_recv(), Ok(20));
+}
+
+// A polled receiver late in the list must not lose the messages taken before it
+#[cfg(feature = "std")]
+#[test]
+fn poll_then_wait_all() {
+    use core::task;
+
+    let waker = helpers::waker::noop_waker();
+    let (senders, mut receivers): (Vec<_>, Vec<_>) =
+        (0..3).map(|_| oneshot::channel::<u128>()).unzip();
+    assert!(receivers[2]
+        .poll_recv(&mut task::Context::from_waker(&waker))
+        .is_pending());
+    let t = std::thread::spawn(move || {
+        for (i, sender) in senders.into_iter().enumerate() {
+            std::thread::sleep(Duration::from_millis(5));
+            sender.send(i as u128).unwrap();
+        }
+    });
+    assert_eq!(oneshot::wait_all(receivers), [Ok(0), Ok(1), Ok(2)]);
+    t.join().unwrap();
+}
+
+#[test]
+fn poll_recv_repeatedly() {
+    use core::task::{self, Poll};
+
+    let waker = helpers::waker::noop_waker();
+    let mut context = task::Context::from_waker(&waker);
+
+    let (sender, mut receiver) = oneshot::channel::<u128>();
+    for _ in 0..3 {
+        assert_eq!(receiver.poll_recv(&mut context), Poll::Pending);
+    }
+    sender.send(19).unwrap();
+    assert_eq!(receiver.poll_recv(&mut context), Poll::Ready(Ok(19)));
+    for _ in 0..3 {
+        assert_eq!(
+            receiver.poll_recv(&mut context),
+            Poll::Ready(Err(oneshot::RecvError::AlreadyReceived))
+        );
+    }
+
+    let (sender, mut receiver) = oneshot::channel::<u128>();
+    assert_eq!(receiver.poll_recv(&mut context), Poll::Pending);
+    mem::drop(sender);
+    for _ in 0..3 {
+        assert_eq!(
+            receiver.poll_recv(&mut context),
+            Poll::Ready(Err(oneshot::RecvError::Disconnected))
+        );
+    }
+}
+
+// Polling again with another waker replaces the stored one, and only the latest is woken
+#[test]
+fn poll_recv_replaces_waker() {
+    use core::task::{self, Poll};
+
+    let (waker1, counter1) = helpers::waker::waker();
+    let (waker2, counter2) = helpers::waker::waker();
+
+    let (sender, mut receiver) = oneshot::channel::<u128>();
+    let mut context = task::Context::from_waker(&waker1);
+    assert_eq!(receiver.poll_recv(&mut context), Poll::Pending);
+    // Polling again with the same waker keeps the one already stored
+    assert_eq!(receiver.poll_recv(&mut context), Poll::Pending);
+    assert_eq!(counter1.clone_count() - counter1.drop_count(), 1);
+
+    let mut context = task::Context::from_waker(&waker2);
+    assert_eq!(receiver.poll_recv(&mut context), Poll::Pending);
+    assert_eq!(counter1.clone_count() - counter1.drop_count(), 0);
+    assert_eq!(counter2.clone_count() - counter2.drop_count(), 1);
+
+    sender.send(19).unwrap();
+    assert_eq!(counter1.wake_count(), 0);
+    assert_eq!(counter2.wake_count(), 1);
+    assert_eq!(counter2.clone_count() - counter2.drop_count(), 0);
+    assert_eq!(receiver.poll_recv(&mut context), Poll::Ready(Ok(19)));
+}
+
+// A hand written future polling a receiver it holds, without pinning it
+#[tokio::test]
+async fn poll_recv_from_manual_future() {
+    use core::future::Future;
+    use core::pin::Pin;
+    use core::task::{self, Poll};
+
+    struct Driver {
+        receiver: oneshot::Receiver<u128>,
+        polls: usize,
+    }
+
+    impl Future for Driver {
+        type Output = (Result<u128, oneshot::RecvError>, usize);
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Self::Output> {
+            self.polls += 1;
+            let polls = self.polls;
+            self.receiver.poll_recv(cx).map(|result| (result, polls))
+        }
+    }
+
+    let (sender, receiver) = oneshot::channel();
+    let t = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        sender.send(19).unwrap();
+    });
+    let (result, polls) = Driver { receiver, polls: 0 }.await;
+    assert_eq!(result, Ok(19));
+    assert!(polls >= 2);
+    t.await.unwrap();
+}
+
+// Dropping a pending `RecvFuture` leaves no waker behind, and a new one receives the message
+#[tokio::test]
+async fn recv_future_is_cancellation_safe() {
+    use core::future::Future;
+    use core::pin::Pin;
+    use core::task::{self, Poll};
+
+    let waker = helpers::waker::noop_waker();
+    let mut context = task::Context::from_waker(&waker);
+
+    let (sender, mut receiver) = oneshot::channel::<u128>();
+    let mut future = receiver.recv_async();
+    assert_eq!(Pin::new(&mut future).poll(&mut context), Poll::Pending);
+    assert!(sender.is_receiver_waiting());
+    drop(future);
+    assert!(!sender.is_receiver_waiting());
+    assert!(matches!(
+        sender.send_status(19),
+        Ok(oneshot::SendStatus::Buffered)
+    ));
+    assert_eq!(receiver.recv_async().await, Ok(19));
+    assert_eq!(
+        receiver.recv_async().await,
+        Err(oneshot::RecvError::AlreadyReceived)
+    );
+}
+
+#[tokio::test]
+async fn recv_future_in_select_loop() {
+    let (sender, mut receiver) = oneshot::channel::<u128>();
+    let t = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        sender.send(19).unwrap();
+    });
+    let mut timeouts = 0;
+    let message = loop {
+        tokio::select! {
+            message = receiver.recv_async() => break message,
+            _ = tokio::time::sleep(Duration::from_millis(5)) => timeouts += 1,
+        }
+    };
+    assert_eq!(message, Ok(19));
+    assert!(timeouts > 0);
+    t.await.unwrap();
+}
+
+#[tokio::test]
+async fn join_first_arrives_first() {
+    let (sender1, receiver1) = oneshot::channel::<u128>();
+    let (sender2, receiver2) = oneshot::channel::<&str>();
+    let t = tokio::spawn(async move {
+        sender1.send(19).unwrap();
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        sender2.send("hello").unwrap();
+    });
+    assert_eq!(oneshot::join(receiver1, receiver2).await, Ok((19, "hello")));
+    t.await.unwrap();
+}
+
+#[tokio::test]
+async fn join_second_arrives_first() {
+    let (sender1, receiver1) = oneshot::channel::<u128>();
+    let (sender2, receiver2) = oneshot::channel::<&str>();
+    let t = tokio::spawn(async move {
+        sender2.send("hello").unwrap();
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        sender1.send(19).unwrap();
+    });
+    assert_eq!(oneshot::join(receiver1, receiver2).await, Ok((19, "hello")));
+    t.await.unwrap();
+}
+
+#[tokio::test]
+async fn join_first_disconnects_early() {
+    let (sender1, receiver1) = oneshot::channel::<u128>();
+    let (sender2, receiver2) = oneshot::channel::<u128>();
+    let t = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        drop(sender1);
+    });
+    // Resolves without the second sender ever sending
+    assert_eq!(
+        oneshot::join(receiver1, receiver2).await,
+        Err(oneshot::RecvError::Disconnected)
+    );
+    assert!(sender2.is_closed());
+    t.await.unwrap();
+}
+
+#[tokio::test]
+async fn join_second_disconnects_early() {
+    let (sender1, receiver1) = oneshot::channel::<DropCounter<()>>();
+    let (sender2, receiver2) = oneshot::channel::<u128>();
+    let (message, counter) = DropCounter::new(());
+    sender1.send(message).unwrap();
+    let t = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        drop(sender2);
+    });
+    assert!(matches!(
+        oneshot::join(receiver1, receiver2).await,
+        Err(oneshot::RecvError::Disconnected)
+    ));
+    // The message already received from the first channel is dropped along with the future
+    assert_eq!(counter.count(), 1);
+    t.await.unwrap();
+}
+
+#[tokio::test]
+async fn select_left_wins() {
+    let (sender1, receiver1) = oneshot::channel::<u128>();
+    let (sender2, receiver2) = oneshot::channel::<&str>();
+    let t = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        sender1.send(19).unwrap();
+    });
+    let receiver2 = match oneshot::select(receiver1, receiver2).await {
+        oneshot::Select::Left(19, receiver2) => receiver2,
+        _ => panic!("expected the left receiver to win"),
+    };
+    t.await.unwrap();
+    // The loser is still usable, and gets a message sent after the select completed
+    sender2.send("hello").unwrap();
+    assert_eq!(receiver2.await, Ok("hello"));
+}
+
+#[tokio::test]
+async fn select_right_wins() {
+    let (sender1, receiver1) = oneshot::channel::<u128>();
+    let (sender2, receiver2) = oneshot::channel::<&str>();
+    let t = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        sender2.send("hello").unwrap();
+    });
+    let receiver1 = match oneshot::select(receiver1, receiver2).await {
+        oneshot::Select::Right("hello", receiver1) => receiver1,
+        _ => panic!("expected the right receiver to win"),
+    };
+    t.await.unwrap();
+    assert!(!sender1.is_closed());
+    drop(sender1);
+    assert_eq!(receiver1.await, Err(oneshot::RecvError::Disconnected));
+}
+
+#[tokio::test]
+async fn select_both_ready_keeps_loser_message() {
+    let (sender1, receiver1) = oneshot::channel::<u128>();
+    let (sender2, receiver2) = oneshot::channel::<u128>();
+    sender1.send(1).unwrap();
+    sender2.send(2).unwrap();
+    match oneshot::select(receiver1, receiver2).await {
+        oneshot::Select::Left(1, receiver2) => assert_eq!(receiver2.try_recv(), Ok(2)),
+        _ => panic!("expected the left receiver to win"),
+    }
+}
+
+#[tokio::test]
+async fn select_one_disconnected_waits_for_other() {
+    let (sender1, receiver1) = oneshot::channel::<u128>();
+    let (sender2, receiver2) = oneshot::channel::<u128>();
+    drop(sender1);
+    let t = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        sender2.send(19).unwrap();
+    });
+    match oneshot::select(receiver1, receiver2).await {
+        oneshot::Select::Right(19, receiver1) => {
+            assert_eq!(receiver1.await, Err(oneshot::RecvError::Disconnected))
+        }
+        _ => panic!("expected the right receiver to win"),
+    }
+    t.await.unwrap();
+}
+
+#[tokio::test]
+async fn select_both_disconnected() {
+    let (sender1, receiver1) = oneshot::channel::<u128>();
+    let (sender2, receiver2) = oneshot::channel::<u128>();
+    let t = tokio::spawn(async move {
+        drop(sender2);
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        drop(sender1);
+    });
+    assert!(matches!(
+        oneshot::select(receiver1, receiver2).await,
+        oneshot::Select::Disconnected
+    ));
+    t.await.unwrap();
+}
+
+// The loser does not keep the waker of the selecting task around
+#[test]
+fn select_drops_loser_waker() {
+    use core::future::Future;
+    use core::task::{self, Poll};
+
+    let (waker, handle) = helpers::waker::waker();
+    let mut context = task::Context::from_waker(&waker);
+
+    let (sender1, receiver1) = oneshot::channel::<u128>();
+    let (_sender2, receiver2) = oneshot::channel::<u128>();
+    let future = oneshot::select(receiver1, receiver2);
+    futures_util::pin_mut!(future);
+    assert!(future.as_mut().poll(&mut context).is_pending());
+    // One clone in each channel
+    assert_eq!(handle.clone_count() - handle.drop_count(), 2);
+    sender1.send(19).unwrap();
+    match future.poll(&mut context) {
+        Poll::Ready(oneshot::Select::Left(19, receiver2)) => {
+            assert_eq!(handle.clone_count(), handle.drop_count());
+            drop(receiver2);
+        }
+        _ => panic!("expected the left receiver to win"),
+    }
+}
+
+// Like a future migrating between executor threads, the receiver is polled on another thread with
+// another waker, and only that waker is woken
+#[test]
+fn poll_with_new_waker_on_other_thread() {
+    use core::future::Future;
+    use core::pin::Pin;
+    use core::task::{self, Poll};
+
+    let (waker1, counter1) = helpers::waker::waker();
+    let (sender, mut receiver) = oneshot::channel::<u128>();
+    assert_eq!(
+        Pin::new(&mut receiver).poll(&mut task::Context::from_waker(&waker1)),
+        Poll::Pending
+    );
+
+    let (waker2, counter2) = helpers::waker::waker();
+    let mut receiver = std::thread::spawn(move || {
+        let mut context = task::Context::from_waker(&waker2);
+        assert_eq!(Pin::new(&mut receiver).poll(&mut context), Poll::Pending);
+        receiver
+    })
+    .join()
+    .unwrap();
+    // The first waker was dropped when it got replaced
+    assert_eq!(counter1.clone_count() - counter1.drop_count(), 0);
+
+    std::thread::spawn(move || sender.send(19).unwrap())
+        .join()
+        .unwrap();
+    assert_eq!(counter1.wake_count(), 0);
+    assert_eq!(counter2.wake_count(), 1);
+    assert_eq!(
+        Pin::new(&mut receiver).poll(&mut task::Context::from_waker(&waker1)),
+        Poll::Ready(Ok(19))
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn join_all_random_order() {
+    let (senders, receivers): (Vec<_>, Vec<_>) =
+        (0..1000).map(|_| oneshot::channel::<usize>()).unzip();
+    let mut senders: Vec<_> = senders.into_iter().enumerate().collect();
+    // Fisher-Yates shuffle with a xorshift generator
+    let mut state = 0x2545_f491_4f6c_dd1du64;
+    for i in (1..senders.len()).rev() {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        senders.swap(i, (state % (i as u64 + 1)) as usize);
+    }
+    let t = std::thread::spawn(move || {
+        for (n, (index, sender)) in senders.into_iter().enumerate() {
+            sender.send(index).unwrap();
+            if n % 100 == 0 {
+                std::thread::sleep(Duration::from_millis(1));
+            }
+        }
+    });
+    let results = oneshot::join_all(receivers).await;
+    assert_eq!(results.len(), 1000);
+    for (index, result) in results.into_iter().enumerate() {
+        assert_eq!(result, Ok(index));
+    }
+    t.join().unwrap();
+}
+
+#[tokio::test]
+async fn join_all_disconnected_and_empty() {
+    assert_eq!(
+        oneshot::join_all(Vec::<oneshot::Receiver<u128>>::new()).await,
+        []
+    );
+
+    let (sender1, receiver1) = oneshot::channel::<u128>();
+    let (sender2, receiver2) = oneshot::channel::<u128>();
+    drop(sender1);
+    sender2.send(19).unwrap();
+    assert_eq!(
+        oneshot::join_all(vec![receiver1, receiver2]).await,
+        [Err(oneshot::RecvError::Disconnected), Ok(19)]
+    );
+}
+
+// The task is woken once per completed receiver, not on every send in the channels
+#[test]
+fn join_all_wakes_per_completed_receiver() {
+    use core::future::Future;
+    use core::task::{self, Poll};
+
+    let (waker, counter) = helpers::waker::waker();
+    let mut context = task::Context::from_waker(&waker);
+
+    let (senders, receivers): (Vec<_>, Vec<_>) =
+        (0..3).map(|_| oneshot::channel::<usize>()).unzip();
+    let future = oneshot::join_all(receivers);
+    futures_util::pin_mut!(future);
+    assert!(future.as_mut().poll(&mut context).is_pending());
+    assert_eq!(counter.wake_count(), 0);
+
+    let mut senders = senders.into_iter();
+    senders.next().unwrap().send(0).unwrap();
+    assert_eq!(counter.wake_count(), 1);
+    assert!(future.as_mut().poll(&mut context).is_pending());
+    drop(senders.next());
+    assert_eq!(counter.wake_count(), 2);
+    senders.next().unwrap().send(2).unwrap();
+    assert_eq!(counter.wake_count(), 3);
+    assert_eq!(
+        future.poll(&mut context),
+        Poll::Ready(vec![Ok(0), Err(oneshot::RecvError::Disconnected), Ok(2)])
+    );
+}
+
+#[tokio::test]
+async fn try_join_both_ok() {
+    let (sender1, receiver1) = oneshot::channel::<Result<u128, &str>>();
+    let (sender2, receiver2) = oneshot::channel::<Result<&str, &str>>();
+    let t = tokio::spawn(async move {
+        sender2.send(Ok("hello")).unwrap();
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        sender1.send(Ok(19)).unwrap();
+    });
+    assert_eq!(
+        oneshot::try_join(receiver1, receiver2).await,
+        Ok((19, "hello"))
+    );
+    t.await.unwrap();
+}
+
+#[tokio::test]
+async fn try_join_first_fails_early() {
+    let (sender1, receiver1) = oneshot::channel::<Result<u128, &str>>();
+    let (sender2, receiver2) = oneshot::channel::<Result<u128, &str>>();
+    let t = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        sender1.send(Err("failed")).unwrap();
+    });
+    assert_eq!(
+        oneshot::try_join(receiver1, receiver2).await,
+        Err(oneshot::RecvOrError::App("failed"))
+    );
+    // The other sender can't send anymore as soon as the join has failed
+    assert!(sender2.is_closed());
+    assert_eq!(sender2.send(Ok(19)).unwrap_err().into_inner(), Ok(19));
+    t.await.unwrap();
+}
+
+#[tokio::test]
+async fn try_join_second_fails_early() {
+    let (sender1, receiver1) = oneshot::channel::<Result<u128, &str>>();
+    let (sender2, receiver2) = oneshot::channel::<Result<u128, &str>>();
+    let t = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        sender2.send(Err("failed")).unwrap();
+    });
+    assert_eq!(
+        oneshot::try_join(receiver1, receiver2).await,
+        Err(oneshot::RecvOrError::App("failed"))
+    );
+    assert_eq!(sender1.send(Ok(19)).unwrap_err().into_inner(), Ok(19));
+    t.await.unwrap();
+}
+
+#[tokio::test]
+async fn try_join_disconnected() {
+    let (sender1, receiver1) = oneshot::channel::<Result<u128, &str>>();
+    let (sender2, receiver2) = oneshot::channel::<Result<u128, &str>>();
+    sender1.send(Ok(19)).unwrap();
+    drop(sender2);
+    assert_eq!(
+        oneshot::try_join(receiver1, receiver2).await,
+        Err(oneshot::RecvOrError::Disconnected)
+    );
+}
@@ -0,0 +1,140 @@
+#![cfg(all(feature = "tokio-util", feature = "async", not(loom)))]
+
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{self, Poll};
+use core::time::Duration;
+use oneshot::RecvOrCancelError;
+use tokio_util::sync::CancellationToken;
+
+mod helpers;
+use helpers::waker::WakerHandle;
+
+/// Polls `future` once with `waker`, so the test can count its clones.
+fn poll_with<F: Future>(future: Pin<&mut F>, waker: &task::Waker) -> Poll<F::Output> {
+    future.poll(&mut task::Context::from_waker(waker))
+}
+
+/// Returns true if no clone of the waker of `handle` is alive.
+fn no_clones_left(handle: &WakerHandle) -> bool {
+    handle.clone_count() == handle.drop_count()
+}
+
+#[tokio::test]
+async fn recv_cancelled_before_send() {
+    let token = CancellationToken::new();
+    let (sender, receiver) = oneshot::channel::<u128>();
+    token.cancel();
+    assert_eq!(
+        receiver.recv_cancellable(&token).await,
+        Err(RecvOrCancelError::Cancelled)
+    );
+    // The receiver is gone after cancellation
+    assert!(sender.is_closed());
+    assert_eq!(sender.send(19).unwrap_err().into_inner(), 19);
+}
+
+#[tokio::test]
+async fn recv_cancelled_while_waiting() {
+    let token = CancellationToken::new();
+    let (sender, receiver) = oneshot::channel::<u128>();
+    let canceller = token.clone();
+    let t = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        canceller.cancel();
+    });
+    assert_eq!(
+        receiver.recv_cancellable(&token).await,
+        Err(RecvOrCancelError::Cancelled)
+    );
+    assert!(sender.is_closed());
+    t.await.unwrap();
+}
+
+#[tokio::test]
+async fn recv_cancelled_after_send() {
+    let token = CancellationToken::new();
+    let (sender, receiver) = oneshot::channel::<u128>();
+    sender.send(19).unwrap();
+    token.cancel();
+    // A message that is already there wins over the cancellation
+    assert_eq!(receiver.recv_cancellable(&token).await, Ok(19));
+}
+
+#[tokio::test]
+async fn recv_cancellable_message_arrives() {
+    let token = CancellationToken::new();
+    let (sender, receiver) = oneshot::channel::<u128>();
+    let t = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        sender.send(19).unwrap();
+    });
+    assert_eq!(receiver.recv_cancellable(&token).await, Ok(19));
+    t.await.unwrap();
+
+    let (sender, receiver) = oneshot::channel::<u128>();
+    drop(sender);
+    assert_eq!(
+        receiver.recv_cancellable(&token).await,
+        Err(RecvOrCancelError::Disconnected)
+    );
+}
+
+#[test]
+fn recv_cancellable_drops_waker_on_cancel() {
+    let (waker, handle) = helpers::waker::waker();
+    let token = CancellationToken::new();
+    let (sender, receiver) = oneshot::channel::<u128>();
+    {
+        let future = receiver.recv_cancellable(&token);
+        futures_util::pin_mut!(future);
+        assert!(poll_with(future.as_mut(), &waker).is_pending());
+        assert!(!no_clones_left(&handle));
+        token.cancel();
+        assert_eq!(
+            poll_with(future.as_mut(), &waker),
+            Poll::Ready(Err(RecvOrCancelError::Cancelled))
+        );
+    }
+    assert!(no_clones_left(&handle));
+    assert!(sender.is_closed());
+}
+
+#[tokio::test]
+async fn closed_or_cancelled() {
+    let token = CancellationToken::new();
+    let (mut sender, receiver) = oneshot::channel::<u128>();
+    drop(receiver);
+    assert!(sender.closed_or_cancelled(&token).await);
+
+    let (mut sender, receiver) = oneshot::channel::<u128>();
+    let canceller = token.clone();
+    let t = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        canceller.cancel();
+    });
+    assert!(!sender.closed_or_cancelled(&token).await);
+    t.await.unwrap();
+    // The sender is still usable after cancellation
+    sender.send(19).unwrap();
+    assert_eq!(receiver.await, Ok(19));
+}
+
+#[test]
+fn closed_or_cancelled_drops_waker_on_cancel() {
+    let (waker, handle) = helpers::waker::waker();
+    let token = CancellationToken::new();
+    let (mut sender, receiver) = oneshot::channel::<u128>();
+    {
+        let future = sender.closed_or_cancelled(&token);
+        futures_util::pin_mut!(future);
+        assert!(poll_with(future.as_mut(), &waker).is_pending());
+        assert!(!no_clones_left(&handle));
+        token.cancel();
+        assert_eq!(poll_with(future.as_mut(), &waker), Poll::Ready(false));
+    }
+    // The waker is gone while the sender is still alive
+    assert!(no_clones_left(&handle));
+    drop(receiver);
+    assert!(sender.is_closed());
+}
@@ -1,5 +1,6 @@
 #![cfg(feature = "async")]
 
+use core::sync::atomic::{AtomicUsize, Ordering::SeqCst};
 use core::{future, mem, pin, task};
 
 #[cfg(loom)]
@@ -8,7 +9,7 @@ pub use loom::sync::{Arc, Mutex};
 pub use std::sync::{Arc, Mutex};
 
 mod helpers;
-use helpers::maybe_loom_model;
+use helpers::{maybe_loom_model, DropCounter};
 
 #[test]
 fn multiple_receiver_polls_keeps_only_latest_waker() {
@@ -63,3 +64,159 @@ fn multiple_receiver_polls_keeps_only_latest_waker() {
         assert_eq!(mock_waker1.lock().unwrap().dropped, 1);
     });
 }
+
+/// Polling again before the sender sends must keep the channel waiting for the message, not
+/// mistake the stored waker for one. Runs under Miri to catch reads of an invalid message.
+#[test]
+fn poll_twice_then_send_returns_message_once() {
+    const VTABLE: task::RawWakerVTable = task::RawWakerVTable::new(
+        |_| task::RawWaker::new(&(), &VTABLE),
+        |_| (),
+        |_| (),
+        |_| (),
+    );
+
+    maybe_loom_model(|| {
+        let waker = unsafe { task::Waker::from_raw(task::RawWaker::new(&(), &VTABLE)) };
+        let mut context = task::Context::from_waker(&waker);
+
+        let (sender, mut receiver) = oneshot::channel();
+        let (message, counter) = DropCounter::new(Box::new(19u128));
+        let mut poll = || future::Future::poll(pin::Pin::new(&mut receiver), &mut context);
+
+        assert!(poll().is_pending());
+        assert!(poll().is_pending());
+        sender.send(message).unwrap();
+        match poll() {
+            task::Poll::Ready(Ok(message)) => assert_eq!(**message.value(), 19),
+            _ => panic!("Expected the message"),
+        }
+        assert!(matches!(
+            poll(),
+            task::Poll::Ready(Err(oneshot::RecvError::AlreadyReceived))
+        ));
+        assert_eq!(counter.count(), 1);
+    });
+}
+
+/// Dropping a receiver that returned `Pending` must drop the stored waker as a waker, exactly
+/// once, and leave the channel for the sender to free. Runs under Miri.
+#[test]
+fn poll_then_drop_receiver_then_drop_sender() {
+    static DROPPED: AtomicUsize = AtomicUsize::new(0);
+    const VTABLE: task::RawWakerVTable = task::RawWakerVTable::new(
+        |_| task::RawWaker::new(&(), &VTABLE),
+        |_| (),
+        |_| (),
+        |_| {
+            DROPPED.fetch_add(1, SeqCst);
+        },
+    );
+
+    maybe_loom_model(|| {
+        DROPPED.store(0, SeqCst);
+        let waker = unsafe { task::Waker::from_raw(task::RawWaker::new(&(), &VTABLE)) };
+        let mut context = task::Context::from_waker(&waker);
+
+        let (sender, mut receiver) = oneshot::channel::<Box<u128>>();
+        let poll_result = future::Future::poll(pin::Pin::new(&mut receiver), &mut context);
+        assert!(poll_result.is_pending());
+        assert_eq!(DROPPED.load(SeqCst), 0);
+
+        drop(receiver);
+        assert_eq!(DROPPED.load(SeqCst), 1);
+        drop(sender);
+        assert_eq!(DROPPED.load(SeqCst), 1);
+    });
+}
+
+/// A task moved to another executor thread between polls must be woken through the waker of
+/// the last poll. Two hand-rolled single task executors stand in for a work stealing runtime.
+#[cfg(not(loom))]
+#[test]
+fn task_moved_between_executors_is_woken_by_latest_waker() {
+    use std::sync::mpsc;
+    use std::thread;
+
+    /// Wakes by sending the name of its executor.
+    struct ExecutorWaker {
+        name: &'static str,
+        woken: Mutex<mpsc::Sender<&'static str>>,
+    }
+
+    impl std::task::Wake for ExecutorWaker {
+        fn wake(self: Arc<Self>) {
+            self.woken.lock().unwrap().send(self.name).unwrap();
+        }
+    }
+
+    fn poll_on_executor(
+        name: &'static str,
+        woken: &mpsc::Sender<&'static str>,
+        mut receiver: oneshot::Receiver<u128>,
+    ) -> (
+        task::Poll<Result<u128, oneshot::RecvError>>,
+        oneshot::Receiver<u128>,
+    ) {
+        let woken = Mutex::new(woken.clone());
+        thread::spawn(move || {
+            let waker = task::Waker::from(Arc::new(ExecutorWaker { name, woken }));
+            let mut context = task::Context::from_waker(&waker);
+            let poll = future::Future::poll(pin::Pin::new(&mut receiver), &mut context);
+            (poll, receiver)
+        })
+        .join()
+        .unwrap()
+    }
+
+    let (woken_sender, woken) = mpsc::channel();
+    let (sender, receiver) = oneshot::channel::<u128>();
+
+    let (poll, receiver) = poll_on_executor("A", &woken_sender, receiver);
+    assert!(poll.is_pending());
+    let (poll, receiver) = poll_on_executor("B", &woken_sender, receiver);
+    assert!(poll.is_pending());
+
+    sender.send(19).unwrap();
+    assert_eq!(woken.try_recv(), Ok("B"));
+    assert!(woken.try_recv().is_err());
+
+    let (poll, _receiver) = poll_on_executor("B", &woken_sender, receiver);
+    assert_eq!(poll, task::Poll::Ready(Ok(19)));
+}
+
+/// A `try_recv` after a pending poll must see an empty channel, and leave the waker of the poll
+/// registered so the task is still woken by the send.
+#[test]
+fn try_recv_after_pending_poll_keeps_waker() {
+    static WOKEN: AtomicUsize = AtomicUsize::new(0);
+    const VTABLE: task::RawWakerVTable = task::RawWakerVTable::new(
+        |_| task::RawWaker::new(&(), &VTABLE),
+        |_| {
+            WOKEN.fetch_add(1, SeqCst);
+        },
+        |_| {
+            WOKEN.fetch_add(1, SeqCst);
+        },
+        |_| (),
+    );
+
+    maybe_loom_model(|| {
+        WOKEN.store(0, SeqCst);
+        let waker = unsafe { task::Waker::from_raw(task::RawWaker::new(&(), &VTABLE)) };
+        let mut context = task::Context::from_waker(&waker);
+
+        let (sender, mut receiver) = oneshot::channel::<Box<u128>>();
+        let poll_result = future::Future::poll(pin::Pin::new(&mut receiver), &mut context);
+        assert!(poll_result.is_pending());
+
+        assert_eq!(receiver.try_recv(), Err(oneshot::TryRecvError::Empty));
+        assert_eq!(receiver.try_recv_mut(), Err(oneshot::TryRecvError::Empty));
+        assert!(receiver.is_empty());
+
+        sender.send(Box::new(19)).unwrap();
+        assert_eq!(WOKEN.load(SeqCst), 1);
+        let poll_result = future::Future::poll(pin::Pin::new(&mut receiver), &mut context);
+        assert_eq!(poll_result, task::Poll::Ready(Ok(Box::new(19))));
+    });
+}
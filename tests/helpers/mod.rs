@@ -12,7 +12,6 @@ use loom::sync::{
     Arc,
 };
 
-#[cfg(loom)]
 pub mod waker;
 
 pub fn maybe_loom_model(test: impl Fn() + Sync + Send + 'static) {
@@ -33,6 +33,11 @@ pub fn waker() -> (Waker, Arc<WakerHandle>) {
     (unsafe { Waker::from_raw(raw_waker) }, waker_handle)
 }
 
+/// A waker for tests that only need to poll, and never look at the waker.
+pub fn noop_waker() -> Waker {
+    waker().0
+}
+
 pub(super) fn waker_vtable() -> &'static RawWakerVTable {
     &RawWakerVTable::new(clone_raw, wake_raw, wake_by_ref_raw, drop_raw)
 }
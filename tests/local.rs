@@ -0,0 +1,96 @@
+#![cfg(not(loom))]
+
+use core::mem;
+use oneshot::{local_channel, TryRecvError};
+use std::rc::Rc;
+
+mod helpers;
+use helpers::DropCounter;
+
+#[test]
+fn send_before_try_recv() {
+    // A message that can't be sent to another thread
+    let (sender, receiver) = local_channel();
+    let message = Rc::new(19u128);
+    assert!(!sender.is_closed());
+    assert_eq!(receiver.try_recv(), Err(TryRecvError::Empty));
+    sender.send(message.clone()).unwrap();
+    assert!(!receiver.is_closed());
+    assert!(Rc::ptr_eq(&receiver.try_recv().unwrap(), &message));
+    assert!(receiver.is_closed());
+    assert_eq!(receiver.try_recv(), Err(TryRecvError::Disconnected));
+}
+
+#[test]
+fn try_recv_with_dropped_sender() {
+    let (sender, receiver) = local_channel::<u128>();
+    mem::drop(sender);
+    assert!(receiver.is_closed());
+    assert_eq!(receiver.try_recv(), Err(TryRecvError::Disconnected));
+}
+
+#[test]
+fn send_with_dropped_receiver() {
+    let (sender, receiver) = local_channel();
+    let (message, counter) = DropCounter::new(19u128);
+    mem::drop(receiver);
+    assert!(sender.is_closed());
+    let error = sender.send(message).unwrap_err();
+    assert_eq!(*error.as_inner().value(), 19);
+    assert_eq!(counter.count(), 0);
+    mem::drop(error);
+    assert_eq!(counter.count(), 1);
+}
+
+#[test]
+fn drop_receiver_with_message() {
+    let (sender, receiver) = local_channel();
+    let (message, counter) = DropCounter::new(());
+    sender.send(message).unwrap();
+    assert_eq!(counter.count(), 0);
+    mem::drop(receiver);
+    assert_eq!(counter.count(), 1);
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn await_before_send() {
+    let local = tokio::task::LocalSet::new();
+    local
+        .run_until(async {
+            let (sender, receiver) = local_channel();
+            let t = tokio::task::spawn_local(async move {
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+                sender.send(Rc::new(19u128)).unwrap();
+            });
+            assert_eq!(*receiver.await.unwrap(), 19);
+            t.await.unwrap();
+        })
+        .await;
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn await_before_send_then_drop_sender() {
+    let local = tokio::task::LocalSet::new();
+    local
+        .run_until(async {
+            let (sender, receiver) = local_channel::<Rc<u128>>();
+            let t = tokio::task::spawn_local(async move {
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+                mem::drop(sender);
+            });
+            assert_eq!(receiver.await, Err(oneshot::RecvError::Disconnected));
+            t.await.unwrap();
+        })
+        .await;
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn await_after_receiving() {
+    let (sender, mut receiver) = local_channel::<u128>();
+    sender.send(19).unwrap();
+    assert_eq!((&mut receiver).await, Ok(19));
+    assert_eq!(receiver.await, Err(oneshot::RecvError::AlreadyReceived));
+}
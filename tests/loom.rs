@@ -3,6 +3,7 @@
 use oneshot::TryRecvError;
 
 use loom::hint;
+use loom::sync::Arc;
 use loom::thread;
 #[cfg(feature = "async")]
 use std::future::Future;
@@ -33,6 +34,226 @@ fn try_recv() {
     })
 }
 
+#[test]
+fn try_recv_from_two_threads() {
+    /// Lets the receiver be shared between threads, which `Receiver` does not allow by itself.
+    struct SharedReceiver(oneshot::Receiver<u128>);
+    // SAFETY: the threads only call `try_recv` on the receiver, which claims the message with an
+    // atomic compare and exchange and is fine to call concurrently.
+    unsafe impl Sync for SharedReceiver {}
+
+    loom::model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        let receiver = Arc::new(SharedReceiver(receiver));
+
+        let threads: Vec<_> = (0..2)
+            .map(|_| {
+                let receiver = receiver.clone();
+                // Losing to the other thread shows up as a disconnected channel
+                thread::spawn(move || receiver.0.try_recv().ok())
+            })
+            .collect();
+        assert!(sender.send(19).is_ok());
+
+        let mut winners: Vec<u128> = threads
+            .into_iter()
+            .filter_map(|t| t.join().unwrap())
+            .collect();
+        if winners.is_empty() {
+            winners.push(receiver.0.try_recv().unwrap());
+        } else {
+            assert_eq!(receiver.0.try_recv(), Err(TryRecvError::Disconnected));
+        }
+        assert_eq!(winners, [19]);
+    })
+}
+
+#[test]
+fn is_closed_while_sending() {
+    loom::model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+
+        let t = thread::spawn(move || {
+            sender.send(19).unwrap();
+        });
+
+        // The message must never be lost, no matter when `is_closed` observes the channel.
+        assert!(!receiver.is_closed());
+        t.join().unwrap();
+        assert!(!receiver.is_closed());
+        assert_eq!(receiver.try_recv(), Ok(19));
+        assert!(receiver.is_closed());
+    })
+}
+
+#[test]
+fn is_closed_while_dropping_sender() {
+    loom::model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+
+        let t = thread::spawn(move || {
+            drop(sender);
+        });
+
+        // Once closed has been observed, the channel must stay closed.
+        if receiver.is_closed() {
+            assert_eq!(receiver.try_recv(), Err(TryRecvError::Disconnected));
+        }
+        t.join().unwrap();
+        assert!(receiver.is_closed());
+        assert_eq!(receiver.try_recv(), Err(TryRecvError::Disconnected));
+    })
+}
+
+#[test]
+fn has_message_while_sending() {
+    loom::model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+
+        let t = thread::spawn(move || {
+            sender.send(19).unwrap();
+        });
+
+        // If a message has been observed it must be possible to receive it right away.
+        let has_message = receiver.has_message();
+        if has_message {
+            assert_eq!(receiver.try_recv(), Ok(19));
+        }
+        t.join().unwrap();
+        if !has_message {
+            assert!(receiver.has_message());
+            assert_eq!(receiver.try_recv(), Ok(19));
+        }
+        assert!(!receiver.has_message());
+    })
+}
+
+#[test]
+fn sender_is_closed_while_dropping_receiver() {
+    loom::model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+
+        let t = thread::spawn(move || {
+            drop(receiver);
+        });
+
+        if sender.is_closed() {
+            assert!(sender.send(19).is_err());
+            t.join().unwrap();
+        } else {
+            t.join().unwrap();
+            assert!(sender.is_closed());
+            assert!(sender.send(19).is_err());
+        }
+    })
+}
+
+#[test]
+fn peek_while_sending() {
+    loom::model(|| {
+        let (sender, mut receiver) = oneshot::channel::<u128>();
+
+        let t = thread::spawn(move || {
+            sender.send(19).unwrap();
+        });
+
+        // The message is either not there yet, or completely written.
+        let peeked = receiver.peek().copied();
+        assert!(matches!(peeked, None | Some(19)));
+        t.join().unwrap();
+        assert_eq!(receiver.peek(), Some(&19));
+        assert_eq!(receiver.try_recv(), Ok(19));
+    })
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn wait_peek_send_different_threads() {
+    loom::model(|| {
+        let (sender, mut receiver) = oneshot::channel::<u128>();
+        let t = thread::spawn(move || {
+            sender.send(19).unwrap();
+        });
+        assert_eq!(receiver.wait_peek(), Ok(&19));
+        t.join().unwrap();
+        assert_eq!(receiver.try_recv(), Ok(19));
+    })
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn wait_peek_drop_sender_different_threads() {
+    loom::model(|| {
+        let (sender, mut receiver) = oneshot::channel::<u128>();
+        let t = thread::spawn(move || {
+            drop(sender);
+        });
+        assert_eq!(receiver.wait_peek(), Err(oneshot::RecvError::Disconnected));
+        t.join().unwrap();
+    })
+}
+
+#[test]
+fn close_while_sending() {
+    loom::model(|| {
+        let (sender, receiver) = oneshot::channel();
+        let (message, counter) = helpers::DropCounter::new(19u128);
+
+        let t = thread::spawn(move || sender.send(message).is_ok());
+
+        receiver.close();
+        let sent = t.join().unwrap();
+        // A message sent before the close must still be received, exactly once. Otherwise it
+        // was dropped together with the send error.
+        match receiver.try_recv() {
+            Ok(message) => {
+                assert!(sent);
+                assert_eq!(counter.count(), 0);
+                drop(message);
+            }
+            Err(error) => {
+                assert!(!sent);
+                assert_eq!(error, TryRecvError::Disconnected);
+            }
+        }
+        assert_eq!(counter.count(), 1);
+        drop(receiver);
+        assert_eq!(counter.count(), 1);
+    })
+}
+
+#[test]
+fn close_while_dropping_sender() {
+    loom::model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+
+        let t = thread::spawn(move || {
+            drop(sender);
+        });
+
+        receiver.close();
+        assert!(receiver.is_closed());
+        t.join().unwrap();
+        drop(receiver);
+    })
+}
+
+#[test]
+fn close_while_dropping_send_error() {
+    loom::model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        receiver.close();
+
+        let t = thread::spawn(move || {
+            let send_error = sender.send(19).unwrap_err();
+            assert_eq!(send_error.into_inner(), 19);
+        });
+
+        drop(receiver);
+        t.join().unwrap();
+    })
+}
+
 #[cfg(feature = "std")]
 #[test]
 fn send_recv_different_threads() {
@@ -49,6 +270,195 @@ fn send_recv_different_threads() {
     })
 }
 
+#[cfg(feature = "std")]
+#[test]
+fn recv_spin_send_different_threads() {
+    loom::model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        let t = thread::spawn(move || {
+            sender.send(19).unwrap();
+        });
+        assert_eq!(receiver.recv_spin(2), Ok(19));
+        t.join().unwrap();
+    })
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn recv_or_cancel_while_sending_and_cancelling() {
+    loom::model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        let (cancel_sender, cancel) = oneshot::channel::<()>();
+        let t1 = thread::spawn(move || {
+            sender.send(19).unwrap();
+        });
+        let t2 = thread::spawn(move || {
+            cancel_sender.send(()).unwrap();
+        });
+
+        let result = receiver.recv_or_cancel(&cancel);
+        t1.join().unwrap();
+        t2.join().unwrap();
+        match result {
+            Ok(message) => {
+                assert_eq!(message, 19);
+                assert_eq!(receiver.try_recv(), Err(TryRecvError::Disconnected));
+            }
+            Err(error) => {
+                assert_eq!(error, oneshot::RecvOrCancelError::Cancelled);
+                assert_eq!(receiver.try_recv(), Ok(19));
+            }
+        }
+        // The cancel message is never consumed
+        assert_eq!(cancel.try_recv(), Ok(()));
+    })
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn recv_or_cancel_while_dropping_sender_and_cancel_sender() {
+    loom::model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        let (cancel_sender, cancel) = oneshot::channel::<()>();
+        let t1 = thread::spawn(move || {
+            drop(sender);
+        });
+        let t2 = thread::spawn(move || {
+            drop(cancel_sender);
+        });
+
+        let result = receiver.recv_or_cancel(&cancel);
+        assert!(result.is_err());
+        t1.join().unwrap();
+        t2.join().unwrap();
+        assert_eq!(receiver.try_recv(), Err(TryRecvError::Disconnected));
+        assert_eq!(cancel.try_recv(), Err(TryRecvError::Disconnected));
+    })
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn select_any_while_sending_on_both() {
+    loom::model(|| {
+        let (sender1, receiver1) = oneshot::channel::<u128>();
+        let (sender2, receiver2) = oneshot::channel::<u128>();
+        let t1 = thread::spawn(move || {
+            sender1.send(19).unwrap();
+        });
+        let t2 = thread::spawn(move || {
+            drop(sender2);
+        });
+
+        let receivers = [receiver1, receiver2];
+        let result = oneshot::select_any(&receivers);
+        t1.join().unwrap();
+        t2.join().unwrap();
+        match result {
+            (0, Ok(message)) => assert_eq!(message, 19),
+            (1, Err(_)) => assert_eq!(receivers[0].try_recv(), Ok(19)),
+            result => panic!("unexpected result {:?}", result),
+        }
+        assert_eq!(receivers[1].try_recv(), Err(TryRecvError::Disconnected));
+    })
+}
+
+#[test]
+fn reset_while_sending() {
+    loom::model(|| {
+        let (sender, mut receiver) = oneshot::channel::<u128>();
+        let t = thread::spawn(move || {
+            sender.send(19).unwrap();
+        });
+
+        let sender = loop {
+            if let Ok(message) = receiver.try_recv() {
+                assert_eq!(message, 19);
+                break receiver.reset().unwrap();
+            }
+            assert!(receiver.reset().is_none());
+            thread::yield_now();
+        };
+        t.join().unwrap();
+
+        let t = thread::spawn(move || {
+            sender.send(20).unwrap();
+        });
+        t.join().unwrap();
+        assert_eq!(receiver.try_recv(), Ok(20));
+    })
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn on_receive_while_sending() {
+    loom::model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        let (result_sender, result_receiver) = oneshot::channel();
+        let t = thread::spawn(move || sender.send(19).unwrap());
+        receiver.on_receive(move |result| result_sender.send(result).unwrap());
+        assert_eq!(result_receiver.recv(), Ok(Ok(19)));
+        t.join().unwrap();
+    })
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn on_receive_while_dropping_sender() {
+    loom::model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        let (result_sender, result_receiver) = oneshot::channel();
+        let t = thread::spawn(move || drop(sender));
+        receiver.on_receive(move |result| result_sender.send(result).unwrap());
+        assert_eq!(
+            result_receiver.recv(),
+            Ok(Err(oneshot::RecvError::Disconnected))
+        );
+        t.join().unwrap();
+    })
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn send_blocking_while_receiving() {
+    loom::model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        let t = thread::spawn(move || receiver.recv());
+        sender.send_blocking(19).unwrap();
+        assert_eq!(t.join().unwrap(), Ok(19));
+    })
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn send_blocking_while_dropping_receiver() {
+    loom::model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        let t = thread::spawn(move || drop(receiver));
+        assert_eq!(sender.send_blocking(19).unwrap_err().into_inner(), 19);
+        t.join().unwrap();
+    })
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn send_blocking_while_try_receiving() {
+    loom::model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        let t = thread::spawn(move || {
+            let result = receiver.try_recv();
+            drop(receiver);
+            result
+        });
+        match sender.send_blocking(19) {
+            Ok(()) => assert_eq!(t.join().unwrap(), Ok(19)),
+            Err(error) => {
+                assert_eq!(error.into_inner(), 19);
+                assert_eq!(t.join().unwrap(), Err(TryRecvError::Empty));
+            }
+        }
+    })
+}
+
 #[cfg(feature = "std")]
 #[test]
 fn recv_drop_sender_different_threads() {
@@ -181,9 +591,10 @@ fn poll_then_try_recv() {
 
         assert_eq!(receiver.try_recv(), Err(TryRecvError::Empty));
 
+        // Polling again with the same waker keeps the stored one
         assert_eq!(Pin::new(&mut receiver).poll(&mut context), Poll::Pending);
-        assert_eq!(waker_handle.clone_count(), 2);
-        assert_eq!(waker_handle.drop_count(), 1);
+        assert_eq!(waker_handle.clone_count(), 1);
+        assert_eq!(waker_handle.drop_count(), 0);
         assert_eq!(waker_handle.wake_count(), 0);
     })
 }
@@ -221,3 +632,561 @@ fn poll_then_try_recv_while_sending() {
         t.join().unwrap();
     })
 }
+
+#[cfg(feature = "async")]
+#[test]
+fn sender_closed_while_dropping_receiver() {
+    loom::model(|| {
+        let (mut sender, receiver) = oneshot::channel::<u128>();
+        let t = thread::spawn(move || {
+            drop(receiver);
+        });
+        loom::future::block_on(sender.closed());
+        assert!(sender.send(19).is_err());
+        t.join().unwrap();
+    })
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn ready_while_sending() {
+    loom::model(|| {
+        let (sender, mut receiver) = oneshot::channel::<u128>();
+        let t = thread::spawn(move || {
+            sender.send(19).unwrap();
+        });
+        assert_eq!(loom::future::block_on(receiver.ready()), Ok(()));
+        assert_eq!(receiver.try_recv(), Ok(19));
+        t.join().unwrap();
+    })
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn poll_ready_then_send() {
+    loom::model(|| {
+        let (sender, mut receiver) = oneshot::channel::<u128>();
+
+        let (waker, waker_handle) = helpers::waker::waker();
+        let mut context = task::Context::from_waker(&waker);
+        assert_eq!(receiver.poll_ready(&mut context), Poll::Pending);
+
+        let t = thread::spawn(move || {
+            sender.send(19).unwrap();
+        });
+        t.join().unwrap();
+
+        assert_eq!(waker_handle.wake_count(), 1);
+        assert_eq!(receiver.poll_ready(&mut context), Poll::Ready(Ok(())));
+        assert_eq!(receiver.try_recv(), Ok(19));
+        assert_eq!(waker_handle.clone_count(), 1);
+        assert_eq!(waker_handle.drop_count(), 1);
+    })
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn poll_twice_then_send() {
+    loom::model(|| {
+        let (sender, mut receiver) = oneshot::channel::<u128>();
+
+        let (waker, waker_handle) = helpers::waker::waker();
+        let mut context = task::Context::from_waker(&waker);
+        assert_eq!(Pin::new(&mut receiver).poll(&mut context), Poll::Pending);
+        // The stored waker wakes the same task, so it's kept
+        assert_eq!(Pin::new(&mut receiver).poll(&mut context), Poll::Pending);
+        assert_eq!(waker_handle.clone_count(), 1);
+        assert_eq!(waker_handle.drop_count(), 0);
+
+        let t = thread::spawn(move || {
+            sender.send(19).unwrap();
+        });
+        assert_eq!(loom::future::block_on(&mut receiver), Ok(19));
+        t.join().unwrap();
+        assert_eq!(waker_handle.clone_count(), 1);
+        assert_eq!(waker_handle.drop_count(), 1);
+    })
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn poll_with_new_waker_while_sending() {
+    loom::model(|| {
+        let (sender, mut receiver) = oneshot::channel::<u128>();
+
+        let (waker1, waker_handle1) = helpers::waker::waker();
+        let mut context1 = task::Context::from_waker(&waker1);
+        assert_eq!(Pin::new(&mut receiver).poll(&mut context1), Poll::Pending);
+
+        let t = thread::spawn(move || {
+            sender.send(19).unwrap();
+        });
+
+        // Polled from another task while the sender might be waking the first one
+        let (waker2, waker_handle2) = helpers::waker::waker();
+        let mut context2 = task::Context::from_waker(&waker2);
+        if Pin::new(&mut receiver).poll(&mut context2).is_pending() {
+            assert_eq!(loom::future::block_on(&mut receiver), Ok(19));
+        }
+        t.join().unwrap();
+
+        // Every clone of the first waker is either woken or dropped by the replace
+        assert_eq!(waker_handle1.clone_count(), 1);
+        assert_eq!(waker_handle1.drop_count(), 1);
+        assert_eq!(waker_handle2.clone_count(), waker_handle2.drop_count());
+    })
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn sender_closed_while_closing_receiver() {
+    loom::model(|| {
+        let (mut sender, receiver) = oneshot::channel::<u128>();
+        let t = thread::spawn(move || {
+            receiver.close();
+            receiver
+        });
+        loom::future::block_on(sender.closed());
+        assert!(sender.send(19).is_err());
+        drop(t.join().unwrap());
+    })
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn poll_then_close() {
+    loom::model(|| {
+        let (sender, mut receiver) = oneshot::channel::<u128>();
+
+        let (waker, waker_handle) = helpers::waker::waker();
+        let mut context = task::Context::from_waker(&waker);
+        assert_eq!(Pin::new(&mut receiver).poll(&mut context), Poll::Pending);
+
+        let t = thread::spawn(move || sender.send(19).is_ok());
+
+        receiver.close();
+        let sent = t.join().unwrap();
+        let expected = if sent {
+            Ok(19)
+        } else {
+            Err(oneshot::RecvError::Disconnected)
+        };
+        assert_eq!(
+            Pin::new(&mut receiver).poll(&mut context),
+            Poll::Ready(expected)
+        );
+        drop(receiver);
+        assert_eq!(waker_handle.clone_count(), waker_handle.drop_count());
+    })
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn poll_closed_then_drop_receiver() {
+    loom::model(|| {
+        let (mut sender, receiver) = oneshot::channel::<u128>();
+
+        let (waker1, waker_handle1) = helpers::waker::waker();
+        let mut context1 = task::Context::from_waker(&waker1);
+        assert_eq!(sender.poll_closed(&mut context1), Poll::Pending);
+        assert_eq!(waker_handle1.clone_count(), 1);
+        assert_eq!(waker_handle1.drop_count(), 0);
+
+        // Polling again should replace the waker
+        let (waker2, waker_handle2) = helpers::waker::waker();
+        let mut context2 = task::Context::from_waker(&waker2);
+        assert_eq!(sender.poll_closed(&mut context2), Poll::Pending);
+        assert_eq!(waker_handle1.drop_count(), 1);
+        assert_eq!(waker_handle2.clone_count(), 1);
+        assert_eq!(waker_handle2.drop_count(), 0);
+
+        let t = thread::spawn(move || {
+            drop(receiver);
+        });
+        t.join().unwrap();
+
+        assert_eq!(waker_handle1.wake_count(), 0);
+        assert_eq!(waker_handle2.wake_count(), 1);
+        assert_eq!(waker_handle2.drop_count(), 1);
+        assert_eq!(sender.poll_closed(&mut context2), Poll::Ready(()));
+        assert_eq!(waker_handle2.clone_count(), 1);
+    })
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn poll_closed_then_send() {
+    loom::model(|| {
+        let (mut sender, receiver) = oneshot::channel::<u128>();
+
+        let (waker, waker_handle) = helpers::waker::waker();
+        let mut context = task::Context::from_waker(&waker);
+        assert_eq!(sender.poll_closed(&mut context), Poll::Pending);
+
+        let t = thread::spawn(move || {
+            sender.send(19).unwrap();
+        });
+        t.join().unwrap();
+        assert_eq!(receiver.try_recv(), Ok(19));
+        drop(receiver);
+
+        // The waker is only freed, never woken
+        assert_eq!(waker_handle.clone_count(), 1);
+        assert_eq!(waker_handle.drop_count(), 1);
+        assert_eq!(waker_handle.wake_count(), 0);
+    })
+}
+
+#[cfg(all(feature = "async", feature = "std"))]
+#[test]
+fn poll_closed_then_send_then_recv() {
+    loom::model(|| {
+        let (mut sender, receiver) = oneshot::channel::<u128>();
+
+        let (waker, waker_handle) = helpers::waker::waker();
+        let mut context = task::Context::from_waker(&waker);
+        assert_eq!(sender.poll_closed(&mut context), Poll::Pending);
+
+        sender.send(19).unwrap();
+        // Consuming the receiver frees the channel without running the receiver's destructor.
+        assert_eq!(receiver.recv(), Ok(19));
+
+        assert_eq!(waker_handle.clone_count(), 1);
+        assert_eq!(waker_handle.drop_count(), 1);
+        assert_eq!(waker_handle.wake_count(), 0);
+    })
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn poll_twice_while_sending() {
+    loom::model(|| {
+        let (sender, mut receiver) = oneshot::channel::<u128>();
+
+        let (waker1, _waker_handle1) = helpers::waker::waker();
+        let (waker2, _waker_handle2) = helpers::waker::waker();
+
+        let t = thread::spawn(move || {
+            sender.send(19).unwrap();
+        });
+
+        // The second poll replaces the waker, racing with the sender taking it
+        let mut results = Vec::new();
+        for waker in [&waker1, &waker2] {
+            let mut context = task::Context::from_waker(waker);
+            if let Poll::Ready(result) = Pin::new(&mut receiver).poll(&mut context) {
+                results.push(result);
+                break;
+            }
+        }
+        if results.is_empty() {
+            results.push(loom::future::block_on(&mut receiver));
+        }
+        assert_eq!(results, [Ok(19)]);
+
+        let mut context = task::Context::from_waker(&waker1);
+        assert_eq!(
+            Pin::new(&mut receiver).poll(&mut context),
+            Poll::Ready(Err(oneshot::RecvError::AlreadyReceived))
+        );
+        t.join().unwrap();
+    })
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn poll_then_drop_receiver_while_sending() {
+    loom::model(|| {
+        let (sender, mut receiver) = oneshot::channel::<u128>();
+
+        let (waker, waker_handle) = helpers::waker::waker();
+        let mut context = task::Context::from_waker(&waker);
+        assert_eq!(Pin::new(&mut receiver).poll(&mut context), Poll::Pending);
+
+        let t = thread::spawn(move || {
+            let _ = sender.send(19);
+        });
+        drop(receiver);
+        t.join().unwrap();
+
+        // The stored clone was dropped exactly once, either by us or by the sender waking it.
+        assert_eq!(waker_handle.clone_count(), 1);
+        assert_eq!(waker_handle.drop_count(), 1);
+    })
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn drop_recv_future_while_sending() {
+    loom::model(|| {
+        let (sender, mut receiver) = oneshot::channel::<u128>();
+
+        let (waker, waker_handle) = helpers::waker::waker();
+        let mut context = task::Context::from_waker(&waker);
+        let mut future = receiver.recv_async();
+        assert_eq!(Pin::new(&mut future).poll(&mut context), Poll::Pending);
+
+        let t = thread::spawn(move || sender.send(19).unwrap());
+        drop(future);
+        t.join().unwrap();
+
+        // The waker was taken back by the drop or woken by the sender, never both.
+        assert_eq!(waker_handle.clone_count(), 1);
+        assert_eq!(waker_handle.drop_count(), 1);
+        assert_eq!(receiver.try_recv(), Ok(19));
+    })
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn poll_then_drop_receiver_while_dropping_sender() {
+    loom::model(|| {
+        let (sender, mut receiver) = oneshot::channel::<u128>();
+
+        let (waker, waker_handle) = helpers::waker::waker();
+        let mut context = task::Context::from_waker(&waker);
+        assert_eq!(Pin::new(&mut receiver).poll(&mut context), Poll::Pending);
+
+        let t = thread::spawn(move || drop(sender));
+        drop(receiver);
+        t.join().unwrap();
+
+        assert_eq!(waker_handle.clone_count(), 1);
+        assert_eq!(waker_handle.drop_count(), 1);
+    })
+}
+
+#[cfg(all(feature = "std", feature = "async"))]
+#[test]
+fn poll_then_recv_mut_while_sending() {
+    loom::model(|| {
+        let (sender, mut receiver) = oneshot::channel::<u128>();
+
+        let (waker, waker_handle) = helpers::waker::waker();
+        let mut context = task::Context::from_waker(&waker);
+        assert_eq!(Pin::new(&mut receiver).poll(&mut context), Poll::Pending);
+
+        let t = thread::spawn(move || {
+            sender.send(1234).unwrap();
+        });
+        assert_eq!(receiver.recv_mut(), Ok(1234));
+        t.join().unwrap();
+
+        // The task waker was either taken back by the receive or woken by the sender, but dropped
+        // exactly once either way.
+        assert_eq!(waker_handle.clone_count(), 1);
+        assert_eq!(waker_handle.drop_count(), 1);
+        assert!(waker_handle.wake_count() <= 1);
+    })
+}
+
+#[cfg(all(feature = "std", feature = "async"))]
+#[test]
+fn poll_then_recv_mut_while_dropping_sender() {
+    loom::model(|| {
+        let (sender, mut receiver) = oneshot::channel::<u128>();
+
+        let (waker, waker_handle) = helpers::waker::waker();
+        let mut context = task::Context::from_waker(&waker);
+        assert_eq!(Pin::new(&mut receiver).poll(&mut context), Poll::Pending);
+
+        let t = thread::spawn(move || drop(sender));
+        assert_eq!(receiver.recv_mut(), Err(oneshot::RecvError::Disconnected));
+        t.join().unwrap();
+
+        assert_eq!(waker_handle.clone_count(), 1);
+        assert_eq!(waker_handle.drop_count(), 1);
+    })
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn async_select_concurrent_send() {
+    loom::model(|| {
+        let (sender1, receiver1) = oneshot::channel::<u128>();
+        let (sender2, receiver2) = oneshot::channel::<u128>();
+        let t1 = thread::spawn(move || {
+            sender1.send(1).unwrap();
+        });
+        sender2.send(2).unwrap();
+        // Whichever receiver loses, its message is still there to be received. If the first one
+        // loses, the select takes its waker back while the sender might be sending.
+        match loom::future::block_on(oneshot::select(receiver1, receiver2)) {
+            oneshot::Select::Left(1, receiver2) => {
+                assert_eq!(loom::future::block_on(receiver2), Ok(2))
+            }
+            oneshot::Select::Right(2, receiver1) => {
+                assert_eq!(loom::future::block_on(receiver1), Ok(1))
+            }
+            _ => panic!("unexpected select result"),
+        }
+        t1.join().unwrap();
+    })
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn select_blocking_concurrent_send() {
+    loom::model(|| {
+        let (sender1, receiver1) = oneshot::channel::<u128>();
+        let (sender2, receiver2) = oneshot::channel::<u128>();
+        let t1 = thread::spawn(move || {
+            sender1.send(1).unwrap();
+        });
+        let t2 = thread::spawn(move || {
+            sender2.send(2).unwrap();
+        });
+        // The losing sender might be unparking the thread while the select takes its waker back.
+        match oneshot::select_blocking(receiver1, receiver2) {
+            oneshot::Select::Left(1, receiver2) => assert_eq!(receiver2.recv(), Ok(2)),
+            oneshot::Select::Right(2, receiver1) => assert_eq!(receiver1.recv(), Ok(1)),
+            _ => panic!("unexpected select result"),
+        }
+        t1.join().unwrap();
+        t2.join().unwrap();
+    })
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn recv_until_cancelled_while_sending() {
+    loom::model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        let token = oneshot::CancelToken::new();
+        let t1 = thread::spawn(move || {
+            sender.send(19).unwrap();
+        });
+        let t2 = thread::spawn({
+            let token = token.clone();
+            move || token.cancel()
+        });
+        let result = receiver.recv_until_cancelled(&token);
+        t1.join().unwrap();
+        t2.join().unwrap();
+        match result {
+            Ok(message) => assert_eq!(message, 19),
+            // The message is still there to be received after a cancel
+            Err(oneshot::RecvOrCancelError::Cancelled) => assert_eq!(receiver.try_recv(), Ok(19)),
+            Err(error) => panic!("unexpected error {:?}", error),
+        }
+    })
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn race_blocking_while_sending_and_dropping() {
+    loom::model(|| {
+        let (senders, receivers): (Vec<_>, Vec<_>) =
+            (0..3).map(|_| oneshot::channel::<u128>()).unzip();
+        let mut senders = senders.into_iter();
+        let (sender0, sender1, sender2) = (
+            senders.next().unwrap(),
+            senders.next().unwrap(),
+            senders.next().unwrap(),
+        );
+        let t0 = thread::spawn(move || drop(sender0));
+        let t1 = thread::spawn(move || sender1.send(1).unwrap());
+        drop(sender2);
+        // Only the second channel gets a message, and the others never win
+        let (index, message, others) = oneshot::race_blocking(receivers).unwrap();
+        assert_eq!((index, message, others.len()), (1, 1, 2));
+        t0.join().unwrap();
+        t1.join().unwrap();
+    })
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn race_blocking_while_sending_on_both() {
+    loom::model(|| {
+        let (sender1, receiver1) = oneshot::channel::<u128>();
+        let (sender2, receiver2) = oneshot::channel::<u128>();
+        let t1 = thread::spawn(move || sender1.send(1).unwrap());
+        let t2 = thread::spawn(move || sender2.send(2).unwrap());
+        // Whichever loses, its message is still there to be received. The losing sender might be
+        // unparking the thread while the race takes its waker back.
+        let (index, message, mut others) =
+            oneshot::race_blocking(vec![receiver1, receiver2]).unwrap();
+        assert_eq!(message, index as u128 + 1);
+        assert_eq!(others.pop().unwrap().recv(), Ok(2 - index as u128));
+        t1.join().unwrap();
+        t2.join().unwrap();
+    })
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn race_blocking_while_dropping_all() {
+    loom::model(|| {
+        let (sender1, receiver1) = oneshot::channel::<u128>();
+        let (sender2, receiver2) = oneshot::channel::<u128>();
+        let t1 = thread::spawn(move || drop(sender1));
+        let t2 = thread::spawn(move || drop(sender2));
+        assert!(oneshot::race_blocking(vec![receiver1, receiver2]).is_err());
+        t1.join().unwrap();
+        t2.join().unwrap();
+    })
+}
+
+// The receiver below times out right after its first park, see `enable_virtual_clock`. So its
+// swap back to EMPTY on expiry races a sender that is still in the RECEIVING state, that has
+// taken the waker and is UNPARKING, or that has already sent or been dropped. The message is a
+// loom `Arc`, for loom to catch a double drop and a leak.
+
+#[cfg(feature = "std")]
+#[test]
+fn recv_deadline_expires_while_sending() {
+    loom::model(|| {
+        let (sender, receiver) = oneshot::channel::<Arc<u128>>();
+        let t = thread::spawn(move || {
+            sender.send(Arc::new(19)).unwrap();
+        });
+        oneshot::test_hooks::enable_virtual_clock();
+        let result = receiver.recv_timeout(Duration::from_secs(1));
+        t.join().unwrap();
+        // The message is received exactly once, now or after the timeout
+        let message = match result {
+            Ok(message) => {
+                assert_eq!(receiver.try_recv().err(), Some(TryRecvError::Disconnected));
+                message
+            }
+            Err(oneshot::RecvTimeoutError::Timeout) => receiver.try_recv().unwrap(),
+            Err(oneshot::RecvTimeoutError::Disconnected) => panic!("receiver disconnected"),
+        };
+        assert_eq!(*message, 19);
+        assert_eq!(Arc::strong_count(&message), 1);
+    })
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn recv_deadline_expires_while_dropping_sender() {
+    loom::model(|| {
+        let (sender, receiver) = oneshot::channel::<Arc<u128>>();
+        let t = thread::spawn(move || drop(sender));
+        oneshot::test_hooks::enable_virtual_clock();
+        // Either way, the sender has not sent anything
+        assert!(receiver.recv_timeout(Duration::from_secs(1)).is_err());
+        t.join().unwrap();
+        assert_eq!(receiver.try_recv().err(), Some(TryRecvError::Disconnected));
+    })
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn recv_deadline_expires_before_send() {
+    loom::model(|| {
+        let (sender, receiver) = oneshot::channel::<Arc<u128>>();
+        oneshot::test_hooks::enable_virtual_clock();
+        // Nothing sends while the receiver waits, so it takes its waker back on expiry
+        assert_eq!(
+            receiver.recv_timeout(Duration::from_secs(1)).err(),
+            Some(oneshot::RecvTimeoutError::Timeout)
+        );
+        let t = thread::spawn(move || {
+            sender.send(Arc::new(19)).unwrap();
+        });
+        t.join().unwrap();
+        assert_eq!(*receiver.try_recv().unwrap(), 19);
+    })
+}
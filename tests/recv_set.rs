@@ -0,0 +1,188 @@
+#![cfg(all(feature = "std", feature = "async", not(loom)))]
+
+use core::task::{self, Poll};
+use core::time::Duration;
+use oneshot::{RecvError, RecvSet};
+
+mod helpers;
+use helpers::waker::waker;
+
+async fn next<T>(set: &mut RecvSet<u32, T>) -> Option<(u32, Result<T, RecvError>)> {
+    futures_util::future::poll_fn(|cx| set.poll_next(cx)).await
+}
+
+#[tokio::test]
+async fn yields_in_completion_order() {
+    let mut set = RecvSet::new();
+    let mut senders = Vec::new();
+    for key in 0..3 {
+        let (sender, receiver) = oneshot::channel::<u32>();
+        assert!(set.insert(key, receiver).is_none());
+        senders.push(sender);
+    }
+    assert_eq!(set.len(), 3);
+    let mut senders = senders.into_iter();
+    let (sender0, sender1, sender2) = (
+        senders.next().unwrap(),
+        senders.next().unwrap(),
+        senders.next().unwrap(),
+    );
+    let t = tokio::spawn(async move {
+        sender2.send(20).unwrap();
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        drop(sender0);
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        sender1.send(10).unwrap();
+    });
+    assert_eq!(next(&mut set).await, Some((2, Ok(20))));
+    assert_eq!(
+        next(&mut set).await,
+        Some((0, Err(RecvError::Disconnected)))
+    );
+    assert_eq!(next(&mut set).await, Some((1, Ok(10))));
+    assert!(set.is_empty());
+    assert_eq!(next(&mut set).await, None);
+    t.await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn many_receivers_from_other_thread() {
+    let mut set = RecvSet::new();
+    let mut senders = Vec::new();
+    for key in 0..1000 {
+        let (sender, receiver) = oneshot::channel::<u32>();
+        set.insert(key, receiver);
+        senders.push((key, sender));
+    }
+    let t = std::thread::spawn(move || {
+        for (key, sender) in senders.into_iter().rev() {
+            sender.send(key * 2).unwrap();
+        }
+    });
+    let mut seen = vec![false; 1000];
+    while let Some((key, result)) = next(&mut set).await {
+        assert_eq!(result, Ok(key * 2));
+        assert!(!seen[key as usize]);
+        seen[key as usize] = true;
+    }
+    assert!(seen.iter().all(|&seen| seen));
+    t.join().unwrap();
+}
+
+#[test]
+fn remove_pending_entry() {
+    let (waker, counter) = waker();
+    let mut context = task::Context::from_waker(&waker);
+
+    let mut set = RecvSet::new();
+    let (sender1, receiver1) = oneshot::channel::<u32>();
+    let (sender2, receiver2) = oneshot::channel::<u32>();
+    set.insert(1, receiver1);
+    set.insert(2, receiver2);
+    assert_eq!(set.poll_next(&mut context), Poll::Pending);
+
+    // The removed receiver no longer wakes the set, and still gets its message
+    let receiver1 = set.remove(&1).unwrap();
+    assert!(set.remove(&1).is_none());
+    assert!(!set.contains_key(&1));
+    sender1.send(10).unwrap();
+    assert_eq!(counter.wake_count(), 0);
+    assert_eq!(set.poll_next(&mut context), Poll::Pending);
+    assert_eq!(receiver1.try_recv(), Ok(10));
+
+    // Dropping a removed receiver closes its channel
+    drop(set.remove(&2));
+    assert!(sender2.is_closed());
+    assert_eq!(set.poll_next(&mut context), Poll::Ready(None));
+}
+
+#[test]
+fn remove_woken_entry_before_poll() {
+    let (waker, counter) = waker();
+    let mut context = task::Context::from_waker(&waker);
+
+    let mut set = RecvSet::new();
+    let (sender1, receiver1) = oneshot::channel::<u32>();
+    let (_sender2, receiver2) = oneshot::channel::<u32>();
+    set.insert(1, receiver1);
+    set.insert(2, receiver2);
+    assert_eq!(set.poll_next(&mut context), Poll::Pending);
+    sender1.send(10).unwrap();
+    assert_eq!(counter.wake_count(), 1);
+
+    // The entry is already in the ready queue, but is not yielded after being removed
+    let receiver1 = set.remove(&1).unwrap();
+    assert_eq!(set.poll_next(&mut context), Poll::Pending);
+    assert_eq!(receiver1.try_recv(), Ok(10));
+
+    // The slot is reused by a new entry, which the stale node does not affect
+    let (sender3, receiver3) = oneshot::channel::<u32>();
+    set.insert(3, receiver3);
+    assert_eq!(set.poll_next(&mut context), Poll::Pending);
+    sender3.send(30).unwrap();
+    assert_eq!(set.poll_next(&mut context), Poll::Ready(Some((3, Ok(30)))));
+}
+
+#[test]
+fn insert_replaces_and_wakes() {
+    let (waker, counter) = waker();
+    let mut context = task::Context::from_waker(&waker);
+
+    let mut set = RecvSet::new();
+    let (sender1, receiver1) = oneshot::channel::<u32>();
+    assert_eq!(
+        set.poll_next(&mut context),
+        Poll::<Option<(u32, Result<u32, RecvError>)>>::Ready(None)
+    );
+    set.insert(1, receiver1);
+    // The task polling the set is woken to poll the new receiver
+    assert_eq!(counter.wake_count(), 1);
+
+    let (sender2, receiver2) = oneshot::channel::<u32>();
+    let replaced = set.insert(1, receiver2).unwrap();
+    assert_eq!(set.len(), 1);
+    sender1.send(10).unwrap();
+    sender2.send(20).unwrap();
+    assert_eq!(replaced.try_recv(), Ok(10));
+    assert_eq!(set.poll_next(&mut context), Poll::Ready(Some((1, Ok(20)))));
+}
+
+#[test]
+fn poll_with_new_waker() {
+    let (waker1, counter1) = waker();
+    let (waker2, counter2) = waker();
+
+    let mut set = RecvSet::new();
+    let (sender, receiver) = oneshot::channel::<u32>();
+    set.insert(1, receiver);
+    assert_eq!(
+        set.poll_next(&mut task::Context::from_waker(&waker1)),
+        Poll::Pending
+    );
+    assert_eq!(
+        set.poll_next(&mut task::Context::from_waker(&waker2)),
+        Poll::Pending
+    );
+    sender.send(10).unwrap();
+    assert_eq!(counter1.wake_count(), 0);
+    assert_eq!(counter2.wake_count(), 1);
+    assert_eq!(
+        set.poll_next(&mut task::Context::from_waker(&waker2)),
+        Poll::Ready(Some((1, Ok(10))))
+    );
+}
+
+#[test]
+fn drop_with_woken_entries() {
+    let (waker, counter) = waker();
+    let mut context = task::Context::from_waker(&waker);
+
+    let mut set = RecvSet::new();
+    let (sender, receiver) = oneshot::channel::<u32>();
+    set.insert(1, receiver);
+    assert_eq!(set.poll_next(&mut context), Poll::Pending);
+    sender.send(10).unwrap();
+    // The node in the ready queue is released along with the set
+    drop(set);
+    assert_eq!(counter.clone_count(), counter.drop_count());
+}
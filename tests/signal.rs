@@ -0,0 +1,50 @@
+#![cfg(all(unix, feature = "std", not(loom)))]
+
+use core::sync::atomic::{AtomicBool, Ordering};
+use oneshot::RecvInterruptedError;
+use std::os::raw::c_int;
+use std::os::unix::thread::JoinHandleExt;
+use std::thread;
+use std::time::{Duration, Instant};
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+extern "C" fn on_sigusr1(_signal: c_int) {
+    // Only async-signal-safe operations are allowed in here. Storing to an atomic is one.
+    INTERRUPTED.store(true, Ordering::Release);
+}
+
+#[test]
+fn recv_interruptible_by_signal() {
+    let handler = on_sigusr1 as extern "C" fn(c_int) as libc::sighandler_t;
+    assert_ne!(
+        unsafe { libc::signal(libc::SIGUSR1, handler) },
+        libc::SIG_ERR
+    );
+
+    let (sender, receiver) = oneshot::channel::<u128>();
+    let t = thread::spawn(move || {
+        let result = receiver.recv_interruptible(&INTERRUPTED);
+        (result, receiver)
+    });
+
+    thread::sleep(Duration::from_millis(50));
+    let start = Instant::now();
+    assert_eq!(
+        unsafe { libc::pthread_kill(t.as_pthread_t(), libc::SIGUSR1) },
+        0
+    );
+    // The signal handler can't unpark the thread, so do it from here once the flag is set
+    while !INTERRUPTED.load(Ordering::Acquire) {
+        thread::sleep(Duration::from_millis(1));
+    }
+    t.thread().unpark();
+
+    let (result, receiver) = t.join().unwrap();
+    assert_eq!(result, Err(RecvInterruptedError::Interrupted));
+    assert!(start.elapsed() < Duration::from_secs(1));
+
+    // The receiver is still usable after the interruption
+    sender.send(19).unwrap();
+    assert_eq!(receiver.try_recv(), Ok(19));
+}
@@ -0,0 +1,141 @@
+#![cfg(not(loom))]
+
+use core::mem;
+use oneshot::{StaticChannel, TryRecvError};
+#[cfg(any(feature = "std", feature = "async"))]
+use std::time::Duration;
+
+mod helpers;
+use helpers::DropCounter;
+
+#[test]
+fn send_before_try_recv() {
+    static CHANNEL: StaticChannel<u128> = StaticChannel::new();
+    let (sender, receiver) = CHANNEL.split();
+    assert!(!sender.is_closed());
+    assert_eq!(receiver.try_recv(), Err(TryRecvError::Empty));
+    sender.send(19).unwrap();
+    assert!(receiver.has_message());
+    assert_eq!(receiver.try_recv(), Ok(19));
+    assert_eq!(receiver.try_recv(), Err(TryRecvError::Disconnected));
+}
+
+#[test]
+fn try_recv_with_dropped_sender() {
+    let channel = StaticChannel::<u128>::new();
+    let (sender, receiver) = channel.split();
+    mem::drop(sender);
+    assert_eq!(receiver.try_recv(), Err(TryRecvError::Disconnected));
+}
+
+#[test]
+fn send_with_dropped_receiver() {
+    let channel = StaticChannel::new();
+    let (sender, receiver) = channel.split();
+    let (message, counter) = DropCounter::new(19u128);
+    mem::drop(receiver);
+    assert!(sender.is_closed());
+    let error = sender.send(message).unwrap_err();
+    assert_eq!(*error.as_inner().value(), 19);
+    assert_eq!(counter.count(), 0);
+    mem::drop(error);
+    assert_eq!(counter.count(), 1);
+}
+
+#[test]
+fn unreceived_message_is_dropped() {
+    let (message, counter) = DropCounter::new(19u128);
+    let channel = StaticChannel::new();
+    let (sender, receiver) = channel.split();
+    sender.send(message).unwrap();
+    mem::drop(receiver);
+    assert_eq!(counter.count(), 1);
+
+    // Leaking the receiver leaves the message to the channel
+    let (message, counter) = DropCounter::new(19u128);
+    let channel = StaticChannel::new();
+    let (sender, receiver) = channel.split();
+    sender.send(message).unwrap();
+    mem::forget(receiver);
+    assert_eq!(counter.count(), 0);
+    mem::drop(channel);
+    assert_eq!(counter.count(), 1);
+}
+
+#[test]
+#[should_panic(expected = "StaticChannel::split called more than once")]
+fn split_twice_panics() {
+    let channel = StaticChannel::<u128>::new();
+    let _endpoints = channel.split();
+    let _ = channel.split();
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn recv_before_send() {
+    static CHANNEL: StaticChannel<u128> = StaticChannel::new();
+    let (sender, receiver) = CHANNEL.split();
+    let t = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(10));
+        sender.send(19).unwrap();
+    });
+    assert_eq!(receiver.recv(), Ok(19));
+    t.join().unwrap();
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn recv_before_send_then_drop_sender() {
+    static CHANNEL: StaticChannel<u128> = StaticChannel::new();
+    let (sender, receiver) = CHANNEL.split();
+    let t = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(10));
+        mem::drop(sender);
+    });
+    assert_eq!(receiver.recv(), Err(oneshot::RecvError::Disconnected));
+    t.join().unwrap();
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn await_before_send() {
+    static CHANNEL: StaticChannel<u128> = StaticChannel::new();
+    let (sender, receiver) = CHANNEL.split();
+    let t = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        sender.send(19).unwrap();
+    });
+    assert_eq!(receiver.await, Ok(19));
+    t.await.unwrap();
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn await_after_receiving() {
+    let channel = StaticChannel::new();
+    let (sender, mut receiver) = channel.split();
+    sender.send(19u128).unwrap();
+    assert_eq!((&mut receiver).await, Ok(19));
+    assert_eq!(receiver.await, Err(oneshot::RecvError::AlreadyReceived));
+}
+
+#[cfg(all(feature = "std", feature = "async"))]
+#[test]
+fn poll_then_recv() {
+    use core::future::Future;
+    use core::pin::Pin;
+    use core::task;
+
+    static CHANNEL: StaticChannel<u128> = StaticChannel::new();
+    let (sender, mut receiver) = CHANNEL.split();
+    let waker = helpers::waker::noop_waker();
+    let mut context = task::Context::from_waker(&waker);
+    assert!(Pin::new(&mut receiver).poll(&mut context).is_pending());
+    assert_eq!(receiver.try_recv(), Err(TryRecvError::Empty));
+    let t = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(10));
+        sender.send(19).unwrap();
+    });
+    assert_eq!(receiver.recv(), Ok(19));
+    t.join().unwrap();
+}
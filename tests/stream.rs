@@ -0,0 +1,160 @@
+#![cfg(all(feature = "stream", not(loom)))]
+
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{self, Poll};
+use core::time::Duration;
+use futures_core::{FusedStream, Stream};
+
+mod helpers;
+
+/// Future resolving to the next item of a stream.
+struct Next<'a, S>(&'a mut S);
+
+impl<S: Stream + Unpin> Future for Next<'_, S> {
+    type Output = Option<S::Item>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut *self.0).poll_next(cx)
+    }
+}
+
+#[tokio::test]
+async fn stream_yields_message_once() {
+    let (sender, receiver) = oneshot::channel();
+    let mut stream = receiver.into_stream();
+    assert_eq!(stream.size_hint(), (1, Some(1)));
+    assert!(!stream.is_terminated());
+    let t = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        sender.send(19u128)
+    });
+    assert_eq!(Next(&mut stream).await, Some(Ok(19)));
+    assert!(stream.is_terminated());
+    assert_eq!(stream.size_hint(), (0, Some(0)));
+    assert_eq!(Next(&mut stream).await, None);
+    t.await.unwrap().unwrap();
+}
+
+#[tokio::test]
+async fn stream_yields_error_once() {
+    let (sender, receiver) = oneshot::channel::<u128>();
+    drop(sender);
+    let mut stream = receiver.into_stream();
+    assert_eq!(
+        Next(&mut stream).await,
+        Some(Err(oneshot::RecvError::Disconnected))
+    );
+    assert_eq!(Next(&mut stream).await, None);
+}
+
+#[tokio::test]
+async fn receiver_is_terminated() {
+    use futures_core::FusedFuture;
+
+    let (sender, mut receiver) = oneshot::channel::<u128>();
+    assert!(!receiver.is_terminated());
+    sender.send(19).unwrap();
+    assert!(!receiver.is_terminated());
+    assert_eq!((&mut receiver).await, Ok(19));
+    assert!(receiver.is_terminated());
+
+    // A disconnect only terminates the receiver once a poll has reported it.
+    let (sender, mut receiver) = oneshot::channel::<u128>();
+    drop(sender);
+    assert!(!receiver.is_terminated());
+    assert_eq!((&mut receiver).await, Err(oneshot::RecvError::Disconnected));
+    assert!(receiver.is_terminated());
+}
+
+/// Polls the receiver like `futures::select!` does in a loop, skipping it once it is terminated.
+/// Returns every output it resolved to.
+fn select_loop<T>(receiver: &mut oneshot::Receiver<T>) -> Vec<Result<T, oneshot::RecvError>> {
+    use futures_core::FusedFuture;
+
+    let waker = helpers::waker::noop_waker();
+    let mut context = task::Context::from_waker(&waker);
+    let mut outputs = Vec::new();
+    for _ in 0..10 {
+        if !receiver.is_terminated() {
+            if let Poll::Ready(output) = Pin::new(&mut *receiver).poll(&mut context) {
+                outputs.push(output);
+            }
+        }
+    }
+    outputs
+}
+
+#[test]
+fn select_loop_polls_until_terminated() {
+    let (sender, mut receiver) = oneshot::channel::<u128>();
+    assert!(select_loop(&mut receiver).is_empty());
+    sender.send(19).unwrap();
+    assert_eq!(select_loop(&mut receiver), [Ok(19)]);
+    assert!(select_loop(&mut receiver).is_empty());
+
+    let (sender, mut receiver) = oneshot::channel::<u128>();
+    drop(sender);
+    assert_eq!(
+        select_loop(&mut receiver),
+        [Err(oneshot::RecvError::Disconnected)]
+    );
+    assert!(select_loop(&mut receiver).is_empty());
+}
+
+#[tokio::test]
+async fn message_stream_collects_message() {
+    use futures_util::StreamExt;
+
+    let (sender, receiver) = oneshot::channel();
+    sender.send(19u128).unwrap();
+    let stream = receiver.into_message_stream();
+    assert_eq!(stream.size_hint(), (0, Some(1)));
+    assert_eq!(stream.collect::<Vec<_>>().await, [19]);
+
+    let (sender, receiver) = oneshot::channel::<u128>();
+    drop(sender);
+    assert!(receiver
+        .into_message_stream()
+        .collect::<Vec<_>>()
+        .await
+        .is_empty());
+}
+
+#[tokio::test]
+async fn message_stream_is_fused() {
+    let (sender, receiver) = oneshot::channel::<u128>();
+    drop(sender);
+    let mut stream = receiver.into_message_stream();
+    assert!(!stream.is_terminated());
+    assert_eq!(Next(&mut stream).await, None);
+    assert!(stream.is_terminated());
+    assert_eq!(Next(&mut stream).await, None);
+    assert_eq!(stream.size_hint(), (0, Some(0)));
+}
+
+#[tokio::test]
+async fn message_streams_in_select() {
+    use futures_util::{stream, StreamExt};
+
+    let (sender1, receiver1) = oneshot::channel();
+    let (sender2, receiver2) = oneshot::channel::<u128>();
+    let (sender3, receiver3) = oneshot::channel();
+    let t = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        sender1.send(1u128).unwrap();
+        drop(sender2);
+        sender3.send(3).unwrap();
+    });
+    let selected = stream::select(
+        receiver1.into_message_stream(),
+        stream::select(
+            receiver2.into_message_stream(),
+            receiver3.into_message_stream(),
+        ),
+    );
+    let mut messages = selected.collect::<Vec<_>>().await;
+    messages.sort_unstable();
+    assert_eq!(messages, [1, 3]);
+    t.await.unwrap();
+}
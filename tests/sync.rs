@@ -1,17 +1,26 @@
 use core::mem;
-use oneshot::TryRecvError;
+use oneshot::{
+    ChannelId, ChannelState, IntoTryRecvError, RecvOrError, SendStatus, SendWithError, TryRecvError,
+};
+use std::collections::HashSet;
 
 #[cfg(feature = "std")]
-use oneshot::{RecvError, RecvTimeoutError};
+use core::mem::MaybeUninit;
+#[cfg(feature = "std")]
+use core::sync::atomic::{AtomicBool, Ordering};
+#[cfg(feature = "std")]
+use oneshot::{RecvError, RecvInterruptedError, RecvOrCancelError, RecvTimeoutError};
+#[cfg(feature = "std")]
+use std::sync::Arc;
 #[cfg(feature = "std")]
 use std::time::{Duration, Instant};
 
 #[cfg(feature = "std")]
 mod thread {
     #[cfg(loom)]
-    pub use loom::thread::spawn;
+    pub use loom::thread::{current, spawn, Thread};
     #[cfg(not(loom))]
-    pub use std::thread::{sleep, spawn};
+    pub use std::thread::{current, sleep, spawn, Thread};
 
     #[cfg(loom)]
     pub fn sleep(_timeout: core::time::Duration) {
@@ -32,7 +41,8 @@ fn send_before_try_recv() {
         assert_eq!(receiver.try_recv(), Err(TryRecvError::Disconnected));
         #[cfg(feature = "std")]
         {
-            assert_eq!(receiver.recv_ref(), Err(RecvError));
+            let mut receiver = receiver;
+            assert_eq!(receiver.recv_mut(), Err(RecvError::AlreadyReceived));
             assert!(receiver.recv_timeout(Duration::from_secs(1)).is_err());
         }
     })
@@ -69,275 +79,2664 @@ fn send_before_recv() {
 
 #[cfg(feature = "std")]
 #[test]
-fn send_before_recv_ref() {
+fn recv_spin() {
     maybe_loom_model(|| {
-        let (sender, receiver) = oneshot::channel();
-        assert!(sender.send(19i128).is_ok());
-
-        assert_eq!(receiver.recv_ref(), Ok(19i128));
-        assert_eq!(receiver.recv_ref(), Err(RecvError));
-        assert_eq!(receiver.try_recv(), Err(TryRecvError::Disconnected));
-        assert!(receiver.recv_timeout(Duration::from_secs(1)).is_err());
-    })
+        let (sender, receiver) = oneshot::channel::<u128>();
+        sender.send(19).unwrap();
+        assert_eq!(receiver.recv_spin(10), Ok(19));
+    });
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        mem::drop(sender);
+        assert_eq!(receiver.recv_spin(10), Err(RecvError::Disconnected));
+    });
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        let t = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(2));
+            sender.send(19).unwrap();
+        });
+        // Spinning does not find the message, so the receiver parks
+        assert_eq!(receiver.recv_spin(1), Ok(19));
+        t.join().unwrap();
+    });
 }
 
 #[cfg(feature = "std")]
 #[test]
-fn send_before_recv_timeout() {
+fn recv_busy() {
     maybe_loom_model(|| {
-        let (sender, receiver) = oneshot::channel();
-        assert!(sender.send(19i128).is_ok());
-
-        let start = Instant::now();
-        let timeout = Duration::from_secs(1);
-        assert_eq!(receiver.recv_timeout(timeout), Ok(19i128));
-        assert!(start.elapsed() < Duration::from_millis(100));
+        let (sender, receiver) = oneshot::channel::<u128>();
+        sender.send(19).unwrap();
+        assert_eq!(receiver.recv_busy(None), Ok(19));
+        assert_eq!(
+            receiver.recv_busy(None),
+            Err(RecvTimeoutError::Disconnected)
+        );
+    });
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        let t = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(2));
+            sender.send(19).unwrap();
+        });
+        assert_eq!(receiver.recv_busy(None), Ok(19));
+        t.join().unwrap();
+    });
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        let t = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(2));
+            mem::drop(sender);
+        });
+        assert_eq!(
+            receiver.recv_busy(None),
+            Err(RecvTimeoutError::Disconnected)
+        );
+        t.join().unwrap();
+    });
+}
 
-        assert!(receiver.recv_timeout(timeout).is_err());
-        assert!(receiver.try_recv().is_err());
-        assert!(receiver.recv().is_err());
-    })
+// Busy waiting on real time explodes the number of loom permutations
+#[cfg(all(feature = "std", not(loom)))]
+#[test]
+fn recv_busy_timeout() {
+    let (sender, receiver) = oneshot::channel::<u128>();
+    let start = Instant::now();
+    let timeout = Duration::from_millis(50);
+    assert_eq!(
+        receiver.recv_busy(Some(timeout)),
+        Err(RecvTimeoutError::Timeout)
+    );
+    assert!(start.elapsed() >= timeout);
+    assert_eq!(
+        receiver.recv_busy(Some(Duration::ZERO)),
+        Err(RecvTimeoutError::Timeout)
+    );
+    sender.send(19).unwrap();
+    assert_eq!(receiver.recv_busy(Some(Duration::ZERO)), Ok(19));
 }
 
+// Measures real time, which loom doesn't advance
+#[cfg(all(feature = "std", not(loom)))]
 #[test]
-fn send_then_drop_receiver() {
-    maybe_loom_model(|| {
-        let (sender, receiver) = oneshot::channel();
-        assert!(sender.send(19i128).is_ok());
-        mem::drop(receiver);
-    })
+fn recv_timed() {
+    let (sender, receiver) = oneshot::channel::<u128>();
+    sender.send(19).unwrap();
+    let (result, waited) = receiver.recv_timed();
+    assert_eq!(result, Ok(19));
+    assert!(waited < Duration::from_millis(50));
+
+    let (sender, receiver) = oneshot::channel::<u128>();
+    let t = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(100));
+        sender.send(19).unwrap();
+    });
+    let (result, waited) = receiver.recv_timed();
+    assert_eq!(result, Ok(19));
+    assert!(waited >= Duration::from_millis(100));
+    assert!(waited < Duration::from_millis(300));
+    t.join().unwrap();
+
+    let (sender, receiver) = oneshot::channel::<u128>();
+    let t = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(100));
+        mem::drop(sender);
+    });
+    let (result, waited) = receiver.recv_timed();
+    assert_eq!(result, Err(RecvError::Disconnected));
+    assert!(waited >= Duration::from_millis(100));
+    t.join().unwrap();
 }
 
+#[cfg(all(feature = "std", not(loom)))]
 #[test]
-fn send_with_dropped_receiver() {
-    maybe_loom_model(|| {
-        let (sender, receiver) = oneshot::channel();
-        mem::drop(receiver);
-        let send_error = sender.send(5u128).unwrap_err();
-        assert_eq!(*send_error.as_inner(), 5);
-        assert_eq!(send_error.into_inner(), 5);
-    })
+fn recv_timeout_timed() {
+    let (sender, receiver) = oneshot::channel::<u128>();
+    let timeout = Duration::from_millis(100);
+    let (result, waited) = receiver.recv_timeout_timed(timeout);
+    assert_eq!(result, Err(RecvTimeoutError::Timeout));
+    assert!(waited >= timeout);
+
+    let t = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(100));
+        sender.send(19).unwrap();
+    });
+    let (result, waited) = receiver.recv_timeout_timed(Duration::from_secs(10));
+    assert_eq!(result, Ok(19));
+    assert!(waited >= Duration::from_millis(100));
+    assert!(waited < Duration::from_millis(300));
+    t.join().unwrap();
 }
 
+#[cfg(feature = "std")]
 #[test]
-fn try_recv_with_dropped_sender() {
+fn recv_into() {
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel();
+        let (message, counter) = DropCounter::new([7u8; 1024]);
+        let mut slot = MaybeUninit::uninit();
+        sender.send(message).unwrap();
+        assert_eq!(receiver.recv_into(&mut slot), Ok(()));
+        assert_eq!(counter.count(), 0);
+        let message = unsafe { slot.assume_init() };
+        assert_eq!(*message.value(), [7u8; 1024]);
+        mem::drop(message);
+        assert_eq!(counter.count(), 1);
+    });
     maybe_loom_model(|| {
         let (sender, receiver) = oneshot::channel::<u128>();
-        mem::drop(sender);
-        receiver.try_recv().unwrap_err();
-    })
+        let t = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(2));
+            sender.send(19).unwrap();
+        });
+        let mut slot = MaybeUninit::uninit();
+        assert_eq!(receiver.recv_into(&mut slot), Ok(()));
+        assert_eq!(unsafe { slot.assume_init() }, 19);
+        t.join().unwrap();
+    });
 }
 
 #[cfg(feature = "std")]
 #[test]
-fn recv_with_dropped_sender() {
+fn recv_into_with_dropped_sender() {
     maybe_loom_model(|| {
         let (sender, receiver) = oneshot::channel::<u128>();
         mem::drop(sender);
-        receiver.recv().unwrap_err();
-    })
+        let mut slot = MaybeUninit::new(5);
+        assert_eq!(receiver.recv_into(&mut slot), Err(RecvError::Disconnected));
+        assert_eq!(unsafe { slot.assume_init() }, 5);
+    });
 }
 
 #[cfg(feature = "std")]
 #[test]
-fn recv_before_send() {
+fn recv_map_ref() {
     maybe_loom_model(|| {
-        let (sender, receiver) = oneshot::channel();
+        let (sender, mut receiver) = oneshot::channel();
+        let (message, counter) = DropCounter::new([7u8; 1024]);
+        sender.send(message).unwrap();
+        assert_eq!(
+            receiver.recv_map_ref(|message| {
+                assert_eq!(counter.count(), 0);
+                message.value()[0]
+            }),
+            Ok(7)
+        );
+        // The message is dropped in place once `f` returns
+        assert_eq!(counter.count(), 1);
+        assert_eq!(
+            receiver.recv_map_ref(|_| panic!("called twice")),
+            Err(RecvError::AlreadyReceived)
+        );
+        mem::drop(receiver);
+        assert_eq!(counter.count(), 1);
+    });
+    maybe_loom_model(|| {
+        let (sender, mut receiver) = oneshot::channel::<u128>();
         let t = thread::spawn(move || {
             thread::sleep(Duration::from_millis(2));
-            sender.send(9u128).unwrap();
+            sender.send_blocking(19).unwrap();
         });
-        assert_eq!(receiver.recv(), Ok(9));
+        // The blocking sender returns once the message is received in place
+        assert_eq!(receiver.recv_map_ref(|message| *message + 1), Ok(20));
         t.join().unwrap();
-    })
+    });
 }
 
 #[cfg(feature = "std")]
 #[test]
-fn recv_timeout_before_send() {
+fn recv_map_ref_with_dropped_sender() {
     maybe_loom_model(|| {
-        let (sender, receiver) = oneshot::channel();
-        let t = thread::spawn(move || {
-            thread::sleep(Duration::from_millis(2));
-            sender.send(9u128).unwrap();
-        });
-        assert_eq!(receiver.recv_timeout(Duration::from_secs(1)), Ok(9));
-        t.join().unwrap();
-    })
+        let (sender, mut receiver) = oneshot::channel::<u128>();
+        mem::drop(sender);
+        assert_eq!(
+            receiver.recv_map_ref(|_| panic!("called without a message")),
+            Err(RecvError::Disconnected)
+        );
+    });
 }
 
 #[cfg(feature = "std")]
 #[test]
-fn recv_before_send_then_drop_sender() {
+fn recv_or_cancel() {
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        let (_cancel_sender, cancel) = oneshot::channel::<()>();
+        sender.send(19).unwrap();
+        assert_eq!(receiver.recv_or_cancel(&cancel), Ok(19));
+        assert_eq!(
+            receiver.recv_or_cancel(&cancel),
+            Err(RecvOrCancelError::Disconnected)
+        );
+    });
     maybe_loom_model(|| {
         let (sender, receiver) = oneshot::channel::<u128>();
+        let (cancel_sender, cancel) = oneshot::channel::<()>();
         let t = thread::spawn(move || {
-            thread::sleep(Duration::from_millis(10));
+            thread::sleep(Duration::from_millis(2));
+            cancel_sender.send(()).unwrap();
+        });
+        assert_eq!(
+            receiver.recv_or_cancel(&cancel),
+            Err(RecvOrCancelError::Cancelled)
+        );
+        t.join().unwrap();
+        // Cancelled for good, but the message can still be received
+        assert_eq!(
+            receiver.recv_or_cancel(&cancel),
+            Err(RecvOrCancelError::Cancelled)
+        );
+        sender.send(19).unwrap();
+        assert_eq!(receiver.recv_or_cancel(&cancel), Ok(19));
+        assert_eq!(cancel.try_recv(), Ok(()));
+    });
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        let (cancel_sender, cancel) = oneshot::channel::<()>();
+        let t = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(2));
             mem::drop(sender);
         });
-        assert!(receiver.recv().is_err());
+        assert_eq!(
+            receiver.recv_or_cancel(&cancel),
+            Err(RecvOrCancelError::Disconnected)
+        );
         t.join().unwrap();
-    })
+        mem::drop(cancel_sender);
+        assert_eq!(cancel.try_recv(), Err(TryRecvError::Disconnected));
+    });
 }
 
 #[cfg(feature = "std")]
 #[test]
-fn recv_timeout_before_send_then_drop_sender() {
+fn recv_interruptible() {
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        let interrupt = AtomicBool::new(true);
+        assert_eq!(
+            receiver.recv_interruptible(&interrupt),
+            Err(RecvInterruptedError::Interrupted)
+        );
+        // A message already in the channel wins over the flag
+        sender.send(19).unwrap();
+        assert_eq!(receiver.recv_interruptible(&interrupt), Ok(19));
+        assert_eq!(
+            receiver.recv_interruptible(&interrupt),
+            Err(RecvInterruptedError::Disconnected)
+        );
+    });
     maybe_loom_model(|| {
         let (sender, receiver) = oneshot::channel::<u128>();
+        let interrupt = AtomicBool::new(false);
         let t = thread::spawn(move || {
-            thread::sleep(Duration::from_millis(10));
-            mem::drop(sender);
+            thread::sleep(Duration::from_millis(2));
+            sender.send(19).unwrap();
         });
-        assert!(receiver.recv_timeout(Duration::from_secs(1)).is_err());
+        assert_eq!(receiver.recv_interruptible(&interrupt), Ok(19));
         t.join().unwrap();
-    })
-}
-
-#[test]
-fn try_recv() {
+    });
     maybe_loom_model(|| {
         let (sender, receiver) = oneshot::channel::<u128>();
-        assert_eq!(receiver.try_recv(), Err(TryRecvError::Empty));
-        mem::drop(sender)
-    })
+        let interrupt = Arc::new(AtomicBool::new(false));
+        let (ready_sender, ready_receiver) = oneshot::channel::<thread::Thread>();
+        let t = thread::spawn({
+            let interrupt = interrupt.clone();
+            move || {
+                let receiving_thread = ready_receiver.recv().unwrap();
+                thread::sleep(Duration::from_millis(2));
+                interrupt.store(true, Ordering::Release);
+                receiving_thread.unpark();
+            }
+        });
+        ready_sender.send(thread::current()).unwrap();
+        assert_eq!(
+            receiver.recv_interruptible(&interrupt),
+            Err(RecvInterruptedError::Interrupted)
+        );
+        t.join().unwrap();
+        sender.send(19).unwrap();
+        assert_eq!(receiver.try_recv(), Ok(19));
+    });
 }
 
 #[cfg(feature = "std")]
 #[test]
-fn try_recv_then_drop_receiver() {
+fn recv_until_cancelled() {
     maybe_loom_model(|| {
         let (sender, receiver) = oneshot::channel::<u128>();
-        let t1 = thread::spawn(move || {
-            let _ = sender.send(42);
+        let token = oneshot::CancelToken::new();
+        assert!(!token.is_cancelled());
+        token.cancel();
+        assert!(token.clone().is_cancelled());
+        assert_eq!(
+            receiver.recv_until_cancelled(&token),
+            Err(RecvOrCancelError::Cancelled)
+        );
+        // A message already in the channel wins over the cancelled token
+        sender.send(19).unwrap();
+        assert_eq!(receiver.recv_until_cancelled(&token), Ok(19));
+        assert_eq!(
+            receiver.recv_until_cancelled(&token),
+            Err(RecvOrCancelError::Disconnected)
+        );
+    });
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        let token = oneshot::CancelToken::new();
+        let t = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(2));
+            sender.send(19).unwrap();
         });
-        let t2 = thread::spawn(move || {
-            assert!(matches!(
-                receiver.try_recv(),
-                Ok(42) | Err(TryRecvError::Empty)
-            ));
-            mem::drop(receiver);
+        assert_eq!(receiver.recv_until_cancelled(&token), Ok(19));
+        t.join().unwrap();
+        assert!(!token.is_cancelled());
+    });
+    // Cancelling unparks the receiving thread
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        let token = oneshot::CancelToken::new();
+        let t = thread::spawn({
+            let token = token.clone();
+            move || {
+                thread::sleep(Duration::from_millis(2));
+                token.cancel();
+            }
         });
-        t1.join().unwrap();
-        t2.join().unwrap();
-    })
+        assert_eq!(
+            receiver.recv_until_cancelled(&token),
+            Err(RecvOrCancelError::Cancelled)
+        );
+        t.join().unwrap();
+        sender.send(19).unwrap();
+        assert_eq!(receiver.try_recv(), Ok(19));
+    });
+}
+
+#[cfg(all(feature = "std", not(loom)))]
+#[test]
+fn cancel_token_wakes_all_receivers() {
+    let token = oneshot::CancelToken::new();
+    let mut senders = Vec::new();
+    let mut threads = Vec::new();
+    for _ in 0..4 {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        senders.push(sender);
+        let token = token.clone();
+        threads.push(thread::spawn(move || receiver.recv_until_cancelled(&token)));
+    }
+    thread::sleep(Duration::from_millis(10));
+    token.cancel();
+    for t in threads {
+        assert_eq!(t.join().unwrap(), Err(RecvOrCancelError::Cancelled));
+    }
 }
 
 #[cfg(feature = "std")]
 #[test]
-fn recv_deadline_and_timeout_no_time() {
+fn select_any() {
     maybe_loom_model(|| {
-        let (_sender, receiver) = oneshot::channel::<u128>();
-
-        let start = Instant::now();
+        let (sender1, receiver1) = oneshot::channel::<u128>();
+        let (sender2, receiver2) = oneshot::channel::<u128>();
+        sender2.send(19).unwrap();
+        sender1.send(20).unwrap();
+        let receivers = [receiver1, receiver2];
+        // The lowest index wins if several are complete
+        assert_eq!(oneshot::select_any(&receivers), (0, Ok(20)));
         assert_eq!(
-            receiver.recv_deadline(start),
-            Err(RecvTimeoutError::Timeout)
+            oneshot::select_any(&receivers),
+            (0, Err(RecvError::AlreadyReceived))
         );
-        assert!(start.elapsed() < Duration::from_millis(200));
-
-        let start = Instant::now();
+        assert_eq!(receivers[1].try_recv(), Ok(19));
+    });
+    maybe_loom_model(|| {
+        let (sender1, receiver1) = oneshot::channel::<u128>();
+        let (sender2, receiver2) = oneshot::channel::<u128>();
+        let t = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(2));
+            sender2.send(19).unwrap();
+        });
+        let mut receivers = [receiver1, receiver2];
+        assert_eq!(oneshot::select_any(&receivers), (1, Ok(19)));
+        t.join().unwrap();
+        // The losing receiver can still be used
+        sender1.send(20).unwrap();
+        assert_eq!(receivers[0].recv_mut(), Ok(20));
+    });
+    maybe_loom_model(|| {
+        let (sender1, receiver1) = oneshot::channel::<u128>();
+        let (_sender2, receiver2) = oneshot::channel::<u128>();
+        let t = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(2));
+            mem::drop(sender1);
+        });
+        let receivers = [receiver1, receiver2];
         assert_eq!(
-            receiver.recv_timeout(Duration::from_millis(0)),
-            Err(RecvTimeoutError::Timeout)
+            oneshot::select_any(&receivers),
+            (0, Err(RecvError::Disconnected))
         );
-        assert!(start.elapsed() < Duration::from_millis(200));
-    })
+        t.join().unwrap();
+        assert_eq!(receivers[1].try_recv(), Err(TryRecvError::Empty));
+    });
 }
 
-// This test doesn't give meaningful results when run with oneshot_test_delay and loom
-#[cfg(all(feature = "std", not(all(oneshot_test_delay, loom))))]
+#[cfg(feature = "std")]
 #[test]
-fn recv_deadline_time_should_elapse() {
-    maybe_loom_model(|| {
-        let (_sender, receiver) = oneshot::channel::<u128>();
-
-        let start = Instant::now();
-        #[cfg(not(loom))]
-        let timeout = Duration::from_millis(100);
-        #[cfg(loom)]
-        let timeout = Duration::from_millis(1);
-        assert_eq!(
-            receiver.recv_deadline(start + timeout),
-            Err(RecvTimeoutError::Timeout)
-        );
-        assert!(start.elapsed() > timeout);
-        assert!(start.elapsed() < timeout * 3);
-    })
+#[should_panic]
+fn select_any_without_receivers() {
+    let _ = oneshot::select_any::<u128>(&[]);
 }
 
-#[cfg(all(feature = "std", not(all(oneshot_test_delay, loom))))]
+#[cfg(feature = "std")]
 #[test]
-fn recv_timeout_time_should_elapse() {
+fn select_blocking() {
+    // The first receiver wins if both messages are there
     maybe_loom_model(|| {
-        let (_sender, receiver) = oneshot::channel::<u128>();
+        let (sender1, receiver1) = oneshot::channel::<u128>();
+        let (sender2, receiver2) = oneshot::channel::<&str>();
+        sender2.send("two").unwrap();
+        sender1.send(1).unwrap();
+        match oneshot::select_blocking(receiver1, receiver2) {
+            oneshot::Select::Left(1, receiver2) => assert_eq!(receiver2.try_recv(), Ok("two")),
+            _ => panic!("unexpected select result"),
+        }
+    });
+    // The second receiver wins, and the first one gets its message later
+    maybe_loom_model(|| {
+        let (sender1, receiver1) = oneshot::channel::<u128>();
+        let (sender2, receiver2) = oneshot::channel::<u128>();
+        let t = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(2));
+            sender2.send(2).unwrap();
+        });
+        let receiver1 = match oneshot::select_blocking(receiver1, receiver2) {
+            oneshot::Select::Right(2, receiver1) => receiver1,
+            _ => panic!("unexpected select result"),
+        };
+        t.join().unwrap();
+        assert_eq!(receiver1.try_recv(), Err(TryRecvError::Empty));
+        sender1.send(1).unwrap();
+        assert_eq!(receiver1.recv(), Ok(1));
+    });
+    // A dropped sender does not end the select while the other one is alive
+    maybe_loom_model(|| {
+        let (sender1, receiver1) = oneshot::channel::<u128>();
+        let (sender2, receiver2) = oneshot::channel::<u128>();
+        mem::drop(sender1);
+        let t = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(2));
+            sender2.send(2).unwrap();
+        });
+        match oneshot::select_blocking(receiver1, receiver2) {
+            oneshot::Select::Right(2, receiver1) => {
+                assert_eq!(receiver1.try_recv(), Err(TryRecvError::Disconnected))
+            }
+            _ => panic!("unexpected select result"),
+        }
+        t.join().unwrap();
+    });
+    // Both senders are dropped
+    maybe_loom_model(|| {
+        let (sender1, receiver1) = oneshot::channel::<u128>();
+        let (sender2, receiver2) = oneshot::channel::<u128>();
+        mem::drop(sender2);
+        let t = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(2));
+            mem::drop(sender1);
+        });
+        assert!(matches!(
+            oneshot::select_blocking(receiver1, receiver2),
+            oneshot::Select::Disconnected
+        ));
+        t.join().unwrap();
+    });
+}
 
-        let start = Instant::now();
-        #[cfg(not(loom))]
-        let timeout = Duration::from_millis(100);
-        #[cfg(loom)]
-        let timeout = Duration::from_millis(1);
+#[cfg(feature = "std")]
+#[test]
+fn race_blocking() {
+    // The lowest index wins if several messages are there, and the others keep theirs
+    maybe_loom_model(|| {
+        let (sender1, receiver1) = oneshot::channel::<u128>();
+        let (sender2, receiver2) = oneshot::channel::<u128>();
+        let (sender3, receiver3) = oneshot::channel::<u128>();
+        sender3.send(3).unwrap();
+        sender2.send(2).unwrap();
+        let (index, message, others) =
+            oneshot::race_blocking(vec![receiver1, receiver2, receiver3]).unwrap();
+        assert_eq!((index, message), (1, 2));
+        assert_eq!(others[0].try_recv(), Err(TryRecvError::Empty));
+        assert_eq!(others[1].try_recv(), Ok(3));
+        // Dropping the losers closes their channels
+        mem::drop(others);
+        assert!(sender1.is_closed());
+    });
+    // Disconnected receivers are skipped while another one can still get its message
+    maybe_loom_model(|| {
+        let (sender1, receiver1) = oneshot::channel::<u128>();
+        let (sender2, receiver2) = oneshot::channel::<u128>();
+        mem::drop(sender1);
+        let t = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(2));
+            sender2.send(2).unwrap();
+        });
+        let (index, message, others) = oneshot::race_blocking(vec![receiver1, receiver2]).unwrap();
+        assert_eq!((index, message), (1, 2));
+        assert_eq!(others[0].try_recv(), Err(TryRecvError::Disconnected));
+        t.join().unwrap();
+    });
+    maybe_loom_model(|| {
+        let (sender1, receiver1) = oneshot::channel::<u128>();
+        let (sender2, receiver2) = oneshot::channel::<u128>();
+        mem::drop(sender2);
+        let t = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(2));
+            mem::drop(sender1);
+        });
+        assert_eq!(
+            oneshot::race_blocking(vec![receiver1, receiver2]).err(),
+            Some(RecvError::Disconnected)
+        );
+        t.join().unwrap();
+        assert_eq!(
+            oneshot::race_blocking::<u128>(Vec::new()).err(),
+            Some(RecvError::Disconnected)
+        );
+    });
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn wait_all() {
+    maybe_loom_model(|| {
+        assert!(oneshot::wait_all::<u128>(Vec::new()).is_empty());
 
+        let (sender1, receiver1) = oneshot::channel::<u128>();
+        let (sender2, receiver2) = oneshot::channel::<u128>();
+        let (sender3, receiver3) = oneshot::channel::<u128>();
+        sender1.send(1).unwrap();
+        mem::drop(sender2);
+        let t = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(2));
+            sender3.send(3).unwrap();
+        });
         assert_eq!(
-            receiver.recv_timeout(timeout),
-            Err(RecvTimeoutError::Timeout)
+            oneshot::wait_all(vec![receiver1, receiver2, receiver3]),
+            [Ok(1), Err(RecvError::Disconnected), Ok(3)]
         );
-        assert!(start.elapsed() > timeout);
-        assert!(start.elapsed() < timeout * 3);
+        t.join().unwrap();
+    });
+}
+
+#[cfg(all(feature = "std", not(loom)))]
+#[test]
+fn wait_all_staggered() {
+    let (senders, receivers): (Vec<_>, Vec<_>) =
+        (0..400u32).map(|_| oneshot::channel::<u32>()).unzip();
+    // Spread the senders over a few threads, each completing its channels at different times
+    let mut threads = Vec::new();
+    let mut senders = senders.into_iter().enumerate().collect::<Vec<_>>();
+    for _ in 0..4 {
+        let chunk = senders.split_off(senders.len() - 100);
+        threads.push(thread::spawn(move || {
+            for (i, sender) in chunk.into_iter().rev() {
+                if i % 5 == 0 {
+                    thread::sleep(Duration::from_millis(1));
+                }
+                if i % 7 == 0 {
+                    mem::drop(sender);
+                } else {
+                    sender.send(i as u32).unwrap();
+                }
+            }
+        }));
+    }
+    let results = oneshot::wait_all(receivers);
+    assert_eq!(results.len(), 400);
+    for (i, result) in results.into_iter().enumerate() {
+        if i % 7 == 0 {
+            assert_eq!(result, Err(RecvError::Disconnected));
+        } else {
+            assert_eq!(result, Ok(i as u32));
+        }
+    }
+    for t in threads {
+        t.join().unwrap();
+    }
+}
+
+#[cfg(all(feature = "std", not(loom)))]
+#[test]
+fn select_blocking_timeout() {
+    let (sender1, receiver1) = oneshot::channel::<u128>();
+    let (sender2, receiver2) = oneshot::channel::<u128>();
+    let start = Instant::now();
+    let (receiver1, receiver2) =
+        match oneshot::select_blocking_timeout(receiver1, receiver2, Duration::from_millis(10)) {
+            Err(receivers) => receivers,
+            Ok(_) => panic!("select should time out"),
+        };
+    assert!(start.elapsed() >= Duration::from_millis(10));
+
+    // The receivers still work after timing out
+    let t = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(2));
+        sender2.send(2).unwrap();
+    });
+    match oneshot::select_blocking_timeout(receiver1, receiver2, Duration::from_secs(10)) {
+        Ok(oneshot::Select::Right(2, receiver1)) => {
+            sender1.send(1).unwrap();
+            assert_eq!(receiver1.recv(), Ok(1));
+        }
+        _ => panic!("unexpected select result"),
+    }
+    t.join().unwrap();
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn recv_error_kind() {
+    // The sender was dropped without sending
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        mem::drop(sender);
+        assert_eq!(receiver.recv(), Err(RecvError::Disconnected));
+    });
+    // The message was taken by an earlier receive
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        sender.send(19).unwrap();
+        assert_eq!(receiver.try_recv(), Ok(19));
+        assert_eq!(receiver.recv(), Err(RecvError::AlreadyReceived));
+    });
+    // The receiver was closed
+    maybe_loom_model(|| {
+        let (_sender, mut receiver) = oneshot::channel::<u128>();
+        receiver.close();
+        assert_eq!(receiver.recv_mut(), Err(RecvError::Disconnected));
+    });
+}
+
+#[test]
+fn recv_error_display() {
+    assert_eq!(
+        oneshot::RecvError::Disconnected.to_string(),
+        "receiving on a closed channel"
+    );
+    assert_eq!(
+        oneshot::RecvError::AlreadyReceived.to_string(),
+        "receiving on a channel whose message was already received"
+    );
+}
+
+#[test]
+fn send_before_try_recv_mut() {
+    maybe_loom_model(|| {
+        let (sender, mut receiver) = oneshot::channel();
+        assert_eq!(receiver.try_recv_mut(), Err(TryRecvError::Empty));
+        assert!(sender.send(19i128).is_ok());
+
+        assert_eq!(receiver.try_recv_mut(), Ok(19i128));
+        assert_eq!(receiver.try_recv_mut(), Err(TryRecvError::Disconnected));
+        assert_eq!(receiver.try_recv(), Err(TryRecvError::Disconnected));
     })
 }
 
-#[cfg(not(loom))]
 #[test]
-fn non_send_type_can_be_used_on_same_thread() {
-    use std::ptr;
+fn try_recv_mut_with_dropped_sender() {
+    maybe_loom_model(|| {
+        let (sender, mut receiver) = oneshot::channel::<u128>();
+        mem::drop(sender);
+        assert_eq!(receiver.try_recv_mut(), Err(TryRecvError::Disconnected));
+    })
+}
 
-    #[derive(Debug, Eq, PartialEq)]
-    struct NotSend(*mut ());
+#[cfg(feature = "std")]
+#[test]
+fn send_before_recv_mut() {
+    maybe_loom_model(|| {
+        let (sender, mut receiver) = oneshot::channel();
+        assert!(sender.send(19i128).is_ok());
 
-    let (sender, receiver) = oneshot::channel();
-    sender.send(NotSend(ptr::null_mut())).unwrap();
-    let reply = receiver.try_recv().unwrap();
-    assert_eq!(reply, NotSend(ptr::null_mut()));
+        assert_eq!(receiver.recv_mut(), Ok(19i128));
+        assert_eq!(receiver.recv_mut(), Err(RecvError::AlreadyReceived));
+        assert_eq!(receiver.try_recv(), Err(TryRecvError::Disconnected));
+        assert!(receiver.recv_timeout(Duration::from_secs(1)).is_err());
+    })
 }
 
+#[cfg(feature = "std")]
 #[test]
-fn message_in_channel_dropped_on_receiver_drop() {
+fn send_before_recv_timeout() {
     maybe_loom_model(|| {
         let (sender, receiver) = oneshot::channel();
-        let (message, counter) = DropCounter::new(());
-        assert_eq!(counter.count(), 0);
-        sender.send(message).unwrap();
-        assert_eq!(counter.count(), 0);
+        assert!(sender.send(19i128).is_ok());
+
+        let start = Instant::now();
+        let timeout = Duration::from_secs(1);
+        assert_eq!(receiver.recv_timeout(timeout), Ok(19i128));
+        assert!(start.elapsed() < Duration::from_millis(100));
+
+        assert!(receiver.recv_timeout(timeout).is_err());
+        assert!(receiver.try_recv().is_err());
+        assert!(receiver.recv().is_err());
+    })
+}
+
+#[test]
+fn send_then_drop_receiver() {
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel();
+        assert!(sender.send(19i128).is_ok());
         mem::drop(receiver);
-        assert_eq!(counter.count(), 1);
     })
 }
 
 #[test]
-fn send_error_drops_message_correctly() {
+fn send_with_dropped_receiver() {
     maybe_loom_model(|| {
-        let (sender, _) = oneshot::channel();
-        let (message, counter) = DropCounter::new(());
+        let (sender, receiver) = oneshot::channel();
+        mem::drop(receiver);
+        let send_error = sender.send(5u128).unwrap_err();
+        assert_eq!(*send_error.as_inner(), 5);
+        assert_eq!(send_error.into_inner(), 5);
+    })
+}
 
-        let send_error = sender.send(message).unwrap_err();
-        assert_eq!(counter.count(), 0);
-        mem::drop(send_error);
-        assert_eq!(counter.count(), 1);
+#[test]
+fn try_send() {
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel();
+        assert!(sender.try_send(19u128).is_ok());
+        assert_eq!(receiver.try_recv(), Ok(19));
+
+        let (sender, receiver) = oneshot::channel();
+        receiver.close();
+        let error = sender.try_send(5u128).unwrap_err();
+        assert_eq!(*error.as_inner(), 5);
+        assert_eq!(error.to_string(), "sending on a closed channel");
+        assert!(matches!(error, oneshot::TrySendError::Disconnected(_)));
+        assert_eq!(error.into_inner(), 5);
+    })
+}
+
+#[test]
+fn send_ref() {
+    struct Holder {
+        sender: oneshot::Sender<u128>,
+    }
+
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel();
+        let mut holder = Holder { sender };
+        assert!(holder.sender.send_ref(19).is_ok());
+        assert!(holder.sender.is_closed());
+        assert!(!holder.sender.is_receiver_waiting());
+        assert!(!holder.sender.same_channel(&receiver));
+        assert_eq!(receiver.try_recv(), Ok(19));
+        // Dropping the spent sender does not touch the channel
+        mem::drop(holder);
+        assert_eq!(receiver.try_recv(), Err(TryRecvError::Disconnected));
+    })
+}
+
+#[test]
+fn send_ref_with_dropped_receiver() {
+    maybe_loom_model(|| {
+        let (mut sender, receiver) = oneshot::channel();
+        mem::drop(receiver);
+        let send_error = sender.send_ref(5u128).unwrap_err();
+        assert_eq!(send_error.into_inner(), 5);
+        let send_error = sender
+            .send_with(|| panic!("message built for a spent sender"))
+            .unwrap_err();
+        assert!(matches!(send_error, SendWithError::Closed));
+    })
+}
+
+#[test]
+#[should_panic(expected = "Invalid to send on oneshot::Sender after Sender::send_ref")]
+fn send_ref_twice_panics() {
+    maybe_loom_model(|| {
+        let (mut sender, _receiver) = oneshot::channel();
+        sender.send_ref(5u128).unwrap();
+        let _ = sender.send_ref(6);
+    })
+}
+
+#[test]
+fn send_with_closure() {
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel();
+        assert!(sender.send_with(|| 19u128).is_ok());
+        assert_eq!(receiver.try_recv(), Ok(19));
+    })
+}
+
+#[test]
+fn send_with_closure_and_dropped_receiver() {
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        mem::drop(receiver);
+        let send_error = sender
+            .send_with(|| panic!("message built for a dropped receiver"))
+            .unwrap_err();
+        assert!(matches!(send_error, SendWithError::Closed));
+        assert_eq!(send_error.into_inner(), None);
+    })
+}
+
+#[test]
+fn send_with_closure_and_closed_receiver() {
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        receiver.close();
+        let send_error = sender
+            .send_with(|| panic!("message built for a closed receiver"))
+            .unwrap_err();
+        assert!(matches!(send_error, SendWithError::Closed));
+        assert_eq!(receiver.try_recv(), Err(TryRecvError::Disconnected));
+    })
+}
+
+#[test]
+fn send_with_closure_dropping_receiver() {
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel();
+        let send_error = sender
+            .send_with(|| {
+                mem::drop(receiver);
+                19u128
+            })
+            .unwrap_err();
+        assert!(matches!(send_error, SendWithError::Send(_)));
+        assert_eq!(send_error.into_inner(), Some(19));
+    })
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn send_blocking() {
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        let t = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(2));
+            receiver.recv()
+        });
+        assert!(sender.send_blocking(19).is_ok());
+        assert_eq!(t.join().unwrap(), Ok(19));
+    });
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        let t = thread::spawn(move || loop {
+            // Receiving by reference also acknowledges the message
+            match receiver.try_recv() {
+                Ok(message) => break (message, receiver),
+                Err(_) => thread::sleep(Duration::from_millis(1)),
+            }
+        });
+        assert!(sender.send_blocking(19).is_ok());
+        let (message, receiver) = t.join().unwrap();
+        assert_eq!(message, 19);
+        assert_eq!(receiver.try_recv(), Err(TryRecvError::Disconnected));
     });
 }
 
+#[cfg(feature = "std")]
 #[test]
-fn send_error_drops_message_correctly_on_into_inner() {
+fn send_blocking_with_dropped_receiver() {
     maybe_loom_model(|| {
-        let (sender, _) = oneshot::channel();
-        let (message, counter) = DropCounter::new(());
+        let (sender, receiver) = oneshot::channel::<u128>();
+        mem::drop(receiver);
+        assert_eq!(sender.send_blocking(19).unwrap_err().into_inner(), 19);
+    });
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        receiver.close();
+        assert_eq!(sender.send_blocking(19).unwrap_err().into_inner(), 19);
+        assert_eq!(receiver.try_recv(), Err(TryRecvError::Disconnected));
+    });
+}
 
-        let send_error = sender.send(message).unwrap_err();
-        assert_eq!(counter.count(), 0);
-        let message = send_error.into_inner();
+#[cfg(feature = "std")]
+#[test]
+fn send_blocking_then_drop_receiver() {
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel();
+        let (message, counter) = DropCounter::new(19u128);
+        let t = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(2));
+            mem::drop(receiver);
+        });
+        // The message is handed back instead of being dropped with the receiver
+        let send_error = sender.send_blocking(message).unwrap_err();
+        t.join().unwrap();
         assert_eq!(counter.count(), 0);
-        mem::drop(message);
+        assert_eq!(*send_error.as_inner().value(), 19);
+        mem::drop(send_error);
         assert_eq!(counter.count(), 1);
     });
 }
+
+#[test]
+fn try_recv_with_dropped_sender() {
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        mem::drop(sender);
+        receiver.try_recv().unwrap_err();
+    })
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn recv_with_dropped_sender() {
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        mem::drop(sender);
+        receiver.recv().unwrap_err();
+    })
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn recv_before_send() {
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel();
+        let t = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(2));
+            sender.send(9u128).unwrap();
+        });
+        assert_eq!(receiver.recv(), Ok(9));
+        t.join().unwrap();
+    })
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn blocking_recv_before_send() {
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel();
+        let t = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(2));
+            sender.send(9u128).unwrap();
+        });
+        assert_eq!(receiver.blocking_recv(), Ok(9));
+        t.join().unwrap();
+    });
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        mem::drop(sender);
+        assert_eq!(
+            receiver.blocking_recv(),
+            Err(oneshot::RecvError::Disconnected)
+        );
+    })
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn recv_timeout_before_send() {
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel();
+        let t = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(2));
+            sender.send(9u128).unwrap();
+        });
+        assert_eq!(receiver.recv_timeout(Duration::from_secs(1)), Ok(9));
+        t.join().unwrap();
+    })
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn recv_before_send_then_drop_sender() {
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        let t = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(10));
+            mem::drop(sender);
+        });
+        assert!(receiver.recv().is_err());
+        t.join().unwrap();
+    })
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn recv_timeout_before_send_then_drop_sender() {
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        let t = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(10));
+            mem::drop(sender);
+        });
+        assert!(receiver.recv_timeout(Duration::from_secs(1)).is_err());
+        t.join().unwrap();
+    })
+}
+
+#[test]
+fn try_recv() {
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        assert_eq!(receiver.try_recv(), Err(TryRecvError::Empty));
+        mem::drop(sender)
+    })
+}
+
+#[test]
+fn into_try_recv() {
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel();
+        let (message, counter) = DropCounter::new(19u128);
+
+        let receiver = match receiver.into_try_recv() {
+            Err(IntoTryRecvError::Empty(receiver)) => receiver,
+            _ => panic!("Channel should be empty"),
+        };
+        let receiver = match receiver.into_try_recv() {
+            Err(error) => {
+                assert_eq!(error.kind(), TryRecvError::Empty);
+                match error {
+                    IntoTryRecvError::Empty(receiver) => receiver,
+                    IntoTryRecvError::Disconnected => unreachable!(),
+                }
+            }
+            Ok(_) => panic!("Channel should be empty"),
+        };
+
+        sender.send(message).unwrap();
+        let message = receiver.into_try_recv().unwrap();
+        assert_eq!(*message.value(), 19);
+        assert_eq!(counter.count(), 0);
+        mem::drop(message);
+        assert_eq!(counter.count(), 1);
+    });
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        mem::drop(sender);
+        assert!(matches!(
+            receiver.into_try_recv(),
+            Err(IntoTryRecvError::Disconnected)
+        ));
+    });
+}
+
+#[test]
+fn map_try_recv() {
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        let mut calls = 0;
+        let mut receiver = receiver.map(|n| {
+            calls += 1;
+            n * 2
+        });
+        assert_eq!(receiver.try_recv(), Err(TryRecvError::Empty));
+        sender.send(19).unwrap();
+        assert_eq!(receiver.try_recv(), Ok(38));
+        assert_eq!(receiver.try_recv(), Err(TryRecvError::Disconnected));
+        mem::drop(receiver);
+        assert_eq!(calls, 1);
+    });
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        let mut receiver = receiver.map(|_| -> u128 { panic!("Should not be called") });
+        mem::drop(sender);
+        assert_eq!(receiver.try_recv(), Err(TryRecvError::Disconnected));
+    });
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn try_recv_then_drop_receiver() {
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        let t1 = thread::spawn(move || {
+            let _ = sender.send(42);
+        });
+        let t2 = thread::spawn(move || {
+            assert!(matches!(
+                receiver.try_recv(),
+                Ok(42) | Err(TryRecvError::Empty)
+            ));
+            mem::drop(receiver);
+        });
+        t1.join().unwrap();
+        t2.join().unwrap();
+    })
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn recv_deadline_and_timeout_no_time() {
+    maybe_loom_model(|| {
+        let (_sender, receiver) = oneshot::channel::<u128>();
+
+        let start = Instant::now();
+        assert_eq!(
+            receiver.recv_deadline(start),
+            Err(RecvTimeoutError::Timeout)
+        );
+        assert!(start.elapsed() < Duration::from_millis(200));
+
+        let start = Instant::now();
+        assert_eq!(
+            receiver.recv_timeout(Duration::from_millis(0)),
+            Err(RecvTimeoutError::Timeout)
+        );
+        assert!(start.elapsed() < Duration::from_millis(200));
+    })
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn recv_deadline_time_should_elapse() {
+    maybe_loom_model(|| {
+        let (_sender, receiver) = oneshot::channel::<u128>();
+
+        let start = Instant::now();
+        #[cfg(not(loom))]
+        let timeout = Duration::from_millis(100);
+        #[cfg(loom)]
+        let timeout = Duration::from_millis(1);
+        assert_eq!(
+            receiver.recv_deadline(start + timeout),
+            Err(RecvTimeoutError::Timeout)
+        );
+        assert!(start.elapsed() > timeout);
+        assert!(start.elapsed() < timeout * 3);
+    })
+}
+
+#[cfg(all(feature = "std", not(loom)))]
+#[test]
+fn deadline_receiver() {
+    let (_sender, receiver) = oneshot::channel::<u128>();
+    let start = Instant::now();
+    let timeout = Duration::from_millis(100);
+    let receiver = receiver.with_deadline(start + timeout);
+    assert_eq!(receiver.deadline(), start + timeout);
+    assert!(receiver.remaining() <= timeout);
+    assert_eq!(receiver.try_recv(), Err(TryRecvError::Empty));
+    assert_eq!(receiver.recv(), Err(RecvTimeoutError::Timeout));
+    assert!(start.elapsed() > timeout);
+    assert_eq!(receiver.remaining(), Duration::ZERO);
+
+    // The inner receiver no longer has a deadline
+    let (sender, receiver) = oneshot::channel::<u128>();
+    let receiver = receiver.with_deadline(Instant::now()).into_inner();
+    let t = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(10));
+        sender.send(19).unwrap();
+    });
+    assert_eq!(receiver.recv(), Ok(19));
+    t.join().unwrap();
+}
+
+#[cfg(all(feature = "std", not(loom)))]
+#[test]
+fn deadline_receiver_before_deadline() {
+    let (sender, receiver) = oneshot::channel::<u128>();
+    let receiver = receiver.with_deadline(Instant::now() + Duration::from_secs(10));
+    let t = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(10));
+        sender.send(19).unwrap();
+    });
+    assert_eq!(receiver.recv(), Ok(19));
+    t.join().unwrap();
+
+    let (sender, receiver) = oneshot::channel::<u128>();
+    mem::drop(sender);
+    let receiver = receiver.with_deadline(Instant::now() + Duration::from_secs(10));
+    assert_eq!(receiver.recv(), Err(RecvTimeoutError::Disconnected));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn recv_timeout_time_should_elapse() {
+    maybe_loom_model(|| {
+        let (_sender, receiver) = oneshot::channel::<u128>();
+
+        let start = Instant::now();
+        #[cfg(not(loom))]
+        let timeout = Duration::from_millis(100);
+        #[cfg(loom)]
+        let timeout = Duration::from_millis(1);
+
+        assert_eq!(
+            receiver.recv_timeout(timeout),
+            Err(RecvTimeoutError::Timeout)
+        );
+        assert!(start.elapsed() > timeout);
+        assert!(start.elapsed() < timeout * 3);
+    })
+}
+
+#[cfg(not(loom))]
+#[test]
+fn non_send_type_can_be_used_on_same_thread() {
+    use std::ptr;
+
+    #[derive(Debug, Eq, PartialEq)]
+    struct NotSend(*mut ());
+
+    let (sender, receiver) = oneshot::channel();
+    sender.send(NotSend(ptr::null_mut())).unwrap();
+    let reply = receiver.try_recv().unwrap();
+    assert_eq!(reply, NotSend(ptr::null_mut()));
+}
+
+#[test]
+fn message_in_channel_dropped_on_receiver_drop() {
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel();
+        let (message, counter) = DropCounter::new(());
+        assert_eq!(counter.count(), 0);
+        sender.send(message).unwrap();
+        assert_eq!(counter.count(), 0);
+        mem::drop(receiver);
+        assert_eq!(counter.count(), 1);
+    })
+}
+
+#[test]
+fn send_error_drops_message_correctly() {
+    maybe_loom_model(|| {
+        let (sender, _) = oneshot::channel();
+        let (message, counter) = DropCounter::new(());
+
+        let send_error = sender.send(message).unwrap_err();
+        assert_eq!(counter.count(), 0);
+        mem::drop(send_error);
+        assert_eq!(counter.count(), 1);
+    });
+}
+
+#[test]
+fn send_error_drops_message_correctly_on_into_inner() {
+    maybe_loom_model(|| {
+        let (sender, _) = oneshot::channel();
+        let (message, counter) = DropCounter::new(());
+
+        let send_error = sender.send(message).unwrap_err();
+        assert_eq!(counter.count(), 0);
+        let message = send_error.into_inner();
+        assert_eq!(counter.count(), 0);
+        mem::drop(message);
+        assert_eq!(counter.count(), 1);
+    });
+}
+
+#[test]
+fn receiver_is_closed() {
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        assert!(!receiver.is_closed());
+        mem::drop(sender);
+        assert!(receiver.is_closed());
+        assert_eq!(receiver.try_recv(), Err(TryRecvError::Disconnected));
+        assert!(receiver.is_closed());
+    });
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        sender.send(19).unwrap();
+        assert!(!receiver.is_closed());
+        assert!(!receiver.is_closed());
+        assert_eq!(receiver.try_recv(), Ok(19));
+        assert!(receiver.is_closed());
+    })
+}
+
+#[test]
+fn receiver_has_message() {
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        assert!(!receiver.has_message());
+        sender.send(19).unwrap();
+        assert!(receiver.has_message());
+        assert!(receiver.has_message());
+        assert_eq!(receiver.try_recv(), Ok(19));
+        assert!(!receiver.has_message());
+    });
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        mem::drop(sender);
+        assert!(!receiver.has_message());
+    })
+}
+
+#[test]
+fn receiver_is_empty() {
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        assert!(receiver.is_empty());
+        sender.send(19).unwrap();
+        assert!(!receiver.is_empty());
+        assert_eq!(receiver.try_recv(), Ok(19));
+        assert!(!receiver.is_empty());
+    });
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        mem::drop(sender);
+        assert!(!receiver.is_empty());
+        assert!(receiver.is_closed());
+    });
+    maybe_loom_model(|| {
+        let (_sender, receiver) = oneshot::channel::<u128>();
+        receiver.close();
+        assert!(!receiver.is_empty());
+    })
+}
+
+#[test]
+fn sender_is_closed() {
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        assert!(!sender.is_closed());
+        mem::drop(receiver);
+        assert!(sender.is_closed());
+        assert!(sender.send(19).is_err());
+    })
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn on_receive_before_send() {
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel();
+        let (result_sender, result_receiver) = oneshot::channel();
+        let (message, counter) = DropCounter::new(19u128);
+        receiver.on_receive(move |result| result_sender.send(result).unwrap());
+        let t = thread::spawn(move || sender.send(message).unwrap());
+        let message = result_receiver.recv().unwrap().unwrap();
+        t.join().unwrap();
+        assert_eq!(*message.value(), 19);
+        mem::drop(message);
+        assert_eq!(counter.count(), 1);
+    })
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn on_receive_after_send() {
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        let (result_sender, result_receiver) = oneshot::channel();
+        sender.send(19).unwrap();
+        // The callback runs before `on_receive` returns
+        receiver.on_receive(move |result| result_sender.send(result).unwrap());
+        assert_eq!(result_receiver.try_recv(), Ok(Ok(19)));
+    })
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn on_receive_with_dropped_sender() {
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        let (result_sender, result_receiver) = oneshot::channel();
+        receiver.on_receive(move |result| result_sender.send(result).unwrap());
+        let t = thread::spawn(move || mem::drop(sender));
+        assert_eq!(result_receiver.recv(), Ok(Err(RecvError::Disconnected)));
+        t.join().unwrap();
+    });
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        let (result_sender, result_receiver) = oneshot::channel();
+        mem::drop(sender);
+        receiver.on_receive(move |result| result_sender.send(result).unwrap());
+        assert_eq!(result_receiver.try_recv(), Ok(Err(RecvError::Disconnected)));
+    });
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn on_receive_after_receiving() {
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        let (result_sender, result_receiver) = oneshot::channel();
+        sender.send(19).unwrap();
+        assert_eq!(receiver.try_recv(), Ok(19));
+        receiver.on_receive(move |result| result_sender.send(result).unwrap());
+        assert_eq!(
+            result_receiver.try_recv(),
+            Ok(Err(RecvError::AlreadyReceived))
+        );
+    })
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn on_receive_drops_callback_once() {
+    // Called by the sender
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        let (captured, counter) = DropCounter::new(());
+        receiver.on_receive(move |_| mem::drop(captured));
+        assert_eq!(counter.count(), 0);
+        sender.send(19).unwrap();
+        assert_eq!(counter.count(), 1);
+    });
+    // Called when the sender is dropped
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        let (captured, counter) = DropCounter::new(());
+        receiver.on_receive(move |_| mem::drop(captured));
+        mem::drop(sender);
+        assert_eq!(counter.count(), 1);
+    });
+    // Called right away
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        let (captured, counter) = DropCounter::new(());
+        sender.send(19).unwrap();
+        receiver.on_receive(move |_| mem::drop(captured));
+        assert_eq!(counter.count(), 1);
+    });
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn receiver_into_iter() {
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        let t = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(2));
+            sender.send(19).unwrap();
+        });
+        let mut received = Vec::new();
+        for message in receiver {
+            received.push(message);
+        }
+        assert_eq!(received, [19]);
+        t.join().unwrap();
+    });
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel();
+        sender.send(19u128).unwrap();
+        let doubled: Vec<u128> = receiver.into_iter().map(|n| n * 2).collect();
+        assert_eq!(doubled, [38]);
+    });
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn receiver_into_iter_with_dropped_sender() {
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        let t = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(2));
+            mem::drop(sender);
+        });
+        assert_eq!(receiver.into_iter().count(), 0);
+        t.join().unwrap();
+    })
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn receiver_into_results() {
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        let t = thread::spawn(move || sender.send(19).unwrap());
+        let mut results = Vec::new();
+        for result in receiver.into_results() {
+            results.push(result);
+        }
+        assert_eq!(results, [Ok(19)]);
+        t.join().unwrap();
+    });
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        let t = thread::spawn(move || mem::drop(sender));
+        let mut results = receiver.into_results();
+        assert_eq!(results.next(), Some(Err(RecvError::Disconnected)));
+        assert_eq!(results.next(), None);
+        assert_eq!(results.next(), None);
+        t.join().unwrap();
+    });
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        sender.send(19).unwrap();
+        let collected: Result<Vec<u128>, RecvError> = receiver.into_results().collect();
+        assert_eq!(collected, Ok(vec![19]));
+    });
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn receiver_iter_next_after_end() {
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel();
+        let (message, counter) = DropCounter::new(19u128);
+        sender.send(message).unwrap();
+        let mut iter = receiver.into_iter();
+        assert_eq!(iter.size_hint(), (0, Some(1)));
+        assert_eq!(*iter.next().unwrap().value(), 19);
+        assert_eq!(counter.count(), 1);
+        assert!(iter.next().is_none());
+        assert!(iter.next().is_none());
+        assert_eq!(iter.size_hint(), (0, Some(0)));
+        mem::drop(iter);
+        assert_eq!(counter.count(), 1);
+    })
+}
+
+#[cfg(all(feature = "std", not(loom)))]
+#[test]
+fn sender_is_receiver_waiting() {
+    let (sender, receiver) = oneshot::channel::<u128>();
+    assert!(!sender.is_receiver_waiting());
+    let t = thread::spawn(move || receiver.recv());
+    while !sender.is_receiver_waiting() {
+        thread::sleep(Duration::from_millis(1));
+    }
+    sender.send(19).unwrap();
+    assert_eq!(t.join().unwrap(), Ok(19));
+
+    // A receiver that only checks for a message is not waiting
+    let (sender, receiver) = oneshot::channel::<u128>();
+    assert_eq!(receiver.try_recv(), Err(TryRecvError::Empty));
+    assert!(!sender.is_receiver_waiting());
+    mem::drop(receiver);
+    assert!(!sender.is_receiver_waiting());
+}
+
+#[test]
+fn receiver_state() {
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        assert_eq!(receiver.state(), ChannelState::Empty);
+        assert_eq!(receiver.try_recv(), Err(TryRecvError::Empty));
+        assert_eq!(receiver.state(), ChannelState::Empty);
+        sender.send(19).unwrap();
+        assert_eq!(receiver.state(), ChannelState::MessageAvailable);
+        assert_eq!(receiver.try_recv(), Ok(19));
+        assert_eq!(receiver.state(), ChannelState::Consumed);
+        assert_eq!(receiver.try_recv(), Err(TryRecvError::Disconnected));
+        assert_eq!(receiver.state(), ChannelState::Consumed);
+    });
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        mem::drop(sender);
+        assert_eq!(receiver.state(), ChannelState::SenderDropped);
+        assert_eq!(receiver.try_recv(), Err(TryRecvError::Disconnected));
+        assert_eq!(receiver.state(), ChannelState::SenderDropped);
+    });
+}
+
+#[test]
+fn same_channel() {
+    maybe_loom_model(|| {
+        let (sender1, receiver1) = oneshot::channel::<u128>();
+        let (sender2, receiver2) = oneshot::channel::<u128>();
+        assert!(sender1.same_channel(&receiver1));
+        assert!(receiver1.same_channel(&sender1));
+        assert!(!sender1.same_channel(&receiver2));
+        assert!(!receiver2.same_channel(&sender1));
+
+        assert!(!sender2.same_channel(&receiver1));
+
+        // Still the same channel after receiving by reference and closing
+        receiver2.close();
+        assert_eq!(receiver2.try_recv(), Err(TryRecvError::Disconnected));
+        assert!(sender2.same_channel(&receiver2));
+        assert!(sender2.send(19).is_err());
+        mem::drop(sender1);
+    })
+}
+
+#[test]
+fn channel_id() {
+    maybe_loom_model(|| {
+        let (sender1, receiver1) = oneshot::channel::<u128>();
+        let (sender2, receiver2) = oneshot::channel::<u128>();
+        let id1 = sender1.channel_id();
+        let id2 = receiver2.channel_id();
+        assert_eq!(id1, receiver1.channel_id());
+        assert_eq!(id2, sender2.channel_id());
+        assert_ne!(id1, id2);
+
+        let ids: HashSet<ChannelId> = [id1, receiver1.channel_id(), id2].into_iter().collect();
+        assert_eq!(ids.len(), 2);
+        assert!(ids.contains(&sender2.channel_id()));
+
+        // The id stays the same after an endpoint is gone
+        sender1.send(19).unwrap();
+        assert_eq!(receiver1.channel_id(), id1);
+        mem::drop(receiver2);
+        assert_eq!(sender2.channel_id(), id2);
+
+        assert_eq!(
+            format!("{:?}", receiver1),
+            format!(
+                "Receiver {{ channel_id: {:?}, state: MessageAvailable }}",
+                id1
+            )
+        );
+    })
+}
+
+#[test]
+fn debug_shows_state() {
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        let id = sender.channel_id();
+        assert_eq!(
+            format!("{:?}", sender),
+            format!("Sender {{ channel_id: {:?}, closed: false }}", id)
+        );
+        assert_eq!(
+            format!("{:?}", receiver),
+            format!("Receiver {{ channel_id: {:?}, state: Empty }}", id)
+        );
+        receiver.close();
+        assert_eq!(
+            format!("{:?}", sender),
+            format!("Sender {{ channel_id: {:?}, closed: true }}", id)
+        );
+        assert_eq!(
+            format!("{:?}", receiver),
+            format!("Receiver {{ channel_id: {:?}, state: Closed }}", id)
+        );
+        mem::drop(sender);
+
+        let (sender, receiver) = oneshot::channel::<u128>();
+        sender.send(19).unwrap();
+        assert_eq!(receiver.try_recv(), Ok(19));
+        assert!(format!("{:?}", receiver).ends_with("state: Consumed }"));
+    })
+}
+
+#[test]
+fn into_raw_from_raw() {
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        let raw_sender = sender.into_raw();
+        let raw_receiver = receiver.into_raw();
+
+        let sender = unsafe { oneshot::Sender::<u128>::from_raw(raw_sender) };
+        let receiver = unsafe { oneshot::Receiver::<u128>::from_raw(raw_receiver) };
+        assert!(sender.same_channel(&receiver));
+        sender.send(19).unwrap();
+        assert_eq!(receiver.try_recv(), Ok(19));
+    });
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel();
+        let (message, counter) = DropCounter::new(());
+        sender.send(message).unwrap();
+        let raw_receiver = receiver.into_raw();
+        assert_eq!(counter.count(), 0);
+        mem::drop(unsafe { oneshot::Receiver::<DropCounter<()>>::from_raw(raw_receiver) });
+        assert_eq!(counter.count(), 1);
+    });
+}
+
+#[test]
+fn receiver_peek() {
+    maybe_loom_model(|| {
+        let (sender, mut receiver) = oneshot::channel::<u128>();
+        assert_eq!(receiver.peek(), None);
+        sender.send(19).unwrap();
+        assert_eq!(receiver.peek(), Some(&19));
+        assert_eq!(receiver.peek(), Some(&19));
+        assert_eq!(receiver.state(), ChannelState::MessageAvailable);
+        assert_eq!(receiver.try_recv(), Ok(19));
+        assert_eq!(receiver.peek(), None);
+        assert_eq!(receiver.try_recv(), Err(TryRecvError::Disconnected));
+    });
+    maybe_loom_model(|| {
+        let (sender, mut receiver) = oneshot::channel::<u128>();
+        mem::drop(sender);
+        assert_eq!(receiver.peek(), None);
+    });
+    maybe_loom_model(|| {
+        let (sender, mut receiver) = oneshot::channel::<u128>();
+        receiver.close();
+        assert!(sender.send(19).is_err());
+        assert_eq!(receiver.peek(), None);
+    });
+}
+
+#[test]
+fn receiver_peek_then_drop() {
+    maybe_loom_model(|| {
+        let (sender, mut receiver) = oneshot::channel();
+        let (message, counter) = DropCounter::new(());
+        sender.send(message).unwrap();
+        assert!(receiver.peek().is_some());
+        assert_eq!(counter.count(), 0);
+        mem::drop(receiver);
+        assert_eq!(counter.count(), 1);
+    })
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn receiver_wait_peek() {
+    maybe_loom_model(|| {
+        let (sender, mut receiver) = oneshot::channel::<u128>();
+        sender.send(19).unwrap();
+        assert_eq!(receiver.wait_peek(), Ok(&19));
+        assert_eq!(receiver.wait_peek(), Ok(&19));
+        assert_eq!(receiver.recv(), Ok(19));
+    });
+    maybe_loom_model(|| {
+        let (sender, mut receiver) = oneshot::channel::<u128>();
+        let t = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(2));
+            sender.send(19).unwrap();
+        });
+        assert_eq!(receiver.wait_peek(), Ok(&19));
+        assert_eq!(receiver.state(), ChannelState::MessageAvailable);
+        assert_eq!(receiver.try_recv(), Ok(19));
+        assert_eq!(receiver.wait_peek(), Err(RecvError::AlreadyReceived));
+        t.join().unwrap();
+    });
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn receiver_wait_peek_with_dropped_sender() {
+    maybe_loom_model(|| {
+        let (sender, mut receiver) = oneshot::channel::<u128>();
+        mem::drop(sender);
+        assert_eq!(receiver.wait_peek(), Err(RecvError::Disconnected));
+    });
+    maybe_loom_model(|| {
+        let (sender, mut receiver) = oneshot::channel::<u128>();
+        let t = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(2));
+            mem::drop(sender);
+        });
+        assert_eq!(receiver.wait_peek(), Err(RecvError::Disconnected));
+        assert_eq!(receiver.state(), ChannelState::SenderDropped);
+        t.join().unwrap();
+    });
+}
+
+#[test]
+fn receiver_close_before_send() {
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        receiver.close();
+        assert!(receiver.is_closed());
+        assert!(sender.is_closed());
+        assert_eq!(receiver.state(), ChannelState::Closed);
+        assert_eq!(sender.send(19).unwrap_err().into_inner(), 19);
+        assert_eq!(receiver.try_recv(), Err(TryRecvError::Disconnected));
+    });
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        receiver.close();
+        mem::drop(sender);
+        assert_eq!(receiver.try_recv(), Err(TryRecvError::Disconnected));
+        assert_eq!(receiver.state(), ChannelState::Closed);
+    });
+}
+
+#[test]
+fn receiver_close_after_send() {
+    maybe_loom_model(|| {
+        let (sender, mut receiver) = oneshot::channel();
+        let (message, counter) = DropCounter::new(19u128);
+        sender.send(message).unwrap();
+        receiver.close();
+        assert_eq!(counter.count(), 0);
+        assert_eq!(receiver.peek().map(|message| *message.value()), Some(19));
+        assert_eq!(*receiver.try_recv().unwrap().value(), 19);
+        assert_eq!(counter.count(), 1);
+        assert!(matches!(
+            receiver.try_recv(),
+            Err(TryRecvError::Disconnected)
+        ));
+    });
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel();
+        let (message, counter) = DropCounter::new(());
+        sender.send(message).unwrap();
+        receiver.close();
+        receiver.close();
+        mem::drop(receiver);
+        assert_eq!(counter.count(), 1);
+    });
+}
+
+#[test]
+fn receiver_close_then_drop_send_error() {
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel();
+        let (message, counter) = DropCounter::new(());
+        receiver.close();
+        let send_error = sender.send(message).unwrap_err();
+        mem::drop(receiver);
+        assert_eq!(counter.count(), 0);
+        mem::drop(send_error);
+        assert_eq!(counter.count(), 1);
+    });
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel();
+        let (message, counter) = DropCounter::new(());
+        receiver.close();
+        let send_error = sender.send(message).unwrap_err();
+        mem::drop(send_error);
+        assert_eq!(counter.count(), 1);
+        assert!(receiver.try_recv().is_err());
+    });
+}
+
+#[test]
+fn receiver_reset() {
+    maybe_loom_model(|| {
+        let (sender, mut receiver) = oneshot::channel::<u128>();
+        assert!(receiver.reset().is_none());
+        sender.send(19).unwrap();
+        // The message has not been received yet
+        assert!(receiver.reset().is_none());
+        assert_eq!(receiver.try_recv(), Ok(19));
+
+        let sender = receiver.reset().unwrap();
+        assert!(sender.same_channel(&receiver));
+        assert_eq!(receiver.state(), ChannelState::Empty);
+        assert_eq!(receiver.try_recv(), Err(TryRecvError::Empty));
+        sender.send(20).unwrap();
+        assert_eq!(receiver.try_recv(), Ok(20));
+    });
+    maybe_loom_model(|| {
+        let (sender, mut receiver) = oneshot::channel::<u128>();
+        mem::drop(sender);
+        let sender = receiver.reset().unwrap();
+        mem::drop(receiver);
+        // The sender frees the channel now
+        assert!(sender.send(19).is_err());
+    });
+}
+
+#[test]
+fn receiver_reset_after_close() {
+    maybe_loom_model(|| {
+        let (sender, mut receiver) = oneshot::channel::<u128>();
+        receiver.close();
+        assert!(receiver.reset().is_none());
+        mem::drop(sender);
+        let sender = receiver.reset().unwrap();
+        assert!(!sender.is_closed());
+        sender.send(19).unwrap();
+        assert_eq!(receiver.try_recv(), Ok(19));
+    });
+    maybe_loom_model(|| {
+        let (sender, mut receiver) = oneshot::channel();
+        let (message, counter) = DropCounter::new(());
+        receiver.close();
+        let send_error = sender.send(message).unwrap_err();
+        // The error still owns the message in the channel
+        assert!(receiver.reset().is_none());
+        mem::drop(send_error);
+        assert_eq!(counter.count(), 1);
+        let sender = receiver.reset().unwrap();
+        let (message, counter) = DropCounter::new(());
+        sender.send(message).unwrap();
+        mem::drop(receiver);
+        assert_eq!(counter.count(), 1);
+    });
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn recv_mut_drops_message_once() {
+    // Received message, then the receiver is dropped
+    maybe_loom_model(|| {
+        let (sender, mut receiver) = oneshot::channel();
+        let (message, counter) = DropCounter::new(());
+        sender.send(message).unwrap();
+        let message = receiver.recv_mut().unwrap();
+        mem::drop(receiver);
+        assert_eq!(counter.count(), 0);
+        mem::drop(message);
+        assert_eq!(counter.count(), 1);
+    });
+    // Message received while waiting
+    maybe_loom_model(|| {
+        let (sender, mut receiver) = oneshot::channel();
+        let (message, counter) = DropCounter::new(());
+        let t = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(2));
+            sender.send(message).unwrap();
+        });
+        mem::drop(receiver.recv_mut().unwrap());
+        assert_eq!(counter.count(), 1);
+        assert!(receiver.recv_mut().is_err());
+        mem::drop(receiver);
+        t.join().unwrap();
+        assert_eq!(counter.count(), 1);
+    });
+    // Timeout, then a message is received. Loom can't model a timeout.
+    #[cfg(not(loom))]
+    maybe_loom_model(|| {
+        let (sender, mut receiver) = oneshot::channel();
+        let (message, counter) = DropCounter::new(());
+        assert!(matches!(
+            receiver.recv_timeout(Duration::from_millis(1)),
+            Err(RecvTimeoutError::Timeout)
+        ));
+        sender.send(message).unwrap();
+        mem::drop(receiver.recv_mut().unwrap());
+        mem::drop(receiver);
+        assert_eq!(counter.count(), 1);
+    });
+}
+
+#[cfg(feature = "std")]
+#[test]
+#[allow(deprecated)]
+fn recv_ref_still_receives() {
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel();
+        sender.send(19u128).unwrap();
+        assert_eq!(receiver.recv_ref(), Ok(19));
+        assert_eq!(receiver.recv_ref(), Err(RecvError::AlreadyReceived));
+    })
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn receiver_close_then_recv() {
+    maybe_loom_model(|| {
+        let (sender, mut receiver) = oneshot::channel::<u128>();
+        receiver.close();
+        assert_eq!(receiver.recv_mut(), Err(RecvError::Disconnected));
+        assert_eq!(
+            receiver.recv_timeout(Duration::from_millis(1)),
+            Err(RecvTimeoutError::Disconnected)
+        );
+        assert_eq!(receiver.recv(), Err(RecvError::Disconnected));
+        assert!(sender.send(19).is_err());
+    })
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn receiver_state_recv_timeout() {
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        assert_eq!(
+            receiver.recv_timeout(Duration::from_millis(1)),
+            Err(RecvTimeoutError::Timeout)
+        );
+        assert_eq!(receiver.state(), ChannelState::Empty);
+        sender.send(19).unwrap();
+        assert_eq!(receiver.state(), ChannelState::MessageAvailable);
+        assert_eq!(receiver.recv_timeout(Duration::from_millis(1)), Ok(19));
+        assert_eq!(receiver.state(), ChannelState::Consumed);
+        assert_eq!(
+            receiver.recv_timeout(Duration::from_millis(1)),
+            Err(RecvTimeoutError::Disconnected)
+        );
+        assert_eq!(receiver.state(), ChannelState::Consumed);
+    });
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        mem::drop(sender);
+        assert_eq!(
+            receiver.recv_timeout(Duration::from_millis(1)),
+            Err(RecvTimeoutError::Disconnected)
+        );
+        assert_eq!(receiver.state(), ChannelState::SenderDropped);
+    });
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        let t = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(2));
+            sender.send(19).unwrap();
+        });
+        assert_eq!(receiver.recv_timeout(Duration::from_secs(1)), Ok(19));
+        assert_eq!(receiver.state(), ChannelState::Consumed);
+        t.join().unwrap();
+    });
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        let t = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(2));
+            mem::drop(sender);
+        });
+        assert_eq!(
+            receiver.recv_timeout(Duration::from_secs(1)),
+            Err(RecvTimeoutError::Disconnected)
+        );
+        assert_eq!(receiver.state(), ChannelState::SenderDropped);
+        t.join().unwrap();
+    });
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn receiver_state_recv_mut() {
+    maybe_loom_model(|| {
+        let (sender, mut receiver) = oneshot::channel::<u128>();
+        sender.send(19).unwrap();
+        assert_eq!(receiver.recv_mut(), Ok(19));
+        assert_eq!(receiver.state(), ChannelState::Consumed);
+        assert_eq!(receiver.recv_mut(), Err(RecvError::AlreadyReceived));
+        assert_eq!(receiver.state(), ChannelState::Consumed);
+    });
+    maybe_loom_model(|| {
+        let (sender, mut receiver) = oneshot::channel::<u128>();
+        mem::drop(sender);
+        assert_eq!(receiver.recv_mut(), Err(RecvError::Disconnected));
+        assert_eq!(receiver.state(), ChannelState::SenderDropped);
+    });
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn recv_flat() {
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<Result<u128, &str>>();
+        let t = thread::spawn(move || sender.send(Ok(19)).unwrap());
+        assert_eq!(receiver.recv_flat(), Ok(19));
+        t.join().unwrap();
+    });
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<Result<u128, &str>>();
+        let t = thread::spawn(move || sender.send(Err("failed")).unwrap());
+        assert_eq!(receiver.recv_flat(), Err(RecvOrError::App("failed")));
+        t.join().unwrap();
+    });
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<Result<u128, &str>>();
+        let t = thread::spawn(move || mem::drop(sender));
+        assert_eq!(receiver.recv_flat(), Err(RecvOrError::Disconnected));
+        t.join().unwrap();
+    });
+}
+
+#[cfg(feature = "std")]
+#[test]
+// The sending thread must unwind for the guard to see the panic
+#[cfg_attr(panic = "abort", ignore)]
+fn send_guard() {
+    use oneshot::SenderPanicked;
+
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<Result<u128, SenderPanicked>>();
+        let guard = sender.send_on_drop_guard();
+        assert!(!guard.is_closed());
+        let t = thread::spawn(move || guard.send(19).unwrap());
+        assert_eq!(receiver.recv_flat(), Ok(19));
+        t.join().unwrap();
+    });
+    // Dropping the guard on purpose disconnects like dropping a sender
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<Result<u128, SenderPanicked>>();
+        let guard = sender.send_on_drop_guard();
+        let t = thread::spawn(move || mem::drop(guard));
+        assert_eq!(receiver.recv_flat(), Err(RecvOrError::Disconnected));
+        t.join().unwrap();
+    });
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<Result<u128, SenderPanicked>>();
+        let guard = sender.send_on_drop_guard();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(move || {
+            let _guard = guard;
+            panic!("sender crashed");
+        }));
+        assert!(result.is_err());
+        assert_eq!(receiver.recv_flat(), Err(RecvOrError::App(SenderPanicked)));
+    });
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<Result<u128, SenderPanicked>>();
+        mem::drop(receiver);
+        let guard = sender.send_on_drop_guard();
+        assert!(guard.is_closed());
+        assert_eq!(guard.send(19).unwrap_err().into_inner(), Ok(19));
+    });
+}
+
+#[test]
+fn try_recv_flat() {
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<Result<u128, &str>>();
+        assert_eq!(receiver.try_recv_flat(), Ok(None));
+        sender.send(Ok(19)).unwrap();
+        assert_eq!(receiver.try_recv_flat(), Ok(Some(19)));
+        assert_eq!(receiver.try_recv_flat(), Err(RecvOrError::Disconnected));
+    });
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<Result<u128, &str>>();
+        sender.send(Err("failed")).unwrap();
+        assert_eq!(receiver.try_recv_flat(), Err(RecvOrError::App("failed")));
+    });
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<Result<u128, &str>>();
+        mem::drop(sender);
+        assert_eq!(receiver.try_recv_flat(), Err(RecvOrError::Disconnected));
+    });
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn recv_or_error_source() {
+    use std::error::Error;
+
+    let app_error = "x".parse::<u32>().unwrap_err();
+    let error = RecvOrError::App(app_error.clone());
+    let source = error.source().unwrap();
+    assert_eq!(source.to_string(), app_error.to_string());
+    assert!(RecvOrError::<std::num::ParseIntError>::Disconnected
+        .source()
+        .is_none());
+    assert_eq!(
+        RecvOrError::<std::num::ParseIntError>::from(RecvError::AlreadyReceived),
+        RecvOrError::Disconnected
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn blocking_receive() {
+    use oneshot::BlockingReceive;
+    use std::sync::mpsc;
+
+    fn receive_twice<R: BlockingReceive<u128>>(
+        receiver: &mut R,
+    ) -> (
+        Result<u128, mpsc::RecvError>,
+        Result<u128, mpsc::TryRecvError>,
+    ) {
+        (receiver.recv(), receiver.try_recv())
+    }
+
+    maybe_loom_model(|| {
+        let (sender, mut receiver) = oneshot::channel::<u128>();
+        assert_eq!(
+            BlockingReceive::try_recv(&mut receiver),
+            Err(mpsc::TryRecvError::Empty)
+        );
+        let t = thread::spawn(move || sender.send(19).unwrap());
+        assert_eq!(
+            receive_twice(&mut receiver),
+            (Ok(19), Err(mpsc::TryRecvError::Disconnected))
+        );
+        t.join().unwrap();
+    });
+    maybe_loom_model(|| {
+        let (sender, mut receiver) = oneshot::channel::<u128>();
+        mem::drop(sender);
+        assert_eq!(
+            receive_twice(&mut receiver),
+            (Err(mpsc::RecvError), Err(mpsc::TryRecvError::Disconnected))
+        );
+        assert_eq!(
+            BlockingReceive::recv_timeout(&mut receiver, Duration::from_millis(1)),
+            Err(mpsc::RecvTimeoutError::Disconnected)
+        );
+    });
+
+    let (sender, mut receiver) = mpsc::channel::<u128>();
+    sender.send(19).unwrap();
+    drop(sender);
+    assert_eq!(
+        receive_twice(&mut receiver),
+        (Ok(19), Err(mpsc::TryRecvError::Disconnected))
+    );
+}
+
+#[cfg(all(feature = "std", not(loom)))]
+#[test]
+fn blocking_receive_timeout() {
+    use oneshot::BlockingReceive;
+    use std::sync::mpsc;
+
+    let (_sender, mut receiver) = oneshot::channel::<u128>();
+    assert_eq!(
+        BlockingReceive::recv_timeout(&mut receiver, Duration::from_millis(1)),
+        Err(mpsc::RecvTimeoutError::Timeout)
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn forward_blocking_through_three_channels() {
+    maybe_loom_model(|| {
+        let (sender, receiver1) = oneshot::channel::<u128>();
+        let (sender2, receiver2) = oneshot::channel();
+        let (sender3, receiver3) = oneshot::channel();
+        let t1 = thread::spawn(move || receiver1.forward_blocking(sender2).unwrap());
+        let t2 = thread::spawn(move || receiver2.forward_blocking(sender3).unwrap());
+        sender.send(19).unwrap();
+        assert_eq!(receiver3.recv(), Ok(19));
+        t1.join().unwrap();
+        t2.join().unwrap();
+    });
+    maybe_loom_model(|| {
+        let (sender, receiver1) = oneshot::channel::<u128>();
+        let (sender2, receiver2) = oneshot::channel();
+        let (sender3, receiver3) = oneshot::channel();
+        let t = thread::spawn(move || receiver1.forward_blocking(sender2).unwrap());
+        mem::drop(sender);
+        receiver2.forward_blocking(sender3).unwrap();
+        assert_eq!(receiver3.recv(), Err(RecvError::Disconnected));
+        t.join().unwrap();
+    });
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn forward_blocking_to_dropped_receiver() {
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        let (dest, dest_receiver) = oneshot::channel();
+        mem::drop(dest_receiver);
+        sender.send(19).unwrap();
+        let error = receiver.forward_blocking(dest).unwrap_err();
+        assert_eq!(error.into_inner(), 19);
+    });
+}
+
+#[test]
+fn channel_pair_sends_to_both() {
+    maybe_loom_model(|| {
+        let (sender, receiver1, receiver2) = oneshot::channel_pair::<u128>();
+        assert!(!sender.is_closed());
+        sender.send(19).unwrap();
+        assert_eq!(receiver1.try_recv(), Ok(19));
+        assert_eq!(receiver2.try_recv(), Ok(19));
+    });
+}
+
+#[test]
+fn channel_pair_with_dropped_receivers() {
+    maybe_loom_model(|| {
+        let (sender, receiver1, receiver2) = oneshot::channel_pair::<u128>();
+        mem::drop(receiver1);
+        assert!(!sender.is_closed());
+        sender.send(19).unwrap();
+        assert_eq!(receiver2.try_recv(), Ok(19));
+    });
+    maybe_loom_model(|| {
+        let (sender, receiver1, receiver2) = oneshot::channel_pair::<u128>();
+        mem::drop(receiver2);
+        sender.send(19).unwrap();
+        assert_eq!(receiver1.try_recv(), Ok(19));
+    });
+    maybe_loom_model(|| {
+        let (sender, receiver1, receiver2) = oneshot::channel_pair::<u128>();
+        mem::drop(receiver1);
+        mem::drop(receiver2);
+        assert!(sender.is_closed());
+        assert_eq!(sender.send(19).unwrap_err().into_inner(), 19);
+    });
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn map_recv_runs_closure_on_receiver_side() {
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        let receiver_thread = thread::current().id();
+        let receiver = receiver.map(move |n| {
+            assert_eq!(thread::current().id(), receiver_thread);
+            n * 2
+        });
+        let t = thread::spawn(move || sender.send(19).unwrap());
+        assert_eq!(receiver.recv(), Ok(38));
+        t.join().unwrap();
+    });
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        let receiver = receiver.map(|_| -> u128 { panic!("Should not be called") });
+        let t = thread::spawn(move || mem::drop(sender));
+        assert_eq!(receiver.recv(), Err(RecvError::Disconnected));
+        t.join().unwrap();
+    });
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn map_drops_closure_on_disconnect() {
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        let (captured, counter) = DropCounter::new(());
+        let mut receiver = receiver.map(move |n| {
+            let _captured = &captured;
+            n
+        });
+        mem::drop(sender);
+        assert_eq!(
+            receiver.recv_timeout(Duration::from_millis(1)),
+            Err(RecvTimeoutError::Disconnected)
+        );
+        // Dropped right away, not only once the receiver goes away
+        assert_eq!(counter.count(), 1);
+    });
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        let (captured, counter) = DropCounter::new(());
+        let mut receiver = receiver.map(move |n| {
+            let _captured = &captured;
+            n
+        });
+        mem::drop(sender);
+        assert_eq!(receiver.try_recv(), Err(TryRecvError::Disconnected));
+        assert_eq!(counter.count(), 1);
+    });
+}
+
+#[cfg(all(feature = "std", not(loom)))]
+#[test]
+fn map_recv_timeout() {
+    let (sender, receiver) = oneshot::channel::<u128>();
+    let mut receiver = receiver.map(|n| n * 2);
+    assert_eq!(
+        receiver.recv_timeout(Duration::from_millis(1)),
+        Err(RecvTimeoutError::Timeout)
+    );
+    let t = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(2));
+        sender.send(19).unwrap();
+    });
+    assert_eq!(receiver.recv_timeout(Duration::from_secs(1)), Ok(38));
+    t.join().unwrap();
+}
+
+#[test]
+fn send_status_buffered() {
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        assert_eq!(sender.send_status(19).unwrap(), SendStatus::Buffered);
+        assert_eq!(receiver.try_recv(), Ok(19));
+    });
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        mem::drop(receiver);
+        assert_eq!(sender.send_status(19).unwrap_err().into_inner(), 19);
+    });
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn send_status_while_receiving() {
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        let t = thread::spawn(move || receiver.recv());
+        // Depending on whether the receiver was already waiting, either status is fine. The
+        // message must arrive in both cases.
+        let status = sender.send_status(19).unwrap();
+        assert!(matches!(status, SendStatus::Buffered | SendStatus::Woke));
+        assert_eq!(t.join().unwrap(), Ok(19));
+    });
+}
+
+#[cfg(all(feature = "std", not(loom)))]
+#[test]
+fn send_status_woke() {
+    let (sender, receiver) = oneshot::channel::<u128>();
+    let t = thread::spawn(move || receiver.recv());
+    while !sender.is_receiver_waiting() {
+        thread::sleep(Duration::from_millis(1));
+    }
+    assert_eq!(sender.send_status(19).unwrap(), SendStatus::Woke);
+    assert_eq!(t.join().unwrap(), Ok(19));
+}
+
+#[cfg(all(feature = "std", debug_assertions, not(loom)))]
+#[test]
+fn pre_park_hook_delays_registering() {
+    use oneshot::test_hooks::set_pre_park_hook;
+
+    // The sender acts while the receiver is held up between seeing the empty channel and
+    // storing its waker.
+    set_pre_park_hook(Some(|| thread::sleep(Duration::from_millis(10))));
+
+    let (sender, receiver) = oneshot::channel::<u128>();
+    let t = thread::spawn(move || sender.send(19).unwrap());
+    assert_eq!(receiver.recv(), Ok(19));
+    t.join().unwrap();
+
+    let (sender, mut receiver) = oneshot::channel::<u128>();
+    let t = thread::spawn(move || mem::drop(sender));
+    assert_eq!(receiver.recv_mut(), Err(RecvError::Disconnected));
+    t.join().unwrap();
+
+    let (sender, receiver) = oneshot::channel::<u128>();
+    let t = thread::spawn(move || sender.send(19).unwrap());
+    assert_eq!(receiver.recv_timeout(Duration::from_secs(1)), Ok(19));
+    t.join().unwrap();
+
+    set_pre_park_hook(None);
+}
+
+#[cfg(all(feature = "std", debug_assertions, not(loom)))]
+#[test]
+fn pre_park_hook_acts_before_registering() {
+    use oneshot::test_hooks::set_pre_park_hook;
+    use oneshot::Sender;
+    use std::cell::RefCell;
+
+    std::thread_local! {
+        static SENDER: RefCell<Option<Sender<u128>>> = const { RefCell::new(None) };
+    }
+
+    // The sender deterministically acts right before the receiver stores its waker.
+    let (sender, receiver) = oneshot::channel::<u128>();
+    SENDER.with(|slot| *slot.borrow_mut() = Some(sender));
+    set_pre_park_hook(Some(|| {
+        if let Some(sender) = SENDER.with(|slot| slot.borrow_mut().take()) {
+            sender.send(19).unwrap();
+        }
+    }));
+    assert_eq!(receiver.recv(), Ok(19));
+
+    let (sender, mut receiver) = oneshot::channel::<u128>();
+    SENDER.with(|slot| *slot.borrow_mut() = Some(sender));
+    assert_eq!(receiver.wait_peek(), Ok(&19));
+    assert_eq!(receiver.recv_mut(), Ok(19));
+
+    let (sender, receiver) = oneshot::channel::<u128>();
+    SENDER.with(|slot| *slot.borrow_mut() = Some(sender));
+    set_pre_park_hook(Some(|| {
+        SENDER.with(|slot| mem::drop(slot.borrow_mut().take()));
+    }));
+    assert_eq!(
+        receiver.recv_deadline(Instant::now() + Duration::from_secs(1)),
+        Err(RecvTimeoutError::Disconnected)
+    );
+
+    set_pre_park_hook(None);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn recv_try_then_deadline() {
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        sender.send(19).unwrap();
+        let deadline = Instant::now() + Duration::from_secs(1);
+        assert_eq!(receiver.recv_try_then_deadline(10, deadline), Ok(19));
+        assert_eq!(
+            receiver.recv_try_then_deadline(10, deadline),
+            Err(RecvTimeoutError::Disconnected)
+        );
+    });
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        let t = thread::spawn(move || sender.send(19).unwrap());
+        let deadline = Instant::now() + Duration::from_secs(1);
+        assert_eq!(receiver.recv_try_then_deadline(10, deadline), Ok(19));
+        t.join().unwrap();
+    });
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        let t = thread::spawn(move || mem::drop(sender));
+        let deadline = Instant::now() + Duration::from_secs(1);
+        assert_eq!(
+            receiver.recv_try_then_deadline(0, deadline),
+            Err(RecvTimeoutError::Disconnected)
+        );
+        t.join().unwrap();
+    });
+}
+
+#[cfg(all(feature = "std", not(loom)))]
+#[test]
+fn recv_try_then_deadline_times_out() {
+    let (sender, receiver) = oneshot::channel::<u128>();
+    let start = Instant::now();
+    let timeout = Duration::from_millis(50);
+    assert_eq!(
+        receiver.recv_try_then_deadline(1000, start + timeout),
+        Err(RecvTimeoutError::Timeout)
+    );
+    assert!(start.elapsed() >= timeout);
+
+    // The channel is left usable after the timeout
+    sender.send(19).unwrap();
+    assert_eq!(receiver.recv_try_then_deadline(0, Instant::now()), Ok(19));
+}
+
+#[test]
+fn on_undelivered_gets_unreceived_message() {
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        let lost = core::cell::Cell::new(None);
+        let receiver = receiver.on_undelivered(|n| lost.set(Some(n)));
+        sender.send(19).unwrap();
+        mem::drop(receiver);
+        assert_eq!(lost.get(), Some(19));
+    });
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        let mut receiver = receiver.on_undelivered(|_| panic!("Should not be called"));
+        sender.send(19).unwrap();
+        assert_eq!(receiver.try_recv(), Ok(19));
+    });
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        let receiver = receiver.on_undelivered(|_| panic!("Should not be called"));
+        mem::drop(sender);
+        mem::drop(receiver);
+    });
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        let receiver = receiver
+            .on_undelivered(|_| panic!("Should not be called"))
+            .into_inner();
+        sender.send(19).unwrap();
+        mem::drop(receiver);
+    });
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn on_undelivered_races_send() {
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        let lost = Arc::new(AtomicBool::new(false));
+        let hook_lost = lost.clone();
+        let receiver = receiver.on_undelivered(move |n| {
+            assert_eq!(n, 19);
+            hook_lost.store(true, Ordering::SeqCst);
+        });
+        let t = thread::spawn(move || sender.send(19).is_ok());
+        mem::drop(receiver);
+        let sent = t.join().unwrap();
+        // The message either went back to the sender or to the hook, never dropped silently.
+        assert_eq!(sent, lost.load(Ordering::SeqCst));
+    });
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn on_undelivered_recv() {
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        let receiver = receiver.on_undelivered(|_| panic!("Should not be called"));
+        let t = thread::spawn(move || sender.send(19).unwrap());
+        assert_eq!(receiver.recv(), Ok(19));
+        t.join().unwrap();
+    });
+}
+
+#[cfg(all(feature = "std", feature = "async"))]
+#[test]
+fn park_with() {
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        let woken = Arc::new(AtomicBool::new(false));
+        let callback_woken = woken.clone();
+        receiver.park_with(move || callback_woken.store(true, Ordering::SeqCst));
+        let t = thread::spawn(move || sender.send(19).unwrap());
+        t.join().unwrap();
+        assert!(woken.load(Ordering::SeqCst));
+        assert_eq!(receiver.try_recv(), Ok(19));
+    });
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        let woken = Arc::new(AtomicBool::new(false));
+        let callback_woken = woken.clone();
+        receiver.park_with(move || callback_woken.store(true, Ordering::SeqCst));
+        let t = thread::spawn(move || mem::drop(sender));
+        t.join().unwrap();
+        assert!(woken.load(Ordering::SeqCst));
+        assert_eq!(receiver.try_recv(), Err(TryRecvError::Disconnected));
+    });
+}
+
+#[cfg(all(feature = "std", feature = "async"))]
+#[test]
+fn park_with_on_done_channel_calls_right_away() {
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        sender.send(19).unwrap();
+        let woken = Arc::new(AtomicBool::new(false));
+        let callback_woken = woken.clone();
+        receiver.park_with(move || callback_woken.store(true, Ordering::SeqCst));
+        assert!(woken.load(Ordering::SeqCst));
+        assert_eq!(receiver.try_recv(), Ok(19));
+    });
+}
+
+#[cfg(all(feature = "std", feature = "async"))]
+#[test]
+fn park_with_replaces_earlier_callback() {
+    maybe_loom_model(|| {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        let (captured, counter) = DropCounter::new(());
+        receiver.park_with(move || {
+            let _captured = captured;
+            panic!("Should not be called");
+        });
+        assert_eq!(counter.count(), 0);
+        let woken = Arc::new(AtomicBool::new(false));
+        let callback_woken = woken.clone();
+        receiver.park_with(move || callback_woken.store(true, Ordering::SeqCst));
+        // The first callback was dropped without being called
+        assert_eq!(counter.count(), 1);
+        sender.send(19).unwrap();
+        assert!(woken.load(Ordering::SeqCst));
+        assert_eq!(receiver.recv_timeout(Duration::from_secs(1)), Ok(19));
+    });
+}
+
+#[cfg(all(feature = "std", not(loom)))]
+#[test]
+fn send_borrowed_message() {
+    let data = [19u128, 20];
+    let (sender, receiver) = oneshot::channel::<&u128>();
+    sender.send(&data[1]).unwrap();
+    assert_eq!(receiver.recv(), Ok(&20));
+
+    // A message that was never received is dropped with the receiver, while still borrowing
+    let (message, counter) = DropCounter::new(&data[0]);
+    let (sender, receiver) = oneshot::channel();
+    sender.send(message).unwrap();
+    mem::drop(receiver);
+    assert_eq!(counter.count(), 1);
+
+    // Borrows of static data can be sent to another thread
+    static DATA: [u128; 2] = [19, 20];
+    let (sender, receiver) = oneshot::channel::<&'static u128>();
+    let t = thread::spawn(move || sender.send(&DATA[1]).unwrap());
+    assert_eq!(receiver.recv(), Ok(&20));
+    t.join().unwrap();
+}
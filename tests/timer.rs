@@ -0,0 +1,307 @@
+#![cfg(all(feature = "async", not(loom)))]
+
+use core::cell::Cell;
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{self, Poll};
+use core::time::Duration;
+use oneshot::timer::AsyncTimer;
+use oneshot::RecvTimeoutError;
+
+mod helpers;
+
+thread_local! {
+    /// Whether the sleeps of `MockTimer` on this thread have completed.
+    static FIRED: Cell<bool> = const { Cell::new(false) };
+    /// The duration of the last sleep created on this thread.
+    static SLEEP_DURATION: Cell<Option<Duration>> = const { Cell::new(None) };
+}
+
+/// A timer firing when the test says so, instead of after some time.
+enum MockTimer {}
+
+struct MockSleep;
+
+impl AsyncTimer for MockTimer {
+    type Sleep = MockSleep;
+
+    fn sleep(duration: Duration) -> Self::Sleep {
+        SLEEP_DURATION.with(|d| d.set(Some(duration)));
+        MockSleep
+    }
+}
+
+impl Future for MockSleep {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, _cx: &mut task::Context<'_>) -> Poll<()> {
+        if FIRED.with(Cell::get) {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+fn poll<F: Future + Unpin>(future: &mut F) -> Poll<F::Output> {
+    let waker = helpers::waker::noop_waker();
+    let mut context = task::Context::from_waker(&waker);
+    Pin::new(future).poll(&mut context)
+}
+
+fn reset() {
+    FIRED.with(|f| f.set(false));
+    SLEEP_DURATION.with(|d| d.set(None));
+}
+
+#[test]
+fn message_before_timeout() {
+    reset();
+    let (sender, receiver) = oneshot::channel::<u128>();
+    let mut future = receiver.recv_timeout_with::<MockTimer>(Duration::from_secs(5));
+    // The sleep is only created when polled
+    assert_eq!(SLEEP_DURATION.with(Cell::get), None);
+    assert_eq!(poll(&mut future), Poll::Pending);
+    assert_eq!(SLEEP_DURATION.with(Cell::get), Some(Duration::from_secs(5)));
+    sender.send(19).unwrap();
+    assert_eq!(poll(&mut future), Poll::Ready(Ok(19)));
+}
+
+#[test]
+fn timeout_fires() {
+    reset();
+    let (_sender, receiver) = oneshot::channel::<u128>();
+    let mut future = receiver.recv_timeout_with::<MockTimer>(Duration::from_secs(5));
+    assert_eq!(poll(&mut future), Poll::Pending);
+    FIRED.with(|f| f.set(true));
+    assert_eq!(
+        poll(&mut future),
+        Poll::Ready(Err(RecvTimeoutError::Timeout))
+    );
+    // The receiver can still be used after the timeout
+    assert!(!future.into_inner().is_closed());
+}
+
+#[test]
+fn message_wins_over_timeout_in_same_poll() {
+    reset();
+    let (sender, receiver) = oneshot::channel::<u128>();
+    let mut future = receiver.recv_timeout_with::<MockTimer>(Duration::from_secs(5));
+    assert_eq!(poll(&mut future), Poll::Pending);
+    FIRED.with(|f| f.set(true));
+    sender.send(19).unwrap();
+    assert_eq!(poll(&mut future), Poll::Ready(Ok(19)));
+}
+
+#[test]
+fn sender_dropped() {
+    reset();
+    let (sender, receiver) = oneshot::channel::<u128>();
+    let mut future = receiver.recv_timeout_with::<MockTimer>(Duration::from_secs(5));
+    assert_eq!(poll(&mut future), Poll::Pending);
+    drop(sender);
+    assert_eq!(
+        poll(&mut future),
+        Poll::Ready(Err(RecvTimeoutError::Disconnected))
+    );
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn tokio_timer() {
+    use oneshot::timer::Tokio;
+
+    let (sender, receiver) = oneshot::channel::<u128>();
+    let result = receiver
+        .recv_timeout_with::<Tokio>(Duration::from_millis(10))
+        .await;
+    assert_eq!(result, Err(RecvTimeoutError::Timeout));
+    drop(sender);
+
+    let (sender, receiver) = oneshot::channel::<u128>();
+    let t = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        sender.send(19).unwrap();
+    });
+    let result = receiver
+        .recv_timeout_with::<Tokio>(Duration::from_secs(5))
+        .await;
+    assert_eq!(result, Ok(19));
+    t.await.unwrap();
+}
+
+#[cfg(feature = "async-std")]
+#[async_std::test]
+async fn async_std_timer() {
+    use oneshot::timer::AsyncStd;
+
+    let (sender, receiver) = oneshot::channel::<u128>();
+    let result = receiver
+        .recv_timeout_with::<AsyncStd>(Duration::from_millis(10))
+        .await;
+    assert_eq!(result, Err(RecvTimeoutError::Timeout));
+    drop(sender);
+
+    let (sender, receiver) = oneshot::channel::<u128>();
+    let t = async_std::task::spawn(async move {
+        async_std::task::sleep(Duration::from_millis(10)).await;
+        sender.send(19).unwrap();
+    });
+    let result = receiver
+        .recv_timeout_with::<AsyncStd>(Duration::from_secs(5))
+        .await;
+    assert_eq!(result, Ok(19));
+    t.await;
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn deadline_receiver_sleeps_until_deadline() {
+    use std::time::Instant;
+
+    reset();
+    let (_sender, receiver) = oneshot::channel::<u128>();
+    let timeout = Duration::from_secs(60);
+    let receiver = receiver.with_deadline(Instant::now() + timeout);
+    let mut future = receiver.recv_with::<MockTimer>();
+    assert_eq!(SLEEP_DURATION.with(Cell::get), None);
+    assert_eq!(poll(&mut future), Poll::Pending);
+    // The sleep covers the time left until the deadline
+    let duration = SLEEP_DURATION.with(Cell::get).unwrap();
+    assert!(duration <= timeout && duration > timeout - Duration::from_secs(10));
+    FIRED.with(|f| f.set(true));
+    assert_eq!(
+        poll(&mut future),
+        Poll::Ready(Err(RecvTimeoutError::Timeout))
+    );
+}
+
+#[cfg(all(feature = "std", feature = "tokio"))]
+#[tokio::test]
+async fn tokio_deadline_receiver_wakes_at_deadline() {
+    use oneshot::timer::Tokio;
+    use std::time::Instant;
+
+    let (_sender, receiver) = oneshot::channel::<u128>();
+    let start = Instant::now();
+    let timeout = Duration::from_millis(100);
+    let receiver = receiver.with_deadline(start + timeout);
+    assert_eq!(
+        receiver.recv_with::<Tokio>().await,
+        Err(RecvTimeoutError::Timeout)
+    );
+    assert!(start.elapsed() >= timeout);
+    assert!(start.elapsed() < timeout * 3);
+
+    let (sender, receiver) = oneshot::channel::<u128>();
+    let receiver = receiver.with_deadline(Instant::now() + Duration::from_secs(5));
+    let t = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        sender.send(19).unwrap();
+    });
+    assert_eq!(receiver.recv_with::<Tokio>().await, Ok(19));
+    t.await.unwrap();
+}
+
+#[cfg(all(feature = "std", feature = "async-std"))]
+#[async_std::test]
+async fn async_std_deadline_receiver_wakes_at_deadline() {
+    use oneshot::timer::AsyncStd;
+    use std::time::Instant;
+
+    let (_sender, receiver) = oneshot::channel::<u128>();
+    let start = Instant::now();
+    let timeout = Duration::from_millis(100);
+    let receiver = receiver.with_deadline(start + timeout);
+    assert_eq!(
+        receiver.recv_with::<AsyncStd>().await,
+        Err(RecvTimeoutError::Timeout)
+    );
+    assert!(start.elapsed() >= timeout);
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test(start_paused = true)]
+async fn tokio_recv_with_timeout_paused() {
+    use tokio::time::Instant;
+
+    let (_sender, receiver) = oneshot::channel::<u128>();
+    let start = Instant::now();
+    let result = receiver.recv_with_timeout(Duration::from_secs(60)).await;
+    assert_eq!(result, Err(RecvTimeoutError::Timeout));
+    // The paused clock jumps straight to the deadline
+    assert_eq!(start.elapsed(), Duration::from_secs(60));
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test(start_paused = true)]
+async fn tokio_recv_timeout_async_paused() {
+    use tokio::time::Instant;
+
+    let (sender, receiver) = oneshot::channel::<u128>();
+    let start = Instant::now();
+    let result = receiver.recv_timeout_async(Duration::from_secs(60)).await;
+    assert_eq!(result, Err(RecvTimeoutError::Timeout));
+    assert_eq!(start.elapsed(), Duration::from_secs(60));
+    drop(sender);
+
+    let (sender, receiver) = oneshot::channel::<u128>();
+    let t = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_secs(10)).await;
+        sender.send(19).unwrap();
+    });
+    let result = receiver.recv_timeout_async(Duration::from_secs(60)).await;
+    assert_eq!(result, Ok(19));
+    t.await.unwrap();
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test(start_paused = true)]
+async fn tokio_recv_with_deadline_message_first() {
+    use tokio::time::Instant;
+
+    let (sender, receiver) = oneshot::channel::<u128>();
+    let start = Instant::now();
+    let t = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_secs(10)).await;
+        sender.send(19).unwrap();
+    });
+    let result = receiver
+        .recv_with_deadline(start + Duration::from_secs(60))
+        .await;
+    assert_eq!(result, Ok(19));
+    assert_eq!(start.elapsed(), Duration::from_secs(10));
+    t.await.unwrap();
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test(start_paused = true)]
+async fn tokio_recv_with_deadline_passed() {
+    use tokio::time::Instant;
+
+    // A message already in the channel wins over a deadline that has passed
+    let (sender, receiver) = oneshot::channel::<u128>();
+    sender.send(19).unwrap();
+    assert_eq!(receiver.recv_with_deadline(Instant::now()).await, Ok(19));
+
+    let (sender, receiver) = oneshot::channel::<u128>();
+    drop(sender);
+    assert_eq!(
+        receiver.recv_with_deadline(Instant::now()).await,
+        Err(RecvTimeoutError::Disconnected)
+    );
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test(start_paused = true)]
+async fn tokio_recv_with_timeout_advance() {
+    let (sender, receiver) = oneshot::channel::<u128>();
+    let future = receiver.recv_with_timeout(Duration::from_secs(5));
+    tokio::pin!(future);
+    assert!(poll(&mut future.as_mut()).is_pending());
+    tokio::time::advance(Duration::from_secs(4)).await;
+    assert!(poll(&mut future.as_mut()).is_pending());
+    // Polling again after Pending keeps the message
+    sender.send(19).unwrap();
+    assert_eq!(future.await, Ok(19));
+}
@@ -0,0 +1,125 @@
+#![cfg(all(feature = "tracing", feature = "std", not(loom)))]
+
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+/// The message and the `woke` field of an event.
+type Recorded = (String, Option<bool>);
+
+/// Collects every event.
+#[derive(Clone, Default)]
+struct Collector(Arc<Mutex<Vec<Recorded>>>);
+
+impl Collector {
+    fn events(&self) -> Vec<Recorded> {
+        self.0.lock().unwrap().clone()
+    }
+}
+
+#[derive(Default)]
+struct EventVisitor {
+    message: String,
+    woke: Option<bool>,
+}
+
+impl Visit for EventVisitor {
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        if field.name() == "woke" {
+            self.woke = Some(value);
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{:?}", value);
+        }
+    }
+}
+
+impl Subscriber for Collector {
+    fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, _span: &Attributes<'_>) -> Id {
+        Id::from_u64(1)
+    }
+
+    fn record(&self, _span: &Id, _values: &Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut visitor = EventVisitor::default();
+        event.record(&mut visitor);
+        self.0.lock().unwrap().push((visitor.message, visitor.woke));
+    }
+
+    fn enter(&self, _span: &Id) {}
+
+    fn exit(&self, _span: &Id) {}
+}
+
+fn event(message: &str, woke: Option<bool>) -> Recorded {
+    (message.to_owned(), woke)
+}
+
+#[test]
+fn send_before_recv() {
+    let collector = Collector::default();
+    tracing::subscriber::with_default(collector.clone(), || {
+        let (sender, receiver) = oneshot::channel();
+        sender.send(19u128).unwrap();
+        assert_eq!(receiver.recv(), Ok(19));
+    });
+    assert_eq!(collector.events(), [event("message sent", Some(false))]);
+}
+
+#[test]
+fn recv_before_send() {
+    let collector = Collector::default();
+    let (sender, receiver) = oneshot::channel();
+    let t = thread::spawn({
+        let collector = collector.clone();
+        move || {
+            tracing::subscriber::with_default(collector, || {
+                thread::sleep(Duration::from_millis(10));
+                sender.send(19u128).unwrap();
+            })
+        }
+    });
+    let message = tracing::subscriber::with_default(collector.clone(), || receiver.recv());
+    assert_eq!(message, Ok(19));
+    t.join().unwrap();
+
+    let events = collector.events();
+    assert_eq!(events.first(), Some(&event("receiver parked", None)));
+    assert!(events.contains(&event("message sent", Some(true))));
+}
+
+#[test]
+fn drop_sender_and_receiver() {
+    let collector = Collector::default();
+    tracing::subscriber::with_default(collector.clone(), || {
+        let (sender, receiver) = oneshot::channel::<u128>();
+        drop(receiver);
+        assert!(sender.send(19).is_err());
+
+        let (sender, receiver) = oneshot::channel::<u128>();
+        drop(sender);
+        drop(receiver);
+    });
+    assert_eq!(
+        collector.events(),
+        [
+            event("receiver dropped", None),
+            event("send failed, the receiver is dropped", None),
+            event("sender dropped", Some(false)),
+            event("receiver dropped", None),
+        ]
+    );
+}
@@ -0,0 +1,100 @@
+#![cfg(target_arch = "wasm32")]
+
+use wasm_bindgen_test::wasm_bindgen_test;
+
+#[cfg(feature = "async")]
+#[wasm_bindgen_test]
+async fn send_before_await() {
+    let (sender, receiver) = oneshot::channel::<u128>();
+    sender.send(19).unwrap();
+    assert_eq!(receiver.await, Ok(19));
+}
+
+#[cfg(feature = "async")]
+#[wasm_bindgen_test]
+async fn await_before_send() {
+    let (sender, receiver) = oneshot::channel::<u128>();
+    wasm_bindgen_futures::spawn_local(async move {
+        assert!(sender.is_receiver_waiting());
+        sender.send(19).unwrap();
+    });
+    assert_eq!(receiver.await, Ok(19));
+}
+
+#[cfg(feature = "async")]
+#[wasm_bindgen_test]
+async fn await_before_sender_dropped() {
+    let (sender, receiver) = oneshot::channel::<u128>();
+    wasm_bindgen_futures::spawn_local(async move { drop(sender) });
+    assert_eq!(receiver.await, Err(oneshot::RecvError::Disconnected));
+}
+
+#[wasm_bindgen_test]
+fn try_recv() {
+    let (sender, receiver) = oneshot::channel::<u128>();
+    assert_eq!(receiver.try_recv(), Err(oneshot::TryRecvError::Empty));
+    sender.send(19).unwrap();
+    assert_eq!(receiver.try_recv(), Ok(19));
+}
+
+#[cfg(feature = "std")]
+#[wasm_bindgen_test]
+fn recv_without_waiting() {
+    let (sender, receiver) = oneshot::channel::<u128>();
+    sender.send(19).unwrap();
+    assert_eq!(receiver.recv(), Ok(19));
+
+    let (sender, receiver) = oneshot::channel::<u128>();
+    drop(sender);
+    assert_eq!(receiver.recv(), Err(oneshot::RecvError::Disconnected));
+}
+
+#[cfg(feature = "std")]
+#[wasm_bindgen_test]
+fn wait_all_without_waiting() {
+    let (senders, receivers): (Vec<_>, Vec<_>) = (0..3).map(|_| oneshot::channel()).unzip();
+    let mut senders = senders.into_iter();
+    drop(senders.next());
+    for sender in senders {
+        sender.send(19u128).unwrap();
+    }
+    assert_eq!(
+        oneshot::wait_all(receivers),
+        [Err(oneshot::RecvError::Disconnected), Ok(19), Ok(19)]
+    );
+}
+
+#[cfg(feature = "std")]
+#[wasm_bindgen_test]
+#[should_panic(expected = "Invalid to block on a oneshot channel on a target without threads")]
+fn recv_that_would_block_panics() {
+    let (_sender, receiver) = oneshot::channel::<u128>();
+    let _ = receiver.recv();
+}
+
+#[cfg(feature = "wasm-bindgen")]
+mod promise {
+    use wasm_bindgen::JsValue;
+    use wasm_bindgen_futures::JsFuture;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    #[wasm_bindgen_test]
+    async fn promise_resolves_with_message() {
+        let (sender, receiver) = oneshot::channel::<u32>();
+        let promise = receiver.into_js_promise();
+        sender.send(19).unwrap();
+        assert_eq!(JsFuture::from(promise).await.unwrap(), JsValue::from(19));
+    }
+
+    #[wasm_bindgen_test]
+    async fn promise_rejects_on_disconnect() {
+        let (sender, receiver) = oneshot::channel::<u32>();
+        let promise = receiver.into_js_promise();
+        drop(sender);
+        let error = js_sys::Error::from(JsFuture::from(promise).await.unwrap_err());
+        assert_eq!(
+            String::from(error.message()),
+            "receiving on a closed channel"
+        );
+    }
+}